@@ -0,0 +1,158 @@
+#ifndef SODO_H
+#define SODO_H
+
+/* Generated by cbindgen from sodo-ffi. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result code of every fallible call.
+ */
+typedef enum SodoStatus {
+  SODO_STATUS_OK = 0,
+  /**
+   * A required pointer argument was null.
+   */
+  SODO_STATUS_NULL_POINTER = 1,
+  /**
+   * A string argument was not valid UTF-8.
+   */
+  SODO_STATUS_INVALID_UTF8 = 2,
+  /**
+   * The grid size has no box shape or is larger than 64.
+   */
+  SODO_STATUS_INVALID_SIZE = 3,
+  /**
+   * The puzzle string could not be parsed or breaks the rules.
+   */
+  SODO_STATUS_INVALID_PUZZLE = 4,
+  /**
+   * The puzzle has no solution.
+   */
+  SODO_STATUS_NO_SOLUTION = 5,
+  /**
+   * No logical move was found.
+   */
+  SODO_STATUS_NO_HINT = 6,
+  /**
+   * Generation failed.
+   */
+  SODO_STATUS_GENERATE_FAILED = 7,
+  /**
+   * The library panicked; [`sodo_last_error`] holds the message.
+   */
+  SODO_STATUS_INTERNAL = 8,
+} SodoStatus;
+
+/**
+ * Puzzle difficulty level.
+ */
+typedef enum SodoDifficulty {
+  SODO_DIFFICULTY_EASY = 0,
+  SODO_DIFFICULTY_MEDIUM = 1,
+  SODO_DIFFICULTY_HARD = 2,
+  SODO_DIFFICULTY_EXPERT = 3,
+} SodoDifficulty;
+
+/**
+ * A suggested placement. Row and column are zero-based.
+ */
+typedef struct SodoHint {
+  size_t row;
+  size_t col;
+  uint8_t value;
+} SodoHint;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the message for the last error on this thread, or an empty string.
+ *
+ * The pointer stays valid until the next failing call on the same thread.
+ */
+const char *sodo_last_error(void);
+
+/**
+ * Returns the library version as a static string.
+ */
+const char *sodo_version(void);
+
+/**
+ * Solves `puzzle` and stores the compact solution in `*out`.
+ *
+ * # Safety
+ *
+ * `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+ * Release the returned string with [`sodo_string_free`].
+ */
+enum SodoStatus sodo_solve(const char *puzzle, size_t size, char **out);
+
+/**
+ * Generates a puzzle and stores it as a compact string in `*out`.
+ *
+ * # Safety
+ *
+ * `out` must be a valid pointer. Release the returned string with
+ * [`sodo_string_free`].
+ */
+enum SodoStatus sodo_generate(size_t size, enum SodoDifficulty difficulty, char **out);
+
+/**
+ * Rates `puzzle` and stores its difficulty in `*out`.
+ *
+ * # Safety
+ *
+ * `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+ */
+enum SodoStatus sodo_rate(const char *puzzle, size_t size, enum SodoDifficulty *out);
+
+/**
+ * Finds the next logical placement and stores it in `*out`.
+ *
+ * Returns [`SodoStatus::NoHint`] when no single can be found.
+ *
+ * # Safety
+ *
+ * `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+ */
+enum SodoStatus sodo_hint(const char *puzzle, size_t size, struct SodoHint *out);
+
+/**
+ * Checks `puzzle` for repeated digits and stores the result in `*out`.
+ *
+ * # Safety
+ *
+ * `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+ */
+enum SodoStatus sodo_validate(const char *puzzle, size_t size, bool *out);
+
+/**
+ * Counts solutions of `puzzle`, stopping at `limit`, into `*out`.
+ *
+ * # Safety
+ *
+ * `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+ */
+enum SodoStatus sodo_count_solutions(const char *puzzle, size_t size, size_t limit, size_t *out);
+
+/**
+ * Releases a string returned by this library. Null is ignored.
+ *
+ * # Safety
+ *
+ * `s` must be null or a pointer obtained from this library that has not been
+ * freed yet.
+ */
+void sodo_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SODO_H */
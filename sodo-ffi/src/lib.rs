@@ -0,0 +1,322 @@
+//! Stable C ABI for the sodo Sudoku library.
+//!
+//! Puzzles cross the boundary as NUL-terminated compact strings (`.` or `0`
+//! for empty cells). Every fallible call returns a [`SodoStatus`]; on failure
+//! [`sodo_last_error`] describes what went wrong. Strings handed out by the
+//! library must be released with [`sodo_string_free`].
+//!
+//! The header `include/sodo.h` is generated from this file by cbindgen.
+
+use sodo::{Difficulty, Solver, Sudoku};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Result code of every fallible call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SodoStatus {
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// The grid size has no box shape or is larger than 64.
+    InvalidSize = 3,
+    /// The puzzle string could not be parsed or breaks the rules.
+    InvalidPuzzle = 4,
+    /// The puzzle has no solution.
+    NoSolution = 5,
+    /// No logical move was found.
+    NoHint = 6,
+    /// Generation failed.
+    GenerateFailed = 7,
+    /// The library panicked; [`sodo_last_error`] holds the message.
+    Internal = 8,
+}
+
+/// Puzzle difficulty level.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SodoDifficulty {
+    Easy = 0,
+    Medium = 1,
+    Hard = 2,
+    Expert = 3,
+}
+
+/// A suggested placement. Row and column are zero-based.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SodoHint {
+    pub row: usize,
+    pub col: usize,
+    pub value: u8,
+}
+
+impl From<SodoDifficulty> for Difficulty {
+    fn from(d: SodoDifficulty) -> Self {
+        match d {
+            SodoDifficulty::Easy => Difficulty::Easy,
+            SodoDifficulty::Medium => Difficulty::Medium,
+            SodoDifficulty::Hard => Difficulty::Hard,
+            SodoDifficulty::Expert => Difficulty::Expert,
+        }
+    }
+}
+
+impl From<Difficulty> for SodoDifficulty {
+    fn from(d: Difficulty) -> Self {
+        match d {
+            Difficulty::Easy => SodoDifficulty::Easy,
+            Difficulty::Medium => SodoDifficulty::Medium,
+            Difficulty::Hard => SodoDifficulty::Hard,
+            Difficulty::Expert => SodoDifficulty::Expert,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// Records `msg` as this thread's last error and returns `status`.
+fn fail(status: SodoStatus, msg: impl Into<String>) -> SodoStatus {
+    let msg = CString::new(msg.into()).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = msg);
+    status
+}
+
+/// Converts a C string argument, recording an error on failure.
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, SodoStatus> {
+    if s.is_null() {
+        return Err(fail(SodoStatus::NullPointer, "Null string argument"));
+    }
+    // SAFETY: the caller guarantees `s` is a valid NUL-terminated string.
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|e| fail(SodoStatus::InvalidUtf8, e.to_string()))
+}
+
+fn check_size(size: usize) -> Result<(), SodoStatus> {
+    if Sudoku::box_shape(size).is_none() {
+        return Err(fail(
+            SodoStatus::InvalidSize,
+            format!("Invalid grid size {size}"),
+        ));
+    }
+    Ok(())
+}
+
+unsafe fn read_puzzle(puzzle: *const c_char, size: usize) -> Result<Sudoku, SodoStatus> {
+    check_size(size)?;
+    // SAFETY: forwarded from the caller's contract.
+    let s = unsafe { read_str(puzzle) }?;
+    Sudoku::from_string(s.trim(), size).map_err(|e| fail(SodoStatus::InvalidPuzzle, e))
+}
+
+/// Hands `s` to the caller through `out`.
+unsafe fn write_string(out: *mut *mut c_char, s: String) -> Result<(), SodoStatus> {
+    let s = CString::new(s).map_err(|e| fail(SodoStatus::InvalidPuzzle, e.to_string()))?;
+    // SAFETY: `out` was checked for null by the caller of this helper.
+    unsafe { *out = s.into_raw() };
+    Ok(())
+}
+
+/// Runs the body of an exported call, folding its result into a status.
+/// A panic is caught there rather than unwinding into the caller.
+fn guard(body: impl FnOnce() -> Result<(), SodoStatus>) -> SodoStatus {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => SodoStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(payload) => {
+            let msg = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Unknown panic".into());
+            fail(SodoStatus::Internal, format!("Internal error: {msg}"))
+        }
+    }
+}
+
+/// Returns the message for the last error on this thread, or an empty string.
+///
+/// The pointer stays valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn sodo_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ptr())
+}
+
+/// Returns the library version as a static string.
+#[unsafe(no_mangle)]
+pub extern "C" fn sodo_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Solves `puzzle` and stores the compact solution in `*out`.
+///
+/// # Safety
+///
+/// `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+/// Release the returned string with [`sodo_string_free`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sodo_solve(
+    puzzle: *const c_char,
+    size: usize,
+    out: *mut *mut c_char,
+) -> SodoStatus {
+    if out.is_null() {
+        return fail(SodoStatus::NullPointer, "Null output pointer");
+    }
+    guard(|| {
+        // SAFETY: forwarded from the caller's contract.
+        let sudoku = unsafe { read_puzzle(puzzle, size) }?;
+        let solution = Solver::new()
+            .solve(sudoku)
+            .map_err(|e| fail(SodoStatus::NoSolution, e))?;
+        // SAFETY: `out` is non-null.
+        unsafe { write_string(out, solution.to_string_compact()) }
+    })
+}
+
+/// Generates a puzzle and stores it as a compact string in `*out`.
+///
+/// # Safety
+///
+/// `out` must be a valid pointer. Release the returned string with
+/// [`sodo_string_free`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sodo_generate(
+    size: usize,
+    difficulty: SodoDifficulty,
+    out: *mut *mut c_char,
+) -> SodoStatus {
+    if out.is_null() {
+        return fail(SodoStatus::NullPointer, "Null output pointer");
+    }
+    guard(|| {
+        check_size(size)?;
+        let puzzle = Solver::new()
+            .generate(size, difficulty.into())
+            .map_err(|e| fail(SodoStatus::GenerateFailed, e))?;
+        // SAFETY: `out` is non-null.
+        unsafe { write_string(out, puzzle.to_string_compact()) }
+    })
+}
+
+/// Rates `puzzle` and stores its difficulty in `*out`.
+///
+/// # Safety
+///
+/// `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sodo_rate(
+    puzzle: *const c_char,
+    size: usize,
+    out: *mut SodoDifficulty,
+) -> SodoStatus {
+    if out.is_null() {
+        return fail(SodoStatus::NullPointer, "Null output pointer");
+    }
+    guard(|| {
+        // SAFETY: forwarded from the caller's contract.
+        let sudoku = unsafe { read_puzzle(puzzle, size) }?;
+        let solver = Solver::new();
+        if solver.count_solutions(&sudoku, Some(1)) == 0 {
+            return Err(fail(SodoStatus::NoSolution, "No solution found"));
+        }
+        // SAFETY: `out` is non-null.
+        unsafe { *out = solver.grade(&sudoku).into() };
+        Ok(())
+    })
+}
+
+/// Finds the next logical placement and stores it in `*out`.
+///
+/// Returns [`SodoStatus::NoHint`] when no single can be found.
+///
+/// # Safety
+///
+/// `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sodo_hint(
+    puzzle: *const c_char,
+    size: usize,
+    out: *mut SodoHint,
+) -> SodoStatus {
+    if out.is_null() {
+        return fail(SodoStatus::NullPointer, "Null output pointer");
+    }
+    guard(|| {
+        // SAFETY: forwarded from the caller's contract.
+        let sudoku = unsafe { read_puzzle(puzzle, size) }?;
+        let (row, col, value) = Solver::new()
+            .hint(&sudoku)
+            .ok_or_else(|| fail(SodoStatus::NoHint, "No hint available"))?;
+        // SAFETY: `out` is non-null.
+        unsafe { *out = SodoHint { row, col, value } };
+        Ok(())
+    })
+}
+
+/// Checks `puzzle` for repeated digits and stores the result in `*out`.
+///
+/// # Safety
+///
+/// `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sodo_validate(
+    puzzle: *const c_char,
+    size: usize,
+    out: *mut bool,
+) -> SodoStatus {
+    if out.is_null() {
+        return fail(SodoStatus::NullPointer, "Null output pointer");
+    }
+    guard(|| {
+        // SAFETY: forwarded from the caller's contract.
+        let sudoku = unsafe { read_puzzle(puzzle, size) }?;
+        // SAFETY: `out` is non-null.
+        unsafe { *out = sudoku.is_valid() };
+        Ok(())
+    })
+}
+
+/// Counts solutions of `puzzle`, stopping at `limit`, into `*out`.
+///
+/// # Safety
+///
+/// `puzzle` must be a valid NUL-terminated string and `out` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sodo_count_solutions(
+    puzzle: *const c_char,
+    size: usize,
+    limit: usize,
+    out: *mut usize,
+) -> SodoStatus {
+    if out.is_null() {
+        return fail(SodoStatus::NullPointer, "Null output pointer");
+    }
+    guard(|| {
+        // SAFETY: forwarded from the caller's contract.
+        let sudoku = unsafe { read_puzzle(puzzle, size) }?;
+        // SAFETY: `out` is non-null.
+        unsafe { *out = Solver::new().count_solutions(&sudoku, Some(limit)) };
+        Ok(())
+    })
+}
+
+/// Releases a string returned by this library. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a pointer obtained from this library that has not been
+/// freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sodo_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: `s` came from `CString::into_raw` in `write_string`.
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
@@ -0,0 +1,203 @@
+//! Node.js native addon for the sodo Sudoku library.
+//!
+//! Exposes the batch solve, generate and rate APIs for server-side use. The
+//! `*Async` variants run on the libuv thread pool and return a `Promise`, so
+//! large batches don't block the event loop; the addon is context-aware and
+//! can be loaded from `worker_threads` as well.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use sodo::{Difficulty, Solver, Sudoku};
+
+fn to_napi(e: String) -> Error {
+    Error::new(Status::InvalidArg, e)
+}
+
+fn parse_difficulty(s: Option<&str>) -> Result<Difficulty> {
+    match s.unwrap_or("medium") {
+        "easy" => Ok(Difficulty::Easy),
+        "medium" => Ok(Difficulty::Medium),
+        "hard" => Ok(Difficulty::Hard),
+        "expert" => Ok(Difficulty::Expert),
+        other => Err(to_napi(format!("Invalid difficulty: {other}"))),
+    }
+}
+
+fn difficulty_name(d: Difficulty) -> &'static str {
+    match d {
+        Difficulty::Easy => "easy",
+        Difficulty::Medium => "medium",
+        Difficulty::Hard => "hard",
+        Difficulty::Expert => "expert",
+    }
+}
+
+fn rate_one(solver: &Solver, puzzle: &str, size: usize) -> std::result::Result<String, String> {
+    let sudoku = Sudoku::from_string(puzzle.trim(), size)?;
+    if solver.count_solutions(&sudoku, Some(1)) == 0 {
+        return Err("No solution found".into());
+    }
+    Ok(difficulty_name(solver.grade(&sudoku)).into())
+}
+
+fn solve_batch(puzzles: &[String], size: usize) -> Vec<Option<String>> {
+    Solver::new()
+        .solve_many(puzzles, size)
+        .into_iter()
+        .map(std::result::Result::ok)
+        .collect()
+}
+
+fn generate_batch(count: u32, size: usize, difficulty: Difficulty) -> Result<Vec<String>> {
+    let mut solver = Solver::new();
+    (0..count)
+        .map(|_| {
+            solver
+                .generate(size, difficulty)
+                .map(|p| p.to_string_compact())
+                .map_err(|e| Error::new(Status::GenericFailure, e))
+        })
+        .collect()
+}
+
+fn rate_batch(puzzles: &[String], size: usize) -> Vec<Option<String>> {
+    let solver = Solver::new();
+    puzzles
+        .iter()
+        .map(|p| rate_one(&solver, p, size).ok())
+        .collect()
+}
+
+/// Solves a puzzle and returns the compact solution.
+#[napi]
+pub fn solve(puzzle: String, size: Option<u32>) -> Result<String> {
+    let sudoku = Sudoku::from_string(puzzle.trim(), size.unwrap_or(9) as usize).map_err(to_napi)?;
+    let solution = Solver::new()
+        .solve(sudoku)
+        .map_err(|e| Error::new(Status::GenericFailure, e))?;
+    Ok(solution.to_string_compact())
+}
+
+/// Generates a puzzle. `difficulty` is `"easy"`, `"medium"` (default),
+/// `"hard"` or `"expert"`.
+#[napi]
+pub fn generate(size: Option<u32>, difficulty: Option<String>) -> Result<String> {
+    let difficulty = parse_difficulty(difficulty.as_deref())?;
+    Ok(generate_batch(1, size.unwrap_or(9) as usize, difficulty)?.remove(0))
+}
+
+/// Rates a puzzle, returning its difficulty name.
+#[napi]
+pub fn rate(puzzle: String, size: Option<u32>) -> Result<String> {
+    rate_one(&Solver::new(), &puzzle, size.unwrap_or(9) as usize).map_err(to_napi)
+}
+
+/// Solves puzzles in order; unsolvable or malformed entries give `null`.
+#[napi]
+pub fn solve_many(puzzles: Vec<String>, size: Option<u32>) -> Vec<Option<String>> {
+    solve_batch(&puzzles, size.unwrap_or(9) as usize)
+}
+
+/// Generates `count` puzzles of the same size and difficulty.
+#[napi]
+pub fn generate_many(
+    count: u32,
+    size: Option<u32>,
+    difficulty: Option<String>,
+) -> Result<Vec<String>> {
+    let difficulty = parse_difficulty(difficulty.as_deref())?;
+    generate_batch(count, size.unwrap_or(9) as usize, difficulty)
+}
+
+/// Rates puzzles in order; unsolvable or malformed entries give `null`.
+#[napi]
+pub fn rate_many(puzzles: Vec<String>, size: Option<u32>) -> Vec<Option<String>> {
+    rate_batch(&puzzles, size.unwrap_or(9) as usize)
+}
+
+pub struct SolveTask {
+    puzzles: Vec<String>,
+    size: usize,
+}
+
+impl Task for SolveTask {
+    type Output = Vec<Option<String>>;
+    type JsValue = Vec<Option<String>>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(solve_batch(&self.puzzles, self.size))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+pub struct GenerateTask {
+    count: u32,
+    size: usize,
+    difficulty: Difficulty,
+}
+
+impl Task for GenerateTask {
+    type Output = Vec<String>;
+    type JsValue = Vec<String>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        generate_batch(self.count, self.size, self.difficulty)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+pub struct RateTask {
+    puzzles: Vec<String>,
+    size: usize,
+}
+
+impl Task for RateTask {
+    type Output = Vec<Option<String>>;
+    type JsValue = Vec<Option<String>>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(rate_batch(&self.puzzles, self.size))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// `solveMany` on a libuv worker thread.
+#[napi]
+pub fn solve_many_async(puzzles: Vec<String>, size: Option<u32>) -> AsyncTask<SolveTask> {
+    AsyncTask::new(SolveTask {
+        puzzles,
+        size: size.unwrap_or(9) as usize,
+    })
+}
+
+/// `generateMany` on a libuv worker thread.
+#[napi]
+pub fn generate_many_async(
+    count: u32,
+    size: Option<u32>,
+    difficulty: Option<String>,
+) -> Result<AsyncTask<GenerateTask>> {
+    Ok(AsyncTask::new(GenerateTask {
+        count,
+        size: size.unwrap_or(9) as usize,
+        difficulty: parse_difficulty(difficulty.as_deref())?,
+    }))
+}
+
+/// `rateMany` on a libuv worker thread.
+#[napi]
+pub fn rate_many_async(puzzles: Vec<String>, size: Option<u32>) -> AsyncTask<RateTask> {
+    AsyncTask::new(RateTask {
+        puzzles,
+        size: size.unwrap_or(9) as usize,
+    })
+}
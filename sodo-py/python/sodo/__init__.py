@@ -0,0 +1,5 @@
+"""Sudoku solver, generator and analyzer."""
+
+from ._sodo import Analysis, Difficulty, Solver, Sudoku, __version__
+
+__all__ = ["Analysis", "Difficulty", "Solver", "Sudoku", "__version__"]
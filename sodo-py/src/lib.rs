@@ -1,14 +1,307 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+//! Python bindings for the sodo Sudoku library.
+//!
+//! Built with maturin as the `sodo._sodo` extension module; the `sodo`
+//! Python package re-exports everything from it.
+
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use sodo::{
+    Analysis as SodoAnalysis, Difficulty as SodoDifficulty, Solver as SodoSolver,
+    Sudoku as SodoSudoku,
+};
+use std::collections::HashMap;
+
+fn value_error(e: String) -> PyErr {
+    PyValueError::new_err(e)
+}
+
+/// Puzzle difficulty level.
+#[pyclass(eq, eq_int, module = "sodo")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl From<Difficulty> for SodoDifficulty {
+    fn from(d: Difficulty) -> Self {
+        match d {
+            Difficulty::Easy => SodoDifficulty::Easy,
+            Difficulty::Medium => SodoDifficulty::Medium,
+            Difficulty::Hard => SodoDifficulty::Hard,
+            Difficulty::Expert => SodoDifficulty::Expert,
+        }
+    }
+}
+
+impl From<SodoDifficulty> for Difficulty {
+    fn from(d: SodoDifficulty) -> Self {
+        match d {
+            SodoDifficulty::Easy => Difficulty::Easy,
+            SodoDifficulty::Medium => Difficulty::Medium,
+            SodoDifficulty::Hard => Difficulty::Hard,
+            SodoDifficulty::Expert => Difficulty::Expert,
+        }
+    }
+}
+
+/// A Sudoku grid.
+#[pyclass(module = "sodo")]
+#[derive(Clone)]
+pub struct Sudoku {
+    inner: SodoSudoku,
+}
+
+#[pymethods]
+impl Sudoku {
+    /// Parses a compact puzzle string (`.` or `0` for empty cells).
+    #[new]
+    #[pyo3(signature = (puzzle, size = 9))]
+    fn new(puzzle: &str, size: usize) -> PyResult<Self> {
+        let inner = SodoSudoku::from_string(puzzle.trim(), size).map_err(value_error)?;
+        Ok(Self { inner })
+    }
+
+    /// Creates an empty grid.
+    #[staticmethod]
+    #[pyo3(signature = (size = 9))]
+    fn empty(size: usize) -> PyResult<Self> {
+        if SodoSudoku::box_shape(size).is_none() {
+            return Err(value_error(format!("Invalid grid size {size}")));
+        }
+        Ok(Self {
+            inner: SodoSudoku::new(size),
+        })
+    }
+
+    #[getter]
+    fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    #[getter]
+    fn box_size(&self) -> usize {
+        self.inner.box_size()
+    }
+
+    #[getter]
+    fn box_rows(&self) -> usize {
+        self.inner.box_rows()
+    }
+
+    #[getter]
+    fn box_cols(&self) -> usize {
+        self.inner.box_cols()
+    }
+
+    /// Value at (row, col), or `None` if the cell is empty.
+    fn get(&self, row: usize, col: usize) -> PyResult<Option<u8>> {
+        self.inner
+            .get(row, col)
+            .map(|c| c.value())
+            .ok_or_else(|| PyIndexError::new_err("Cell out of bounds"))
+    }
+
+    /// Sets (row, col) to `value`; 0 clears the cell.
+    fn set(&mut self, row: usize, col: usize, value: u8) -> PyResult<()> {
+        self.inner.set(row, col, value).map_err(value_error)
+    }
+
+    /// Whether (row, col) holds a given clue.
+    fn is_given(&self, row: usize, col: usize) -> PyResult<bool> {
+        self.inner
+            .get(row, col)
+            .map(|c| c.is_given())
+            .ok_or_else(|| PyIndexError::new_err("Cell out of bounds"))
+    }
+
+    /// Digits that could go in (row, col), ascending.
+    fn candidates(&self, row: usize, col: usize) -> Vec<u32> {
+        self.inner
+            .candidates(row, col)
+            .into_iter()
+            .map(u32::from)
+            .collect()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.inner.is_valid()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.inner.is_complete()
+    }
+
+    fn is_solved(&self) -> bool {
+        self.inner.is_solved()
+    }
+
+    fn empty_count(&self) -> usize {
+        self.inner.empty_count()
+    }
+
+    /// Rows of the grid, with 0 for empty cells.
+    fn to_list(&self) -> Vec<Vec<u32>> {
+        let n = self.inner.size();
+        let values: Vec<u32> = self
+            .inner
+            .cells()
+            .map(|c| c.value().map_or(0, u32::from))
+            .collect();
+        values.chunks(n).map(<[u32]>::to_vec).collect()
+    }
+
+    /// Compact string, one character per cell.
+    fn to_string_compact(&self) -> String {
+        self.inner.to_string_compact()
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Sudoku('{}')", self.inner.to_string_compact())
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Summary of a puzzle from `Solver.analyze`.
+#[pyclass(module = "sodo", get_all)]
+pub struct Analysis {
+    difficulty: Difficulty,
+    /// Successful applications of each strategy on the logical solve path.
+    techniques: HashMap<String, usize>,
+    clues: usize,
+    cells: usize,
+    /// Symmetry of the given pattern, e.g. `"Rotational180"`.
+    symmetry: String,
+    /// Number of solutions, counted up to 2.
+    solutions: usize,
+    minimal: bool,
+    backdoor: Option<usize>,
+    /// Unavoidable sets as lists of (row, col) cells.
+    unavoidable: Vec<Vec<(usize, usize)>>,
+    unhit: usize,
+    is_unique: bool,
+    is_logical: bool,
+    report: String,
+}
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+impl From<SodoAnalysis> for Analysis {
+    fn from(a: SodoAnalysis) -> Self {
+        Self {
+            difficulty: a.difficulty.into(),
+            symmetry: format!("{:?}", a.symmetry),
+            unavoidable: a.unavoidable.iter().map(|u| u.cells.clone()).collect(),
+            is_unique: a.is_unique(),
+            is_logical: a.is_logical(),
+            report: a.to_string(),
+            techniques: a.techniques,
+            clues: a.clues,
+            cells: a.cells,
+            solutions: a.solutions,
+            minimal: a.minimal,
+            backdoor: a.backdoor,
+            unhit: a.unhit,
+        }
     }
 }
+
+#[pymethods]
+impl Analysis {
+    fn __str__(&self) -> String {
+        self.report.clone()
+    }
+}
+
+/// A Sudoku solver using logical strategies and backtracking.
+#[pyclass(module = "sodo")]
+pub struct Solver {
+    inner: SodoSolver,
+}
+
+#[pymethods]
+impl Solver {
+    #[new]
+    #[pyo3(signature = (backtracking = true))]
+    fn new(backtracking: bool) -> Self {
+        Self {
+            inner: SodoSolver::new().use_backtracking(backtracking),
+        }
+    }
+
+    /// Returns the solved grid.
+    fn solve(&mut self, py: Python<'_>, sudoku: &Sudoku) -> PyResult<Sudoku> {
+        let puzzle = sudoku.inner.clone();
+        let inner = py
+            .detach(|| self.inner.solve(puzzle))
+            .map_err(value_error)?;
+        Ok(Sudoku { inner })
+    }
+
+    /// Solves compact puzzle strings, returning a solution string or `None`
+    /// for each one that fails. The GIL is released while solving.
+    #[pyo3(signature = (puzzles, size = 9))]
+    fn solve_many(
+        &mut self,
+        py: Python<'_>,
+        puzzles: Vec<String>,
+        size: usize,
+    ) -> Vec<Option<String>> {
+        py.detach(|| {
+            self.inner
+                .solve_many(&puzzles, size)
+                .into_iter()
+                .map(Result::ok)
+                .collect()
+        })
+    }
+
+    /// Returns the next logical placement as (row, col, value), or `None`.
+    fn hint(&self, sudoku: &Sudoku) -> Option<(usize, usize, u8)> {
+        self.inner.hint(&sudoku.inner)
+    }
+
+    /// Counts solutions, stopping at `limit`.
+    #[pyo3(signature = (sudoku, limit = 2))]
+    fn count_solutions(&self, py: Python<'_>, sudoku: &Sudoku, limit: usize) -> usize {
+        let puzzle = &sudoku.inner;
+        py.detach(|| self.inner.count_solutions(puzzle, Some(limit)))
+    }
+
+    /// Generates a puzzle with a unique solution.
+    #[pyo3(signature = (size = 9, difficulty = Difficulty::Medium))]
+    fn generate(
+        &mut self,
+        py: Python<'_>,
+        size: usize,
+        difficulty: Difficulty,
+    ) -> PyResult<Sudoku> {
+        let inner = py
+            .detach(|| self.inner.generate(size, difficulty.into()))
+            .map_err(value_error)?;
+        Ok(Sudoku { inner })
+    }
+
+    /// Rates how hard the puzzle is to solve by hand.
+    fn rate(&self, py: Python<'_>, sudoku: &Sudoku) -> Difficulty {
+        py.detach(|| self.inner.grade(&sudoku.inner).into())
+    }
+
+    /// Full report: difficulty, techniques, uniqueness, symmetry and more.
+    fn analyze(&self, py: Python<'_>, sudoku: &Sudoku) -> Analysis {
+        py.detach(|| self.inner.analyze(&sudoku.inner)).into()
+    }
+}
+
+#[pymodule]
+fn _sodo(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_class::<Difficulty>()?;
+    m.add_class::<Sudoku>()?;
+    m.add_class::<Solver>()?;
+    m.add_class::<Analysis>()?;
+    Ok(())
+}
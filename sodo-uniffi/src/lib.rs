@@ -0,0 +1,393 @@
+//! UniFFI bindings for the sodo Sudoku library.
+//!
+//! Generates Swift and Kotlin APIs from this crate's exports, so native apps
+//! share the engine the wasm build uses. Puzzles are compact strings, as in
+//! the other bindings. Build the library, then run
+//! `cargo run -p sodo-uniffi --features cli --bin uniffi-bindgen -- generate --library <lib> --language swift --out-dir <dir>`.
+
+use sodo::{
+    Difficulty as SodoDifficulty, Game as SodoGame, HintLevel as SodoHintLevel, Solver, Sudoku,
+};
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+uniffi::setup_scaffolding!();
+
+/// Error raised by every fallible call.
+#[derive(Debug, uniffi::Error)]
+pub enum SodoError {
+    /// The puzzle, save or argument was rejected.
+    Invalid { message: String },
+    /// The puzzle has no solution.
+    NoSolution { message: String },
+    /// The session has used up its hints.
+    NoHintsLeft,
+}
+
+impl fmt::Display for SodoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid { message } | Self::NoSolution { message } => f.write_str(message),
+            Self::NoHintsLeft => f.write_str("No hints left"),
+        }
+    }
+}
+
+impl std::error::Error for SodoError {}
+
+fn invalid(message: String) -> SodoError {
+    SodoError::Invalid { message }
+}
+
+/// Puzzle difficulty level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl From<Difficulty> for SodoDifficulty {
+    fn from(d: Difficulty) -> Self {
+        match d {
+            Difficulty::Easy => SodoDifficulty::Easy,
+            Difficulty::Medium => SodoDifficulty::Medium,
+            Difficulty::Hard => SodoDifficulty::Hard,
+            Difficulty::Expert => SodoDifficulty::Expert,
+        }
+    }
+}
+
+impl From<SodoDifficulty> for Difficulty {
+    fn from(d: SodoDifficulty) -> Self {
+        match d {
+            SodoDifficulty::Easy => Difficulty::Easy,
+            SodoDifficulty::Medium => Difficulty::Medium,
+            SodoDifficulty::Hard => Difficulty::Hard,
+            SodoDifficulty::Expert => Difficulty::Expert,
+        }
+    }
+}
+
+/// How much a session hint reveals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum HintLevel {
+    /// Only the cell to look at.
+    Cell,
+    /// The cell and its value.
+    Value,
+}
+
+impl From<HintLevel> for SodoHintLevel {
+    fn from(level: HintLevel) -> Self {
+        match level {
+            HintLevel::Cell => SodoHintLevel::Cell,
+            HintLevel::Value => SodoHintLevel::Value,
+        }
+    }
+}
+
+/// A suggested placement. Row and column are zero-based; `value` is absent
+/// for cell-level session hints.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Hint {
+    pub row: u32,
+    pub col: u32,
+    pub value: Option<u8>,
+}
+
+/// A board position, zero-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct Position {
+    pub row: u32,
+    pub col: u32,
+}
+
+/// Summary of a puzzle from [`analyze`].
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct Analysis {
+    pub difficulty: Difficulty,
+    /// Successful applications of each strategy on the logical solve path.
+    pub techniques: std::collections::HashMap<String, u32>,
+    pub clues: u32,
+    pub cells: u32,
+    /// Symmetry of the given pattern, e.g. `"Rotational180"`.
+    pub symmetry: String,
+    /// Number of solutions, counted up to 2.
+    pub solutions: u32,
+    pub minimal: bool,
+    pub backdoor: Option<u32>,
+    /// Human-readable report, as printed by `sodo analyze`.
+    pub report: String,
+}
+
+/// Progress of a game session, from [`Game::check`].
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct Progress {
+    pub wrong: Vec<Position>,
+    pub correct: u32,
+    pub empty: u32,
+    pub editable: u32,
+    pub completion: f64,
+}
+
+fn parse(puzzle: &str, size: u32) -> Result<Sudoku, SodoError> {
+    Sudoku::from_string(puzzle.trim(), size as usize).map_err(invalid)
+}
+
+/// Solves a puzzle, returning the compact solution.
+#[uniffi::export]
+pub fn solve(puzzle: String, size: u32) -> Result<String, SodoError> {
+    let solution = Solver::new()
+        .solve(parse(&puzzle, size)?)
+        .map_err(|message| SodoError::NoSolution { message })?;
+    Ok(solution.to_string_compact())
+}
+
+/// Generates a puzzle with a unique solution.
+#[uniffi::export]
+pub fn generate(size: u32, difficulty: Difficulty) -> Result<String, SodoError> {
+    let puzzle = Solver::new()
+        .generate(size as usize, difficulty.into())
+        .map_err(invalid)?;
+    Ok(puzzle.to_string_compact())
+}
+
+/// Next logical placement, or `None` if the strategies find nothing.
+#[uniffi::export]
+pub fn hint(puzzle: String, size: u32) -> Result<Option<Hint>, SodoError> {
+    let sudoku = parse(&puzzle, size)?;
+    Ok(Solver::new().hint(&sudoku).map(|(r, c, v)| Hint {
+        row: r as u32,
+        col: c as u32,
+        value: Some(v),
+    }))
+}
+
+/// Whether the puzzle has no repeated digits.
+#[uniffi::export]
+pub fn validate(puzzle: String, size: u32) -> Result<bool, SodoError> {
+    Ok(parse(&puzzle, size)?.is_valid())
+}
+
+/// Counts solutions, stopping at `limit`.
+#[uniffi::export]
+pub fn count_solutions(puzzle: String, size: u32, limit: u32) -> Result<u32, SodoError> {
+    let sudoku = parse(&puzzle, size)?;
+    Ok(Solver::new().count_solutions(&sudoku, Some(limit as usize)) as u32)
+}
+
+/// Rates how hard the puzzle is to solve by hand.
+#[uniffi::export]
+pub fn rate(puzzle: String, size: u32) -> Result<Difficulty, SodoError> {
+    let sudoku = parse(&puzzle, size)?;
+    Ok(Solver::new().grade(&sudoku).into())
+}
+
+/// Difficulty, techniques, uniqueness and other properties of a puzzle.
+#[uniffi::export]
+pub fn analyze(puzzle: String, size: u32) -> Result<Analysis, SodoError> {
+    let a = Solver::new().analyze(&parse(&puzzle, size)?);
+    Ok(Analysis {
+        difficulty: a.difficulty.into(),
+        techniques: a
+            .techniques
+            .iter()
+            .map(|(k, &v)| (k.clone(), v as u32))
+            .collect(),
+        clues: a.clues as u32,
+        cells: a.cells as u32,
+        symmetry: format!("{:?}", a.symmetry),
+        solutions: a.solutions as u32,
+        minimal: a.minimal,
+        backdoor: a.backdoor.map(|b| b as u32),
+        report: a.to_string(),
+    })
+}
+
+/// Interactive play session with entries, pencil marks and undo/redo.
+#[derive(uniffi::Object)]
+pub struct Game {
+    inner: Mutex<SodoGame>,
+}
+
+impl Game {
+    fn lock(&self) -> MutexGuard<'_, SodoGame> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[uniffi::export]
+impl Game {
+    /// Starts a session from a compact puzzle string.
+    #[uniffi::constructor]
+    pub fn new(puzzle: String, size: u32) -> Result<Self, SodoError> {
+        Ok(Self {
+            inner: Mutex::new(SodoGame::new(parse(&puzzle, size)?)),
+        })
+    }
+
+    /// Restores a session written by [`Game::save`].
+    #[uniffi::constructor]
+    pub fn load(saved: String) -> Result<Self, SodoError> {
+        Ok(Self {
+            inner: Mutex::new(SodoGame::load(&saved).map_err(invalid)?),
+        })
+    }
+
+    /// Serializes the whole session for later [`Game::load`].
+    pub fn save(&self) -> Result<String, SodoError> {
+        self.lock().save().map_err(invalid)
+    }
+
+    /// Enters a value (0 clears the cell).
+    pub fn place(&self, row: u32, col: u32, value: u8) -> Result<(), SodoError> {
+        self.lock()
+            .place(row as usize, col as usize, value)
+            .map_err(invalid)
+    }
+
+    pub fn clear(&self, row: u32, col: u32) -> Result<(), SodoError> {
+        self.lock()
+            .clear(row as usize, col as usize)
+            .map_err(invalid)
+    }
+
+    pub fn toggle_mark(&self, row: u32, col: u32, value: u8) -> Result<(), SodoError> {
+        self.lock()
+            .toggle_mark(row as usize, col as usize, value)
+            .map_err(invalid)
+    }
+
+    /// Clear a placed digit from peers' pencil marks automatically.
+    pub fn set_auto_marks(&self, enabled: bool) {
+        self.lock().set_auto_marks(enabled);
+    }
+
+    /// Pencil marks on a cell, ascending.
+    pub fn marks(&self, row: u32, col: u32) -> Vec<u8> {
+        self.lock()
+            .marks(row as usize, col as usize)
+            .iter()
+            .collect()
+    }
+
+    pub fn undo(&self) -> bool {
+        self.lock().undo()
+    }
+
+    pub fn redo(&self) -> bool {
+        self.lock().redo()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.lock().can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.lock().can_redo()
+    }
+
+    /// Number of moves currently applied.
+    pub fn move_count(&self) -> u32 {
+        self.lock().moves().count() as u32
+    }
+
+    /// Compact board string as it was after the first `moves` moves.
+    pub fn board_at(&self, moves: u32) -> String {
+        self.lock().board_at(moves as usize).to_string_compact()
+    }
+
+    /// Whether a cell is part of the original puzzle.
+    pub fn is_given(&self, row: u32, col: u32) -> bool {
+        self.lock()
+            .board()
+            .get(row as usize, col as usize)
+            .is_some_and(|c| c.is_given())
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.lock().is_solved()
+    }
+
+    /// Compares entries against the solution.
+    pub fn check(&self) -> Progress {
+        let report = self.lock().check();
+        Progress {
+            completion: report.completion(),
+            wrong: report
+                .wrong
+                .iter()
+                .map(|&(r, c)| Position {
+                    row: r as u32,
+                    col: c as u32,
+                })
+                .collect(),
+            correct: report.correct as u32,
+            empty: report.empty as u32,
+            editable: report.editable as u32,
+        }
+    }
+
+    /// Suggests the next placement and charges it to the session.
+    pub fn hint(&self, level: HintLevel) -> Result<Option<Hint>, SodoError> {
+        let hint = self
+            .lock()
+            .hint(level.into())
+            .map_err(|_| SodoError::NoHintsLeft)?;
+        Ok(hint.map(|h| Hint {
+            row: h.row as u32,
+            col: h.col as u32,
+            value: h.value,
+        }))
+    }
+
+    pub fn hints_used(&self) -> u32 {
+        self.lock().hints_used() as u32
+    }
+
+    /// Hints still allowed, or `None` when unlimited.
+    pub fn hints_left(&self) -> Option<u32> {
+        self.lock().hints_left().map(|n| n as u32)
+    }
+
+    /// Caps the hints this session may take; `None` removes the cap.
+    pub fn set_hint_limit(&self, limit: Option<u32>) {
+        self.lock().set_hint_limit(limit.map(|n| n as usize));
+    }
+
+    pub fn pause(&self) {
+        self.lock().pause();
+    }
+
+    pub fn resume(&self) {
+        self.lock().resume();
+    }
+
+    /// Credits play time measured by the host, in milliseconds.
+    pub fn advance(&self, ms: u64) {
+        self.lock().timer_mut().advance(Duration::from_millis(ms));
+    }
+
+    /// Time played in milliseconds, excluding pauses.
+    pub fn elapsed_ms(&self) -> u64 {
+        self.lock().elapsed().as_millis() as u64
+    }
+
+    /// Score from time, mistakes and hints used.
+    pub fn score(&self) -> u64 {
+        self.lock().score()
+    }
+
+    /// Number of wrong values entered this session.
+    pub fn mistakes(&self) -> u32 {
+        self.lock().mistakes() as u32
+    }
+
+    /// Current board as compact string.
+    pub fn board(&self) -> String {
+        self.lock().board().to_string_compact()
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use sodo::{Difficulty as SodoDifficulty, Solver, Sudoku};
+use sodo::{
+    Difficulty as SodoDifficulty, Game as SodoGame, HintLevel, House, Problem, Solver, Sudoku,
+};
+use std::time::Duration;
 use wasm_bindgen::prelude::*;
 
 /// 9x9 grid: `number[][]` where 0 = empty, 1-9 = filled.
@@ -68,10 +71,10 @@ pub fn validate_solution(puzzle: JsValue, solution: JsValue) -> Result<bool, Str
     for i in 0..9 {
         let mut row_seen = [false; 10];
         let mut col_seen = [false; 10];
-        for j in 0..9 {
+        for (j, col) in s.iter().enumerate() {
             let row_val = s[i][j];
-            let col_val = s[j][i];
-            if row_val < 1 || row_val > 9 || row_seen[row_val as usize] {
+            let col_val = col[i];
+            if !(1..=9).contains(&row_val) || row_seen[row_val as usize] {
                 return Ok(false);
             }
             if col_seen[col_val as usize] {
@@ -107,6 +110,14 @@ pub fn validate_grid(grid: JsValue) -> Result<bool, String> {
     Ok(from_grid(&g)?.is_valid())
 }
 
+/// Lists every repeated digit in a grid, for highlighting.
+/// @returns `{ house: "row" | "col" | "box", index, value, cells: [row, col][] }[]`
+#[wasm_bindgen(js_name = "getConflicts")]
+pub fn get_conflicts(grid: JsValue) -> Result<JsValue, String> {
+    let g = parse_grid(grid)?;
+    conflicts_to_js(&from_grid(&g)?)
+}
+
 /// Checks if a grid puzzle is solvable.
 #[wasm_bindgen(js_name = "isSolvable")]
 pub fn is_solvable_grid(grid: JsValue) -> Result<bool, String> {
@@ -197,6 +208,19 @@ pub fn solve(puzzle: &str, size: Option<usize>) -> Result<String, String> {
     solver.solve(sudoku).map(|s| s.to_string_compact())
 }
 
+/// Solves many compact puzzle strings in one call.
+/// @returns Array of solution strings, with `null` for puzzles that failed.
+#[wasm_bindgen(js_name = "solveMany")]
+pub fn solve_many(puzzles: Vec<String>, size: Option<usize>) -> Result<JsValue, String> {
+    let size = size.unwrap_or(9);
+    let solutions: Vec<Option<String>> = Solver::new()
+        .solve_many(&puzzles, size)
+        .into_iter()
+        .map(Result::ok)
+        .collect();
+    serde_wasm_bindgen::to_value(&solutions).map_err(|e| e.to_string())
+}
+
 /// Validates puzzle string for constraint violations.
 #[wasm_bindgen]
 pub fn validate(puzzle: &str, size: Option<usize>) -> Result<bool, String> {
@@ -204,6 +228,38 @@ pub fn validate(puzzle: &str, size: Option<usize>) -> Result<bool, String> {
     Ok(Sudoku::from_string(puzzle, size)?.is_valid())
 }
 
+/// Lists everything wrong with a puzzle string: bad characters, repeated
+/// digits, dead cells and, with `unique`, a missing or non-unique solution.
+/// @returns `{ ok, messages: string[], cells: [row, col][], problems }`
+#[wasm_bindgen(js_name = "validateDetailed")]
+pub fn validate_detailed(
+    puzzle: &str,
+    size: Option<usize>,
+    unique: Option<bool>,
+) -> Result<JsValue, String> {
+    let size = size.unwrap_or(9);
+    let mut report = Sudoku::validate_string(puzzle, size);
+    if report.is_ok() && unique.unwrap_or(false) {
+        report = Solver::new().validate(&Sudoku::from_string(puzzle, size)?);
+    }
+    let info = ValidationInfo {
+        ok: report.is_ok(),
+        messages: report.problems.iter().map(ToString::to_string).collect(),
+        cells: report.cells(),
+        problems: report.problems,
+    };
+    serde_wasm_bindgen::to_value(&info).map_err(|e| e.to_string())
+}
+
+/// Result of `validateDetailed`.
+#[derive(Serialize)]
+pub struct ValidationInfo {
+    pub ok: bool,
+    pub messages: Vec<String>,
+    pub cells: Vec<(usize, usize)>,
+    pub problems: Vec<Problem>,
+}
+
 /// Gets hint from puzzle string.
 /// @returns `{ row, col, value }` or `null`
 #[wasm_bindgen]
@@ -216,6 +272,14 @@ pub fn hint(puzzle: &str, size: Option<usize>) -> Result<JsValue, String> {
     }
 }
 
+/// Rates a puzzle string and reports its properties.
+/// @returns `{ difficulty, techniques, clues, symmetric, solutions, minimal, backdoor }`
+#[wasm_bindgen]
+pub fn analyze(puzzle: &str, size: Option<usize>) -> Result<JsValue, String> {
+    let sudoku = Sudoku::from_string(puzzle, size.unwrap_or(9))?;
+    serde_wasm_bindgen::to_value(&Solver::new().analyze(&sudoku)).map_err(|e| e.to_string())
+}
+
 /// Formats puzzle string as human-readable grid.
 #[wasm_bindgen]
 pub fn format(puzzle: &str, size: Option<usize>) -> Result<String, String> {
@@ -223,6 +287,248 @@ pub fn format(puzzle: &str, size: Option<usize>) -> Result<String, String> {
     Ok(Sudoku::from_string(puzzle, size)?.to_string())
 }
 
+/// Progress of a game session, as returned by `Game.check()`.
+#[derive(Serialize, Deserialize)]
+pub struct Progress {
+    pub wrong: Vec<(usize, usize)>,
+    pub correct: usize,
+    pub empty: usize,
+    pub editable: usize,
+    pub completion: f64,
+}
+
+/// Interactive play session with entries, pencil marks and undo/redo.
+#[wasm_bindgen]
+pub struct Game {
+    inner: SodoGame,
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Starts a session from a compact puzzle string.
+    #[wasm_bindgen(constructor)]
+    pub fn new(puzzle: &str, size: Option<usize>) -> Result<Game, String> {
+        let sudoku = Sudoku::from_string(puzzle, size.unwrap_or(9))?;
+        Ok(Self {
+            inner: SodoGame::new(sudoku),
+        })
+    }
+
+    /// Restores a session written by `save()`.
+    pub fn load(saved: &str) -> Result<Game, String> {
+        Ok(Self {
+            inner: SodoGame::load(saved)?,
+        })
+    }
+
+    /// Serializes the whole session to a string for later `Game.load()`.
+    pub fn save(&self) -> Result<String, String> {
+        self.inner.save()
+    }
+
+    /// Enters a value (0 clears the cell).
+    pub fn place(&mut self, row: usize, col: usize, value: u8) -> Result<(), String> {
+        self.inner.place(row, col, value)
+    }
+
+    pub fn clear(&mut self, row: usize, col: usize) -> Result<(), String> {
+        self.inner.clear(row, col)
+    }
+
+    #[wasm_bindgen(js_name = "toggleMark")]
+    pub fn toggle_mark(&mut self, row: usize, col: usize, value: u8) -> Result<(), String> {
+        self.inner.toggle_mark(row, col, value)
+    }
+
+    /// Clear a placed digit from peers' pencil marks automatically.
+    #[wasm_bindgen(js_name = "setAutoMarks")]
+    pub fn set_auto_marks(&mut self, enabled: bool) {
+        self.inner.set_auto_marks(enabled);
+    }
+
+    /// Pencil marks on a cell, ascending.
+    pub fn marks(&self, row: usize, col: usize) -> Vec<u8> {
+        self.inner.marks(row, col).iter().collect()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.inner.undo()
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.inner.redo()
+    }
+
+    #[wasm_bindgen(js_name = "canUndo")]
+    pub fn can_undo(&self) -> bool {
+        self.inner.can_undo()
+    }
+
+    #[wasm_bindgen(js_name = "canRedo")]
+    pub fn can_redo(&self) -> bool {
+        self.inner.can_redo()
+    }
+
+    /// Number of moves currently applied.
+    #[wasm_bindgen(js_name = "moveCount")]
+    pub fn move_count(&self) -> usize {
+        self.inner.moves().count()
+    }
+
+    /// Compact board string as it was after the first `moves` moves.
+    #[wasm_bindgen(js_name = "boardAt")]
+    pub fn board_at(&self, moves: usize) -> String {
+        self.inner.board_at(moves).to_string_compact()
+    }
+
+    /// Whether a cell is part of the original puzzle.
+    #[wasm_bindgen(js_name = "isGiven")]
+    pub fn is_given(&self, row: usize, col: usize) -> bool {
+        self.inner
+            .board()
+            .get(row, col)
+            .is_some_and(|c| c.is_given())
+    }
+
+    #[wasm_bindgen(js_name = "isSolved")]
+    pub fn is_solved(&self) -> bool {
+        self.inner.is_solved()
+    }
+
+    /// Compares entries against the solution.
+    /// @returns `{ wrong: [row, col][], correct, empty, editable, completion }`
+    pub fn check(&self) -> Result<JsValue, String> {
+        let report = self.inner.check();
+        let progress = Progress {
+            completion: report.completion(),
+            wrong: report.wrong,
+            correct: report.correct,
+            empty: report.empty,
+            editable: report.editable,
+        };
+        serde_wasm_bindgen::to_value(&progress).map_err(|e| e.to_string())
+    }
+
+    /// Suggests the next placement and charges it to the session.
+    /// `level` is `"cell"` (position only) or `"value"` (the default).
+    /// @returns `{ row, col, value }` (no `value` for cell hints) or `null`
+    pub fn hint(&mut self, level: Option<String>) -> Result<JsValue, String> {
+        let level = match level.as_deref().unwrap_or("value") {
+            "cell" => HintLevel::Cell,
+            "value" => HintLevel::Value,
+            other => return Err(format!("Invalid hint level: {other}")),
+        };
+        Ok(match self.inner.hint(level)? {
+            Some(h) => {
+                let obj = make_hint_obj(h.row, h.col, h.value.unwrap_or(0));
+                if h.value.is_none() {
+                    js_sys::Reflect::delete_property(obj.unchecked_ref(), &"value".into()).unwrap();
+                }
+                obj
+            }
+            None => JsValue::NULL,
+        })
+    }
+
+    #[wasm_bindgen(js_name = "hintsUsed")]
+    pub fn hints_used(&self) -> usize {
+        self.inner.hints_used()
+    }
+
+    /// Hints still allowed, or `undefined` when unlimited.
+    #[wasm_bindgen(js_name = "hintsLeft")]
+    pub fn hints_left(&self) -> Option<usize> {
+        self.inner.hints_left()
+    }
+
+    /// Caps the hints this session may take; omit to remove the cap.
+    #[wasm_bindgen(js_name = "setHintLimit")]
+    pub fn set_hint_limit(&mut self, limit: Option<usize>) {
+        self.inner.set_hint_limit(limit);
+    }
+
+    pub fn pause(&mut self) {
+        self.inner.pause();
+    }
+
+    pub fn resume(&mut self) {
+        self.inner.resume();
+    }
+
+    /// Credits play time measured by the host, in milliseconds.
+    /// Browsers give Rust no clock of its own, so call this from a JS timer.
+    pub fn advance(&mut self, ms: f64) {
+        self.inner
+            .timer_mut()
+            .advance(Duration::from_secs_f64(ms.max(0.0) / 1000.0));
+    }
+
+    /// Time played in milliseconds, excluding pauses.
+    #[wasm_bindgen(js_name = "elapsedMs")]
+    pub fn elapsed_ms(&self) -> f64 {
+        self.inner.elapsed().as_secs_f64() * 1000.0
+    }
+
+    /// Score from time, mistakes and hints used.
+    pub fn score(&self) -> f64 {
+        self.inner.score() as f64
+    }
+
+    /// Repeated digits on the current board, shaped like `getConflicts`.
+    pub fn conflicts(&self) -> Result<JsValue, String> {
+        conflicts_to_js(self.inner.board())
+    }
+
+    /// Number of wrong values entered this session.
+    pub fn mistakes(&self) -> usize {
+        self.inner.mistakes()
+    }
+
+    /// Current board as compact string.
+    pub fn board(&self) -> String {
+        self.inner.board().to_string_compact()
+    }
+
+    /// Current board as `number[][]`.
+    pub fn grid(&self) -> Result<JsValue, String> {
+        serde_wasm_bindgen::to_value(&to_grid(self.inner.board())).map_err(|e| e.to_string())
+    }
+}
+
+/// A digit repeated within a house, as returned by `getConflicts`.
+#[derive(Serialize, Deserialize)]
+pub struct ConflictInfo {
+    pub house: String,
+    pub index: usize,
+    pub value: u8,
+    pub cells: Vec<(usize, usize)>,
+}
+
+fn conflicts_to_js(sudoku: &Sudoku) -> Result<JsValue, String> {
+    let conflicts: Vec<ConflictInfo> = sudoku
+        .conflicts()
+        .into_iter()
+        .map(|c| {
+            let (house, index) = match c.house {
+                House::Row(i) => ("row", i),
+                House::Col(i) => ("col", i),
+                House::Box(i) => ("box", i),
+                House::Diagonal(i) => ("diagonal", i),
+                House::Window(i) => ("window", i),
+                House::Group(i) => ("group", i),
+                House::Cage(i) => ("cage", i),
+            };
+            ConflictInfo {
+                house: house.into(),
+                index,
+                value: c.value,
+                cells: c.cells,
+            }
+        })
+        .collect();
+    serde_wasm_bindgen::to_value(&conflicts).map_err(|e| e.to_string())
+}
+
 impl From<Difficulty> for SodoDifficulty {
     fn from(d: Difficulty) -> Self {
         match d {
@@ -266,11 +572,8 @@ fn check_grid_format(grid: &Grid) -> Result<(), String> {
 }
 
 fn to_grid(sudoku: &Sudoku) -> Grid {
-    sudoku
-        .grid
-        .iter()
-        .map(|row| row.iter().map(|c| c.value().unwrap_or(0)).collect())
-        .collect()
+    let values: Vec<u8> = sudoku.cells().map(|c| c.value().unwrap_or(0)).collect();
+    values.chunks(sudoku.size()).map(<[u8]>::to_vec).collect()
 }
 
 fn from_grid(grid: &Grid) -> Result<Sudoku, String> {
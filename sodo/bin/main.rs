@@ -22,6 +22,9 @@ enum Command {
         /// Grid size
         #[arg(short, long, default_value = "9")]
         size: usize,
+        /// Treat input as one puzzle per line and print compact solutions
+        #[arg(short, long)]
+        batch: bool,
     },
     /// Generate a new puzzle
     #[command(visible_alias = "g")]
@@ -54,6 +57,15 @@ enum Command {
         #[arg(short, long, default_value = "9")]
         size: usize,
     },
+    /// Rate a puzzle and report its properties
+    #[command(visible_alias = "a")]
+    Analyze {
+        /// Puzzle string
+        puzzle: String,
+        /// Grid size
+        #[arg(short, long, default_value = "9")]
+        size: usize,
+    },
 }
 
 #[derive(Clone, ValueEnum)]
@@ -79,7 +91,12 @@ fn main() {
     let cli = Cli::parse();
 
     match cli.command {
-        Command::Solve { puzzle, file, size } => solve(puzzle, file, size),
+        Command::Solve {
+            puzzle,
+            file,
+            size,
+            batch,
+        } => solve(puzzle, file, size, batch),
         Command::Generate { size, difficulty } => generate(size, difficulty.into()),
         Command::Validate {
             puzzle,
@@ -87,10 +104,11 @@ fn main() {
             check,
         } => validate(&puzzle, size, check),
         Command::Hint { puzzle, size } => hint(&puzzle, size),
+        Command::Analyze { puzzle, size } => analyze(&puzzle, size),
     }
 }
 
-fn solve(puzzle: Option<String>, file: Option<PathBuf>, size: usize) {
+fn solve(puzzle: Option<String>, file: Option<PathBuf>, size: usize, batch: bool) {
     let input = match (puzzle, file) {
         (Some(p), _) => p,
         (_, Some(f)) => fs::read_to_string(&f).unwrap_or_else(|e| {
@@ -103,6 +121,10 @@ fn solve(puzzle: Option<String>, file: Option<PathBuf>, size: usize) {
         }
     };
 
+    if batch {
+        return solve_batch(&input, size);
+    }
+
     let sudoku = parse(input.trim(), size);
     println!("Puzzle:\n{sudoku}");
 
@@ -111,8 +133,17 @@ fn solve(puzzle: Option<String>, file: Option<PathBuf>, size: usize) {
         Ok((solution, stats)) => {
             println!("Solution:\n{solution}");
             println!(
-                "Stats: {} iters, {} cells, {} backtracks",
-                stats.iterations, stats.cells_filled, stats.backtracks
+                "Stats: {} iters, {} cells, {} eliminations, {} backtracks in {:.2?} ({:.0} nodes/s)",
+                stats.iterations,
+                stats.cells_filled,
+                stats.eliminations,
+                stats.backtracks,
+                stats.elapsed,
+                stats.nodes_per_sec()
+            );
+            println!(
+                "Phases: {:.2?} logic, {:.2?} search, {} guesses, depth {}",
+                stats.logic_time, stats.search_time, stats.guesses, stats.max_depth
             );
         }
         Err(e) => {
@@ -122,6 +153,31 @@ fn solve(puzzle: Option<String>, file: Option<PathBuf>, size: usize) {
     }
 }
 
+fn solve_batch(input: &str, size: usize) {
+    let (numbers, lines): (Vec<usize>, Vec<&str>) = input
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .unzip();
+
+    let mut failed = false;
+    let results = Solver::new().solve_many(&lines, size);
+    for (n, result) in numbers.into_iter().zip(results) {
+        match result {
+            Ok(solution) => println!("{solution}"),
+            Err(e) => {
+                eprintln!("Line {}: {e}", n + 1);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
 fn generate(size: usize, difficulty: Difficulty) {
     let mut solver = Solver::new();
     match solver.generate(size, difficulty) {
@@ -140,8 +196,10 @@ fn validate(puzzle: &str, size: usize, check_solvable: bool) {
     let sudoku = parse(puzzle, size);
     println!("{sudoku}");
 
-    if !sudoku.is_valid() {
+    let report = sudoku.validate_detailed();
+    if !report.is_ok() {
         println!("Invalid!");
+        print!("{report}");
         process::exit(1);
     }
 
@@ -169,11 +227,22 @@ fn hint(puzzle: &str, size: usize) {
     let solver = Solver::new();
 
     match solver.hint(&sudoku) {
-        Some((r, c, v)) => println!("Place {v} at row {}, col {}", r + 1, c + 1),
+        Some((r, c, v)) => println!(
+            "Place {} at row {}, col {}",
+            Sudoku::symbol(v).unwrap_or('?'),
+            r + 1,
+            c + 1
+        ),
         None => println!("No hint available"),
     }
 }
 
+fn analyze(puzzle: &str, size: usize) {
+    let sudoku = parse(puzzle, size);
+    println!("{sudoku}");
+    print!("{}", Solver::new().analyze(&sudoku));
+}
+
 fn parse(s: &str, size: usize) -> Sudoku {
     Sudoku::from_string(s, size).unwrap_or_else(|e| {
         eprintln!("Invalid puzzle: {e}");
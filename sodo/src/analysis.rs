@@ -0,0 +1,564 @@
+//! Whole-puzzle analysis, for "about this puzzle" screens.
+
+use crate::sodo::{Cell, Mask, Sudoku, SymmetryKind, bit, digits};
+use crate::solver::{Difficulty, Solver, Stats};
+use crate::strategy::{Step, Strategy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Larger backdoors aren't searched for; each extra cell multiplies the work.
+const MAX_BACKDOOR: usize = 2;
+
+/// Step effort at which [`Solver::rate`] adds half its tie-breaker.
+const EFFORT_SCALE: f64 = 20.0;
+
+/// Largest unavoidable sets [`Solver::analyze`] reports.
+const MAX_UNAVOIDABLE: usize = 12;
+
+/// Alternative fillings tried per digit subset when hunting unavoidable sets.
+const MAX_ALTERNATIVES: usize = 1024;
+
+/// Cells of a solution grid whose digits can be rearranged into another valid
+/// solution. Every unique puzzle for that grid has a clue in every such set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UnavoidableSet {
+    /// Cells in the set, as (row, col) in row-major order.
+    pub cells: Vec<(usize, usize)>,
+    /// Digits the set's cells hold, ascending.
+    pub digits: Vec<u8>,
+}
+
+/// Everything [`Solver::analyze`] found out about a puzzle.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Analysis {
+    pub difficulty: Difficulty,
+    /// [`Rating::score`], as [`Solver::rate`] gives it.
+    pub score: f64,
+    /// The logical solve's hardest step by [`Strategy::rating`], the
+    /// first if several tie. `None` if no strategy makes progress.
+    pub hardest: Option<Step>,
+    /// Successful applications of each strategy on the logical solve path.
+    pub techniques: HashMap<String, usize>,
+    pub clues: usize,
+    /// Total cells in the grid.
+    pub cells: usize,
+    pub symmetry: SymmetryKind,
+    /// Number of solutions, counted up to 2.
+    pub solutions: usize,
+    /// Whether the puzzle is unique and every clue is needed to keep it so.
+    pub minimal: bool,
+    /// Fewest cells that must be revealed before the solver's strategies
+    /// finish the puzzle alone. `None` if unsolvable or more than 2.
+    pub backdoor: Option<usize>,
+    /// Unavoidable sets of up to 12 cells in the (first) solution, smallest first.
+    pub unavoidable: Vec<UnavoidableSet>,
+    /// How many of `unavoidable` contain no clue. Non-zero means the clues
+    /// can't single out that solution.
+    pub unhit: usize,
+}
+
+/// A puzzle's score from [`Solver::rate`], finer than its [`Difficulty`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rating {
+    /// The hardest step's [`Strategy::rating`](crate::Strategy::rating),
+    /// or 10 if the strategies can't finish, plus under 0.1 that grows
+    /// with the effort of every step. Higher is harder.
+    pub score: f64,
+    pub difficulty: Difficulty,
+    /// Strategy of the hardest step, if any step was needed.
+    pub hardest: Option<String>,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.2} ({:?})", self.score, self.difficulty)
+    }
+}
+
+/// Who is solving, for [`Analysis::estimate_time`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SkillProfile {
+    /// Plays now and then, mostly with singles.
+    Casual,
+    /// Plays regularly and knows the common techniques.
+    Experienced,
+}
+
+/// A likely range of human solve times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeEstimate {
+    pub low: Duration,
+    pub high: Duration,
+}
+
+impl TimeEstimate {
+    /// Midpoint of the range.
+    pub fn typical(&self) -> Duration {
+        (self.low + self.high) / 2
+    }
+}
+
+impl fmt::Display for TimeEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mins = |d: Duration| (d.as_secs() + 30) / 60;
+        match (mins(self.low).max(1), mins(self.high).max(1)) {
+            (lo, hi) if lo == hi => write!(f, "~{lo} min"),
+            (lo, hi) => write!(f, "{lo}-{hi} min"),
+        }
+    }
+}
+
+impl Analysis {
+    pub fn is_unique(&self) -> bool {
+        self.solutions == 1
+    }
+
+    /// Estimates how long a human would take, from the rating and the number
+    /// of cells to fill.
+    ///
+    /// Per-cell times follow the typical finishing times puzzle apps report
+    /// for each difficulty tier; treat the result as a ballpark. Returns
+    /// `None` for puzzles without a unique solution.
+    pub fn estimate_time(&self, profile: SkillProfile) -> Option<TimeEstimate> {
+        if !self.is_unique() {
+            return None;
+        }
+        // Seconds per empty cell as (low, high).
+        let (low, high) = match self.difficulty {
+            Difficulty::Easy => (6, 10),
+            Difficulty::Medium => (10, 18),
+            Difficulty::Hard => (20, 40),
+            Difficulty::Expert => (40, 90),
+        };
+        let divisor = match profile {
+            SkillProfile::Casual => 1,
+            SkillProfile::Experienced => 3,
+        };
+        let cells = (self.cells - self.clues) as u64;
+        Some(TimeEstimate {
+            low: Duration::from_secs(cells * low / divisor),
+            high: Duration::from_secs(cells * high / divisor),
+        })
+    }
+
+    /// Returns true if the strategies solve the puzzle without guessing.
+    pub fn is_logical(&self) -> bool {
+        self.backdoor == Some(0)
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        writeln!(f, "Difficulty: {:?} ({:.2})", self.difficulty, self.score)?;
+        writeln!(f, "Clues:      {}", self.clues)?;
+        match self.solutions {
+            0 => writeln!(f, "Solutions:  none")?,
+            1 => writeln!(f, "Solutions:  1 (unique)")?,
+            _ => writeln!(f, "Solutions:  multiple")?,
+        }
+        writeln!(f, "Minimal:    {}", yes_no(self.minimal))?;
+        writeln!(f, "Symmetry:   {:?}", self.symmetry)?;
+        match self.backdoor {
+            Some(n) => writeln!(f, "Backdoor:   {n}")?,
+            None => writeln!(f, "Backdoor:   >{MAX_BACKDOOR}")?,
+        }
+        writeln!(f, "Guessing:   {}", yes_no(!self.is_logical()))?;
+        if let Some(step) = &self.hardest {
+            writeln!(f, "Hardest:    {}", step.strategy)?;
+        }
+
+        match self.unavoidable.first() {
+            Some(smallest) => writeln!(
+                f,
+                "Unavoidable: {} sets (smallest {} cells), {} unhit",
+                self.unavoidable.len(),
+                smallest.cells.len(),
+                self.unhit
+            )?,
+            None => writeln!(f, "Unavoidable: none found")?,
+        }
+        if let (Some(casual), Some(experienced)) = (
+            self.estimate_time(SkillProfile::Casual),
+            self.estimate_time(SkillProfile::Experienced),
+        ) {
+            writeln!(f, "Est. time:  {casual} casual, {experienced} experienced")?;
+        }
+
+        let mut techniques: Vec<_> = self.techniques.iter().collect();
+        techniques.sort();
+        write!(f, "Techniques:")?;
+        if techniques.is_empty() {
+            write!(f, " none")?;
+        }
+        for (i, (name, n)) in techniques.into_iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(f, "{sep}{name} x{n}")?;
+        }
+        writeln!(f)
+    }
+}
+
+impl Solver {
+    /// Rates a puzzle by the hardest strategy its logical solve needs, or
+    /// [`Difficulty::Expert`] if the strategies can't finish it. Clue
+    /// counts and search effort play no part.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(Solver::new().grade(&puzzle), Difficulty::Easy);
+    /// ```
+    pub fn grade(&self, sudoku: &Sudoku) -> Difficulty {
+        let mut stats = Stats::default();
+        let logical = sudoku.is_valid() && self.apply_strategies(&mut sudoku.clone(), &mut stats);
+        self.rating(logical, &stats)
+    }
+
+    /// Scores a puzzle by its hardest step on the logical solve, with the
+    /// effort of the rest breaking ties, so puzzles of one [`Difficulty`]
+    /// sort among themselves. Puzzles the strategies can't finish score
+    /// 10 and up.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// let mut solver = Solver::new();
+    /// let rating = solver.rate(&puzzle);
+    /// assert_eq!(rating.difficulty, Difficulty::Easy);
+    /// assert!(rating.score >= 1.5 && rating.score < 2.5);
+    ///
+    /// let hard = solver.generate_seeded(9, Difficulty::Hard, 1).unwrap();
+    /// assert!(solver.rate(&hard).score > rating.score);
+    /// ```
+    pub fn rate(&self, sudoku: &Sudoku) -> Rating {
+        let mut stats = Stats::default();
+        let logical = sudoku.is_valid() && self.apply_strategies(&mut sudoku.clone(), &mut stats);
+        self.score(logical, &stats)
+    }
+
+    /// The rating of a solve that went as `stats` say, finishing if `logical`.
+    fn score(&self, logical: bool, stats: &Stats) -> Rating {
+        let used: Vec<(&dyn Strategy, usize)> = self
+            .strategies
+            .iter()
+            .filter_map(|s| Some((s.as_ref(), *stats.strategies_used.get(s.name())?)))
+            .collect();
+        let hardest = used
+            .iter()
+            .map(|&(s, _)| s)
+            .max_by(|a, b| a.rating().total_cmp(&b.rating()));
+        let effort: f64 = used.iter().map(|&(s, n)| s.rating() * n as f64).sum();
+        let base = match hardest {
+            _ if !logical => 10.0,
+            Some(s) => s.rating(),
+            None => 0.0,
+        };
+        Rating {
+            score: base + 0.1 * effort / (effort + EFFORT_SCALE),
+            difficulty: self.rating(logical, stats),
+            hardest: hardest.map(|s| s.name().to_string()),
+        }
+    }
+
+    /// The grade of a solve that went as `stats` say, finishing if `logical`.
+    fn rating(&self, logical: bool, stats: &Stats) -> Difficulty {
+        if !logical {
+            return Difficulty::Expert;
+        }
+        self.strategies
+            .iter()
+            .filter(|s| stats.strategies_used.contains_key(s.name()))
+            .map(|s| s.difficulty())
+            .max()
+            .unwrap_or(Difficulty::Easy)
+    }
+
+    /// Rates a puzzle and gathers technique counts, the hardest step, clue
+    /// count, symmetry, uniqueness, minimality, backdoor size and whether
+    /// it takes guessing in one report.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// let analysis = Solver::new().analyze(&puzzle);
+    /// assert_eq!(analysis.clues, 30);
+    /// assert!(analysis.is_unique() && analysis.is_logical());
+    /// assert_eq!(analysis.difficulty, Difficulty::Easy);
+    /// assert!(analysis.techniques.contains_key("Naked Singles"));
+    /// assert_eq!(analysis.hardest.unwrap().strategy, "Naked Singles");
+    /// ```
+    ///
+    /// Minimality and backdoor search cost a solution count per clue and a
+    /// logical solve per candidate reveal, so this is much slower than `solve`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(clues = sudoku.size() * sudoku.size() - sudoku.empty_count()))
+    )]
+    pub fn analyze(&self, sudoku: &Sudoku) -> Analysis {
+        let valid = sudoku.is_valid();
+        let solutions = if valid {
+            self.count_solutions(sudoku, Some(2))
+        } else {
+            0
+        };
+
+        let mut logic = sudoku.clone();
+        let mut stats = Stats::default();
+        let logical = valid && self.apply_strategies(&mut logic, &mut stats);
+
+        let solution = if logical {
+            Some(logic.clone())
+        } else if solutions > 0 {
+            let mut solution = sudoku.clone();
+            self.run(&mut solution, &mut Stats::default())
+                .ok()
+                .map(|_| solution)
+        } else {
+            None
+        };
+
+        let backdoor = match &solution {
+            _ if logical => Some(0),
+            Some(s) if logic.is_valid() => self.backdoor(&logic, s, MAX_BACKDOOR),
+            _ => None,
+        };
+
+        let rated = |step: &Step| {
+            self.strategies
+                .iter()
+                .find(|s| s.name() == step.strategy)
+                .map_or(0.0, |s| s.rating())
+        };
+        let hardest = valid
+            .then(|| self.solve_steps(sudoku))
+            .into_iter()
+            .flatten()
+            .fold(None, |best: Option<Step>, step| match best {
+                Some(best) if rated(&best) >= rated(&step) => Some(best),
+                _ => Some(step),
+            });
+
+        let unavoidable = solution
+            .map(|s| s.unavoidable_sets(MAX_UNAVOIDABLE))
+            .unwrap_or_default();
+        let unhit = unavoidable
+            .iter()
+            .filter(|set| set.cells.iter().all(|&(r, c)| sudoku.cell(r, c).is_empty()))
+            .count();
+
+        Analysis {
+            difficulty: self.rating(logical, &stats),
+            score: self.score(logical, &stats).score,
+            hardest,
+            techniques: stats.strategies_used,
+            clues: sudoku.size() * sudoku.size() - sudoku.empty_count(),
+            cells: sudoku.size() * sudoku.size(),
+            symmetry: sudoku.symmetry(),
+            solutions,
+            minimal: solutions == 1 && self.is_minimal(sudoku),
+            backdoor,
+            unavoidable,
+            unhit,
+        }
+    }
+
+    /// Returns true if removing any single clue leaves several solutions.
+    fn is_minimal(&self, sudoku: &Sudoku) -> bool {
+        let size = sudoku.size();
+        (0..size * size)
+            .map(|i| (i / size, i % size))
+            .filter(|&(r, c)| !sudoku.cell(r, c).is_empty())
+            .all(|(r, c)| {
+                let mut fewer = sudoku.clone();
+                fewer.put(r, c, Cell::Empty);
+                self.count_solutions(&fewer, Some(2)) > 1
+            })
+    }
+
+    /// The puzzle's backdoor size for this solver's strategies: the fewest
+    /// cells whose solution values, once revealed, let the strategies
+    /// finish the rest. 0 if they finish it as it is. `None` if the puzzle
+    /// has no solution or needs more than `max` cells. With several
+    /// solutions, the values are the first solution found.
+    ///
+    /// Choose the strategy set with [`keep_strategies`](Self::keep_strategies),
+    /// such as singles alone for the classic measure. Each size tried costs
+    /// a logical solve per way of choosing that many empty cells, so keep
+    /// `max` small.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let singles = Solver::new()
+    ///     .keep_strategies(&["Naked Singles", "Hidden Singles"])
+    ///     .unwrap();
+    /// let easy = Solver::new().generate_seeded(9, Difficulty::Easy, 1).unwrap();
+    /// assert_eq!(singles.backdoor_size(&easy, 2), Some(0));
+    ///
+    /// let hard = Solver::new().generate_seeded(9, Difficulty::Hard, 1).unwrap();
+    /// assert!(singles.backdoor_size(&hard, 2).is_some_and(|n| n > 0));
+    /// ```
+    pub fn backdoor_size(&self, sudoku: &Sudoku, max: usize) -> Option<usize> {
+        if !sudoku.is_valid() {
+            return None;
+        }
+        let mut logic = sudoku.clone();
+        if self.apply_strategies(&mut logic, &mut Stats::default()) {
+            return Some(0);
+        }
+        let mut solution = sudoku.clone();
+        self.run(&mut solution, &mut Stats::default()).ok()?;
+        self.backdoor(&logic, &solution, max)
+    }
+
+    /// Smallest number of `solution` values to reveal in `board` so the
+    /// strategies finish it, up to `max`.
+    fn backdoor(&self, board: &Sudoku, solution: &Sudoku, max: usize) -> Option<usize> {
+        let size = board.size();
+        let empties: Vec<(usize, usize)> = (0..size * size)
+            .map(|i| (i / size, i % size))
+            .filter(|&(r, c)| board.cell(r, c).is_empty())
+            .collect();
+        (1..=max).find(|&k| self.reveal(board, solution, &empties, k))
+    }
+
+    /// Tries every way of revealing `k` of `empties` in order.
+    fn reveal(
+        &self,
+        board: &Sudoku,
+        solution: &Sudoku,
+        empties: &[(usize, usize)],
+        k: usize,
+    ) -> bool {
+        if k == 0 {
+            return self.apply_strategies(&mut board.clone(), &mut Stats::default());
+        }
+        empties.iter().enumerate().any(|(i, &(r, c))| {
+            let mut next = board.clone();
+            next.put(r, c, solution.cell(r, c));
+            self.reveal(&next, solution, &empties[i + 1..], k - 1)
+        })
+    }
+}
+
+impl Sudoku {
+    /// Finds unavoidable sets of at most `max_size` cells in a solved grid.
+    ///
+    /// Each pair of digits (and, up to 9x9, each triple) is erased and every
+    /// other way of refilling just those cells yields a set; supersets of
+    /// smaller sets are dropped. This finds the small sets that matter in
+    /// practice but isn't exhaustive. Returns nothing unless the grid is solved.
+    pub fn unavoidable_sets(&self, max_size: usize) -> Vec<UnavoidableSet> {
+        if !self.is_solved() {
+            return Vec::new();
+        }
+
+        let n = self.size() as u8;
+        let mut subsets: Vec<Vec<u8>> = Vec::new();
+        for a in 1..=n {
+            for b in a + 1..=n {
+                subsets.push(vec![a, b]);
+                if n <= 9 {
+                    subsets.extend((b + 1..=n).map(|c| vec![a, b, c]));
+                }
+            }
+        }
+
+        let mut found: Vec<Vec<(usize, usize)>> = Vec::new();
+        for subset in subsets {
+            let mask = subset.iter().fold(0, |m, &d| m | bit(d));
+            for set in self.alternatives(mask) {
+                if set.len() <= max_size && !found.contains(&set) {
+                    found.push(set);
+                }
+            }
+        }
+
+        found.sort_by_key(Vec::len);
+        let mut minimal: Vec<Vec<(usize, usize)>> = Vec::new();
+        for set in found {
+            if !minimal.iter().any(|m| m.iter().all(|c| set.contains(c))) {
+                minimal.push(set);
+            }
+        }
+
+        minimal
+            .into_iter()
+            .map(|cells| {
+                let mut digits: Vec<u8> = cells
+                    .iter()
+                    .filter_map(|&(r, c)| self.cell(r, c).value())
+                    .collect();
+                digits.sort_unstable();
+                digits.dedup();
+                UnavoidableSet { cells, digits }
+            })
+            .collect()
+    }
+
+    /// Erases every digit in `mask` and returns, for each other valid
+    /// refilling, the cells that changed.
+    fn alternatives(&self, mask: Mask) -> Vec<Vec<(usize, usize)>> {
+        let size = self.size();
+        let mut board = self.clone();
+        for r in 0..size {
+            for c in 0..size {
+                if self.cell(r, c).value().is_some_and(|v| mask & bit(v) != 0) {
+                    board.put(r, c, Cell::Empty);
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        refill(&mut board, self, &mut out);
+        out
+    }
+}
+
+/// Enumerates completions of `board`, recording how each differs from `original`.
+fn refill(board: &mut Sudoku, original: &Sudoku, out: &mut Vec<Vec<(usize, usize)>>) {
+    if out.len() >= MAX_ALTERNATIVES {
+        return;
+    }
+    let Some((r, c)) = Solver::find_mrv_cell(board) else {
+        let size = board.size();
+        let diff: Vec<(usize, usize)> = (0..size * size)
+            .map(|i| (i / size, i % size))
+            .filter(|&(r, c)| board.cell(r, c).value() != original.cell(r, c).value())
+            .collect();
+        if !diff.is_empty() {
+            out.push(diff);
+        }
+        return;
+    };
+
+    for v in digits(board.candidate_mask(r, c)) {
+        board.put(r, c, Cell::Filled(v));
+        refill(board, original, out);
+    }
+    board.put(r, c, Cell::Empty);
+}
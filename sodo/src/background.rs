@@ -0,0 +1,131 @@
+//! Puzzle generation off the async executor, for web services.
+//!
+//! Requires a tokio runtime: work runs on its blocking pool.
+
+use crate::options::CancelToken;
+use crate::sodo::Sudoku;
+use crate::solver::{Difficulty, Solver};
+use std::future::Future;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// What to generate with [`Solver::generate_async`].
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    pub size: usize,
+    pub difficulty: Difficulty,
+    progress: Option<UnboundedSender<GenerateProgress>>,
+}
+
+impl GenerateOptions {
+    pub fn new(size: usize, difficulty: Difficulty) -> Self {
+        Self {
+            size,
+            difficulty,
+            progress: None,
+        }
+    }
+
+    /// Reports progress on `tx`. Sends are dropped once the receiver closes.
+    pub fn progress(mut self, tx: UnboundedSender<GenerateProgress>) -> Self {
+        self.progress = Some(tx);
+        self
+    }
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self::new(9, Difficulty::Medium)
+    }
+}
+
+/// A step of an in-flight [`Solver::generate_async`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerateProgress {
+    /// A full solution grid is ready; clue removal starts. Sent once per
+    /// grid: more than once only when a clue mask or required technique
+    /// sends the generator back for a fresh grid.
+    Filled,
+    /// `removed` of `target` cells have been cleared. A removal pass that
+    /// misses the difficulty starts over from zero on the same grid.
+    Removing { removed: usize, target: usize },
+}
+
+/// Flags the blocking job to stop when the future is dropped.
+struct CancelOnDrop(CancelToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+impl Solver {
+    /// Generates a puzzle on tokio's blocking pool.
+    ///
+    /// The job starts immediately, so this must be called from within a
+    /// runtime. Dropping the future cancels the job at its next step, be
+    /// it in filling the grid or removing clues, and frees the pool thread.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, GenerateOptions, GenerateProgress, Solver};
+    /// use tokio::sync::mpsc::unbounded_channel;
+    ///
+    /// let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// rt.block_on(async {
+    ///     let (tx, mut rx) = unbounded_channel();
+    ///     let options = GenerateOptions::new(9, Difficulty::Hard).progress(tx);
+    ///     let puzzle = Solver::new().seed(3).generate_async(options).await.unwrap();
+    ///     assert!(puzzle.empty_count() > 0);
+    ///     let mut filled = 0;
+    ///     while let Some(p) = rx.recv().await {
+    ///         filled += usize::from(p == GenerateProgress::Filled);
+    ///     }
+    ///     assert_eq!(filled, 1);
+    ///
+    ///     let (tx, mut rx) = unbounded_channel();
+    ///     let options = GenerateOptions::new(16, Difficulty::Hard).progress(tx);
+    ///     drop(Solver::new().generate_async(options));
+    ///     // The job hangs up without filling a grid.
+    ///     assert_eq!(rx.recv().await, None);
+    /// });
+    /// ```
+    pub fn generate_async(
+        mut self,
+        options: GenerateOptions,
+    ) -> impl Future<Output = Result<Sudoku, String>> + Send + 'static {
+        let token = CancelToken::new();
+        let guard = CancelOnDrop(token.clone());
+        let GenerateOptions {
+            size,
+            difficulty,
+            progress,
+        } = options;
+
+        let job = tokio::task::spawn_blocking(move || {
+            // Searches check the token too, so filling a grid stops as well.
+            self.cancel_with(token.clone());
+            let send = |p| {
+                if let Some(tx) = &progress {
+                    let _ = tx.send(p);
+                }
+            };
+            self.generate_with(
+                size,
+                difficulty,
+                &mut || send(GenerateProgress::Filled),
+                &mut |removed, target| {
+                    if token.is_cancelled() {
+                        return false;
+                    }
+                    send(GenerateProgress::Removing { removed, target });
+                    true
+                },
+            )
+        });
+
+        async move {
+            let _guard = guard;
+            job.await.map_err(|e| e.to_string())?
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Sets of digits, one bit each.
+
+use crate::sodo::{Mask, bit, span};
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+
+/// A set of digits, such as a cell's candidates from
+/// [`Sudoku::candidates`](crate::Sudoku::candidates). Digit `v` is bit
+/// `v - 1`, so sets are `Copy` and set operations are single instructions,
+/// for grids up to [`MAX_SIZE`](crate::MAX_SIZE).
+///
+/// ```
+/// use sodo::CandidateSet;
+///
+/// let a: CandidateSet = [1, 2, 5].into_iter().collect();
+/// let b: CandidateSet = [2, 5, 7].into_iter().collect();
+/// assert_eq!((a & b).iter().collect::<Vec<_>>(), [2, 5]);
+/// assert_eq!((a | b).len(), 4);
+/// assert_eq!((a - b).single(), Some(1));
+/// assert!(a.contains(5) && !a.contains(7));
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CandidateSet(Mask);
+
+impl CandidateSet {
+    /// No digits.
+    pub const EMPTY: Self = Self(0);
+
+    /// Every digit of a grid `size` wide.
+    pub fn full(size: usize) -> Self {
+        Self(span(1, size))
+    }
+
+    /// The set whose bit `v - 1` holds digit `v`.
+    pub fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Bit `v - 1` for each digit `v`.
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, value: u8) -> bool {
+        (1..=64).contains(&value) && self.0 & bit(value) != 0
+    }
+
+    /// Adds `value`, a digit from 1 to 64. Returns true if it was new;
+    /// values out of that range are ignored and return false.
+    pub fn insert(&mut self, value: u8) -> bool {
+        if !(1..=64).contains(&value) {
+            return false;
+        }
+        let fresh = !self.contains(value);
+        self.0 |= bit(value);
+        fresh
+    }
+
+    /// Removes `value`. Returns true if it was there.
+    pub fn remove(&mut self, value: u8) -> bool {
+        let held = self.contains(value);
+        if held {
+            self.0 &= !bit(value);
+        }
+        held
+    }
+
+    /// The only digit, if there is exactly one.
+    pub fn single(self) -> Option<u8> {
+        (self.len() == 1).then(|| self.0.trailing_zeros() as u8 + 1)
+    }
+
+    /// The smallest digit.
+    pub fn first(self) -> Option<u8> {
+        self.iter().next()
+    }
+
+    /// Digits in ascending order.
+    pub fn iter(self) -> Digits {
+        Digits(self.0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        self | other
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        self & other
+    }
+
+    /// Digits in `self` but not in `other`.
+    pub fn difference(self, other: Self) -> Self {
+        self - other
+    }
+
+    pub fn is_subset(self, other: Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+}
+
+impl fmt::Debug for CandidateSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl BitOr for CandidateSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitAnd for CandidateSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+}
+
+impl Sub for CandidateSet {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl BitOrAssign for CandidateSet {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAndAssign for CandidateSet {
+    fn bitand_assign(&mut self, other: Self) {
+        self.0 &= other.0;
+    }
+}
+
+impl SubAssign for CandidateSet {
+    fn sub_assign(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+}
+
+impl IntoIterator for CandidateSet {
+    type Item = u8;
+    type IntoIter = Digits;
+
+    fn into_iter(self) -> Digits {
+        Digits(self.0)
+    }
+}
+
+/// Digits of a [`CandidateSet`], in ascending order.
+#[derive(Debug, Clone)]
+pub struct Digits(Mask);
+
+impl Iterator for Digits {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        (self.0 != 0).then(|| {
+            let v = self.0.trailing_zeros() as u8 + 1;
+            self.0 &= self.0 - 1;
+            v
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.0.count_ones() as usize;
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for Digits {}
+
+impl FromIterator<u8> for CandidateSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(values: I) -> Self {
+        let mut set = Self::EMPTY;
+        set.extend(values);
+        set
+    }
+}
+
+impl Extend<u8> for CandidateSet {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, values: I) {
+        for v in values {
+            self.insert(v);
+        }
+    }
+}
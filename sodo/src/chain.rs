@@ -0,0 +1,385 @@
+//! Alternating inference chains over candidates.
+//!
+//! A strong link between two candidates means at least one is true: the only
+//! two spots for a digit in a house, or the two candidates of a bivalue cell.
+//! A weak link means at most one is: the same digit in cells that see each
+//! other, or two digits in one cell. A chain alternating strong and weak links,
+//! starting and ending strong, proves one of its ends true, so any candidate
+//! weakly linked to both ends is false.
+
+use crate::Sudoku;
+use crate::sodo::digits;
+use crate::solver::Difficulty;
+use crate::strategy::{Finding, Pos, Strategy, eliminate_all, empty_cells, sees, strong_links};
+
+/// Longest chain searched for by default, in candidates.
+const MAX_LENGTH: usize = 12;
+
+/// Which links a chain may use.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// One digit, strong links between cells only.
+    X,
+    /// Strong links inside bivalue cells, weak links between cells.
+    Xy,
+    /// Anything.
+    Any,
+}
+
+/// X-Chain: a single-digit chain over the strong links between cells.
+pub struct XChain {
+    /// Longest chain tried, in candidates.
+    pub max_length: usize,
+}
+
+/// XY-Chain: bivalue cells linked through shared digits.
+pub struct XyChain {
+    /// Longest chain tried, in candidates.
+    pub max_length: usize,
+}
+
+/// Alternating inference chain mixing every kind of link.
+pub struct Aic {
+    /// Longest chain tried, in candidates.
+    pub max_length: usize,
+}
+
+macro_rules! chain_strategy {
+    ($ty:ident, $name:literal, $kind:expr, $difficulty:expr, $rating:literal) => {
+        impl Default for $ty {
+            fn default() -> Self {
+                Self {
+                    max_length: MAX_LENGTH,
+                }
+            }
+        }
+
+        impl Strategy for $ty {
+            fn name(&self) -> &'static str {
+                $name
+            }
+
+            fn difficulty(&self) -> Difficulty {
+                $difficulty
+            }
+
+            fn rating(&self) -> f64 {
+                $rating
+            }
+
+            fn apply(&self, sudoku: &mut Sudoku) -> bool {
+                self.find(sudoku).is_some()
+            }
+
+            /// Highlights the chain's cells, and draws the chain.
+            fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+                let chain = find_chain(sudoku, $kind, self.max_length)?;
+                let mut highlights: Vec<Pos> = chain.nodes.iter().map(|&(p, _)| p).collect();
+                highlights.sort_unstable();
+                highlights.dedup();
+                eliminate_all(sudoku, chain.ruled_out).then(|| Finding {
+                    highlights,
+                    chain: chain.nodes.iter().map(|&((r, c), d)| (r, c, d)).collect(),
+                    ..Finding::default()
+                })
+            }
+        }
+    };
+}
+
+chain_strategy!(XChain, "X-Chain", Kind::X, Difficulty::Expert, 6.5);
+chain_strategy!(XyChain, "XY-Chain", Kind::Xy, Difficulty::Expert, 6.6);
+chain_strategy!(Aic, "AIC", Kind::Any, Difficulty::Expert, 7.0);
+
+/// A productive chain from [`find_chain`].
+struct Chain {
+    nodes: Vec<(Pos, u8)>,
+    ruled_out: Vec<(Pos, u8)>,
+}
+
+/// The link graph over every remaining candidate.
+struct Graph {
+    nodes: Vec<(Pos, u8)>,
+    strong: Vec<Vec<usize>>,
+    weak: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    fn new(sudoku: &Sudoku, kind: Kind) -> Self {
+        let n = sudoku.size();
+        let cells = empty_cells(sudoku);
+        let nodes: Vec<(Pos, u8)> = cells
+            .iter()
+            .flat_map(|&(r, c)| digits(sudoku.candidate_mask(r, c)).map(move |d| ((r, c), d)))
+            .collect();
+        let mut index = vec![usize::MAX; n * n * n];
+        for (i, &((r, c), d)) in nodes.iter().enumerate() {
+            index[(r * n + c) * n + d as usize - 1] = i;
+        }
+        let at = |(r, c): Pos, d: u8| index[(r * n + c) * n + d as usize - 1];
+
+        let mut strong = vec![Vec::new(); nodes.len()];
+        let mut link = |a: usize, b: usize| {
+            if !strong[a].contains(&b) {
+                strong[a].push(b);
+                strong[b].push(a);
+            }
+        };
+        if kind != Kind::Xy {
+            for d in 1..=n as u8 {
+                for (_, p, q) in strong_links(sudoku, d) {
+                    link(at(p, d), at(q, d));
+                }
+            }
+        }
+        if kind != Kind::X {
+            for &(r, c) in &cells {
+                let mask = sudoku.candidate_mask(r, c);
+                if mask.count_ones() == 2 {
+                    let mut ds = digits(mask);
+                    let (x, y) = (ds.next().unwrap(), ds.next().unwrap());
+                    link(at((r, c), x), at((r, c), y));
+                }
+            }
+        }
+
+        let weak = (0..nodes.len())
+            .map(|i| {
+                (0..nodes.len())
+                    .filter(|&j| {
+                        let ((a, x), (b, y)) = (nodes[i], nodes[j]);
+                        match kind {
+                            Kind::X | Kind::Xy => x == y && sees(sudoku, a, b),
+                            Kind::Any => weakly_linked(sudoku, nodes[i], nodes[j]),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            nodes,
+            strong,
+            weak,
+        }
+    }
+}
+
+/// At most one of two distinct candidates can be true.
+fn weakly_linked(sudoku: &Sudoku, (a, x): (Pos, u8), (b, y): (Pos, u8)) -> bool {
+    if a == b {
+        x != y
+    } else {
+        x == y && sees(sudoku, a, b)
+    }
+}
+
+/// The shortest productive chain from the first start that has one.
+fn find_chain(sudoku: &Sudoku, kind: Kind, max_length: usize) -> Option<Chain> {
+    let graph = Graph::new(sudoku, kind);
+    let count = graph.nodes.len();
+
+    for start in 0..count {
+        if graph.strong[start].is_empty() {
+            continue;
+        }
+        // Breadth-first over (candidate, last link was strong), keeping the
+        // parent of each state to rebuild the chain.
+        let mut parent = vec![[usize::MAX; 2]; count];
+        let mut frontier = vec![(start, false)];
+        parent[start][0] = start;
+        let mut length = 1;
+
+        while !frontier.is_empty() && length < max_length {
+            let mut next = Vec::new();
+            for &(node, strong) in &frontier {
+                let links = if strong {
+                    &graph.weak[node]
+                } else {
+                    &graph.strong[node]
+                };
+                for &to in links {
+                    let slot = usize::from(!strong);
+                    if parent[to][slot] != usize::MAX {
+                        continue;
+                    }
+                    parent[to][slot] = node;
+                    next.push((to, !strong));
+                }
+            }
+            length += 1;
+
+            for &(end, strong) in &next {
+                if !strong || end == start {
+                    continue;
+                }
+                let ends = (graph.nodes[start], graph.nodes[end]);
+                let ruled_out: Vec<(Pos, u8)> = graph
+                    .nodes
+                    .iter()
+                    .copied()
+                    .filter(|&c| {
+                        c != ends.0
+                            && c != ends.1
+                            && weakly_linked(sudoku, c, ends.0)
+                            && weakly_linked(sudoku, c, ends.1)
+                    })
+                    .collect();
+                if ruled_out.is_empty() {
+                    continue;
+                }
+
+                let mut nodes = vec![graph.nodes[end]];
+                let (mut at, mut slot) = (end, 1);
+                while at != start || slot != 0 {
+                    at = parent[at][slot];
+                    slot ^= 1;
+                    nodes.push(graph.nodes[at]);
+                }
+                nodes.reverse();
+                return Some(Chain { nodes, ruled_out });
+            }
+            frontier = next;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::tests::check;
+
+    /// A chain of strong links on 6 clears it from r1c6.
+    #[test]
+    fn x_chain() {
+        let finding = check(
+            &XChain::default(),
+            ".5.............7....23.9....84......1..9..3.5...4.19.......5.6...3.2..5.4..1.3.8.",
+            ".59.1.83.3.1.5.79.8.23.951.98453267112.98.34553.4.19282.87.5.63..3.2..5.4.51.328.",
+            "
+        67 26 467 246
+        46 268 468 246
+        467 467 46
+
+        67 67
+        67 67
+        19 49 14
+        67 1679 68 48 14 79
+        679 69 79
+    ",
+            &[(0, 5, 6)],
+        );
+        assert_eq!(
+            finding.highlights,
+            [(0, 0), (4, 5), (5, 4), (7, 0), (7, 3), (8, 4)]
+        );
+        assert_eq!(
+            finding.chain,
+            [
+                (0, 0, 6),
+                (7, 0, 6),
+                (7, 3, 6),
+                (8, 4, 6),
+                (5, 4, 6),
+                (4, 5, 6)
+            ]
+        );
+    }
+
+    /// Bivalue cells linked end to end clear 8 from r3c5.
+    #[test]
+    fn xy_chain() {
+        let finding = check(
+            &XyChain::default(),
+            ".....1....7.......9.17..5....2.5.4.........8...8.4.9...6...9........2.38...47329.",
+            "52...1....7...5...9.17.45626.29584...49..7685758.4692.263..9754497562138..5473296",
+            "
+        46 68 389 38 47 79
+        38 46 26 29 38 14 19
+        38 38
+        13 17 137
+        13 123 12
+        13 13
+        18 18
+
+        18 18
+    ",
+            &[(2, 4, 8)],
+        );
+        assert_eq!(
+            finding.highlights,
+            [(0, 2), (0, 3), (0, 7), (2, 1), (3, 1), (3, 7)]
+        );
+        assert_eq!(
+            finding.chain,
+            [
+                (0, 3, 8),
+                (0, 3, 6),
+                (0, 2, 6),
+                (0, 2, 4),
+                (0, 7, 4),
+                (0, 7, 7),
+                (3, 7, 7),
+                (3, 7, 1),
+                (3, 1, 1),
+                (3, 1, 3),
+                (2, 1, 3),
+                (2, 1, 8)
+            ]
+        );
+    }
+
+    /// A chain mixing cell and house links clears 5 from r2c1.
+    #[test]
+    fn aic() {
+        let finding = check(
+            &Aic::default(),
+            "...........84....124......6..1..57......8......39.45.81......239..5.8..77.....86.",
+            ".16...4.9..84..2.124....3.68.1..57.445..8.6.26.39.4518185.4.923962538147734...865",
+            "
+        35 2378 257 237 578
+        35 79 5679 3679 57
+        79 178 159 179 578
+        29 236 26 39
+        79 137 137 39
+        27 27
+        67 67
+
+        12 129 129
+    ",
+            &[(1, 0, 5)],
+        );
+        assert_eq!(
+            finding.highlights,
+            [
+                (1, 0),
+                (1, 1),
+                (1, 4),
+                (1, 5),
+                (1, 7),
+                (3, 1),
+                (3, 4),
+                (5, 1)
+            ]
+        );
+        assert_eq!(
+            finding.chain,
+            [
+                (1, 0, 3),
+                (1, 5, 3),
+                (1, 5, 6),
+                (1, 4, 6),
+                (3, 4, 6),
+                (3, 4, 2),
+                (3, 1, 2),
+                (5, 1, 2),
+                (5, 1, 7),
+                (1, 1, 7),
+                (1, 7, 7),
+                (1, 7, 5)
+            ]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Clues written outside the grid, each about the cells it looks along.
+
+use crate::line::parse_cell;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// What a [`Clue`] says about the cells it looks along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ClueKind {
+    /// The cells add up to the clue's value. Digits may repeat, as far as
+    /// the other rules allow.
+    LittleKiller,
+}
+
+impl ClueKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::LittleKiller => "little-killer",
+        }
+    }
+}
+
+/// A compass direction across the grid, north being up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction {
+    N,
+    Ne,
+    E,
+    Se,
+    S,
+    Sw,
+    W,
+    Nw,
+}
+
+impl Direction {
+    const ALL: [Self; 8] = [
+        Self::N,
+        Self::Ne,
+        Self::E,
+        Self::Se,
+        Self::S,
+        Self::Sw,
+        Self::W,
+        Self::Nw,
+    ];
+
+    /// One step as (rows, cols).
+    pub(crate) fn step(self) -> (isize, isize) {
+        match self {
+            Self::N => (-1, 0),
+            Self::Ne => (-1, 1),
+            Self::E => (0, 1),
+            Self::Se => (1, 1),
+            Self::S => (1, 0),
+            Self::Sw => (1, -1),
+            Self::W => (0, -1),
+            Self::Nw => (-1, -1),
+        }
+    }
+
+    pub(crate) fn is_diagonal(self) -> bool {
+        let (dr, dc) = self.step();
+        dr != 0 && dc != 0
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::N => "n",
+            Self::Ne => "ne",
+            Self::E => "e",
+            Self::Se => "se",
+            Self::S => "s",
+            Self::Sw => "sw",
+            Self::W => "w",
+            Self::Nw => "nw",
+        }
+    }
+}
+
+/// A clue beyond the edge of the grid, looking in `direction` from the
+/// `start` cell to the far edge; see [`Sudoku::with_clues`].
+///
+/// Clues read and print as the kind, the one-based start cell, the
+/// direction and the value:
+///
+/// ```
+/// use sodo::{Clue, Direction};
+///
+/// let clue: Clue = "little-killer r1c2 se 23".parse().unwrap();
+/// assert_eq!(clue, Clue::little_killer((0, 1), Direction::Se, 23));
+/// assert_eq!(clue.cells(4), [(0, 1), (1, 2), (2, 3)]);
+/// assert_eq!(clue.to_string(), "little-killer r1c2 se 23");
+/// ```
+///
+/// [`Sudoku::with_clues`]: crate::Sudoku::with_clues
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct Clue {
+    pub kind: ClueKind,
+    /// First cell looked at, as (row, col), on the edge the clue sits off.
+    pub start: (usize, usize),
+    pub direction: Direction,
+    pub value: u32,
+}
+
+impl Clue {
+    /// A Little Killer sum along a diagonal.
+    pub fn little_killer(start: (usize, usize), direction: Direction, value: u32) -> Self {
+        Self {
+            kind: ClueKind::LittleKiller,
+            start,
+            direction,
+            value,
+        }
+    }
+
+    /// Cells the clue looks along in a grid `size` wide, from `start`.
+    pub fn cells(&self, size: usize) -> Vec<(usize, usize)> {
+        let (dr, dc) = self.direction.step();
+        let mut out = Vec::new();
+        let (mut r, mut c) = self.start;
+        while r < size && c < size {
+            out.push((r, c));
+            r = r.wrapping_add_signed(dr);
+            c = c.wrapping_add_signed(dc);
+        }
+        out
+    }
+
+    /// True if the step back from `start` leaves a grid `size` wide.
+    pub(crate) fn is_outside(&self, size: usize) -> bool {
+        let (dr, dc) = self.direction.step();
+        let (r, c) = self.start;
+        let back = |x: usize, d: isize| x.checked_add_signed(-d).is_none_or(|x| x >= size);
+        r < size && c < size && (back(r, dr) || back(c, dc))
+    }
+}
+
+impl fmt::Display for Clue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (r, c) = self.start;
+        write!(
+            f,
+            "{} r{}c{} {} {}",
+            self.kind.name(),
+            r + 1,
+            c + 1,
+            self.direction.name(),
+            self.value
+        )
+    }
+}
+
+impl FromStr for Clue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let [kind, start, direction, value] = parts[..] else {
+            return Err(format!(
+                "Expected a kind, a cell, a direction and a value in '{s}'"
+            ));
+        };
+        let kind = match kind.to_ascii_lowercase().as_str() {
+            "little-killer" => ClueKind::LittleKiller,
+            _ => return Err(format!("Unknown clue kind '{kind}'")),
+        };
+        let direction = Direction::ALL
+            .into_iter()
+            .find(|d| d.name().eq_ignore_ascii_case(direction))
+            .ok_or(format!("Unknown direction '{direction}'"))?;
+        Ok(Self {
+            kind,
+            start: parse_cell(start)?,
+            direction,
+            value: value
+                .parse()
+                .map_err(|_| format!("Invalid value '{value}'"))?,
+        })
+    }
+}
+
+impl From<Clue> for String {
+    fn from(clue: Clue) -> Self {
+        clue.to_string()
+    }
+}
+
+impl TryFrom<String> for Clue {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, String> {
+        s.parse()
+    }
+}
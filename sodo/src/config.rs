@@ -0,0 +1,146 @@
+//! Rule sets that travel together, for puzzles mixing several variants.
+
+use crate::Sudoku;
+use crate::clue::Clue;
+use crate::line::Line;
+use crate::sodo::{Cage, Mark, Variant};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Every rule a puzzle carries beyond rows, columns and boxes, built up one
+/// rule set at a time.
+///
+/// Apply one with [`Sudoku::with_config`], or give it to a
+/// [`Solver`](crate::Solver) for the grids it builds itself. It is also
+/// how a [`Sudoku`] serializes its rules, next to the grid.
+///
+/// ```
+/// use sodo::{Difficulty, Solver, Sudoku, Variant, VariantConfig};
+///
+/// let config = VariantConfig::new()
+///     .variant(Variant::AntiKnight)
+///     .variant(Variant::Diagonal);
+/// let s = Sudoku::new(9).with_config(&config).unwrap();
+/// assert_eq!(s.config(), config);
+///
+/// let puzzle = Solver::new()
+///     .config(config.clone())
+///     .generate(9, Difficulty::Easy)
+///     .unwrap();
+/// assert_eq!(puzzle.config(), config);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VariantConfig {
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub variants: Vec<Variant>,
+    /// Jigsaw layout, as for [`Sudoku::with_regions`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub regions: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub cages: Vec<Cage>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub lines: Vec<Line>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub clues: Vec<Clue>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub marks: Vec<((usize, usize), Mark)>,
+}
+
+impl VariantConfig {
+    /// No rules beyond the classic ones.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a variant rule, see [`Sudoku::with_variant`].
+    pub fn variant(mut self, variant: Variant) -> Self {
+        if !self.variants.contains(&variant) {
+            self.variants.push(variant);
+        }
+        self
+    }
+
+    /// Sets the jigsaw layout, see [`Sudoku::with_regions`].
+    pub fn regions(mut self, layout: impl Into<String>) -> Self {
+        self.regions = Some(layout.into());
+        self
+    }
+
+    /// Adds Killer cages, see [`Sudoku::with_cages`].
+    pub fn cages(mut self, cages: impl IntoIterator<Item = Cage>) -> Self {
+        self.cages.extend(cages);
+        self
+    }
+
+    /// Adds lines, see [`Sudoku::with_lines`].
+    pub fn lines(mut self, lines: impl IntoIterator<Item = Line>) -> Self {
+        self.lines.extend(lines);
+        self
+    }
+
+    /// Adds outside clues, see [`Sudoku::with_clues`].
+    pub fn clues(mut self, clues: impl IntoIterator<Item = Clue>) -> Self {
+        self.clues.extend(clues);
+        self
+    }
+
+    /// Adds cell marks, see [`Sudoku::with_marks`].
+    pub fn marks(mut self, marks: impl IntoIterator<Item = ((usize, usize), Mark)>) -> Self {
+        self.marks.extend(marks);
+        self
+    }
+
+    /// True if there are no rules beyond the classic ones.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Sudoku {
+    /// Applies every rule in `config`. Its variants join any already in
+    /// force; its layout, cages, lines, clues and marks replace the grid's
+    /// own, save that no layout keeps the boxes as they are.
+    pub fn with_config(self, config: &VariantConfig) -> Result<Self, String> {
+        let mut sudoku = config.variants.iter().fold(self, |s, &v| s.with_variant(v));
+        if let Some(layout) = &config.regions {
+            sudoku = sudoku.with_regions(layout)?;
+        }
+        sudoku
+            .with_cages(config.cages.clone())?
+            .with_lines(config.lines.clone())?
+            .with_clues(config.clues.clone())?
+            .with_marks(config.marks.clone())
+    }
+
+    /// The rules of this grid beyond the classic ones, short of any
+    /// [`Constraint`](crate::Constraint)s.
+    pub fn config(&self) -> VariantConfig {
+        VariantConfig {
+            variants: self.variants().to_vec(),
+            regions: self.regions(),
+            cages: self.cages().to_vec(),
+            lines: self.lines().to_vec(),
+            clues: self.clues().to_vec(),
+            marks: self.marks(),
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Rules a grid can carry on top of its built-in ones.
+
+use crate::sodo::House;
+use crate::{CandidateSet, Sudoku};
+use std::fmt;
+use std::sync::Arc;
+
+/// A rule on the digits of a grid. Register one with
+/// [`Sudoku::with_constraint`] and it holds like the built-in rules: in
+/// candidates, [`Sudoku::can_place`], validity, and the solver.
+///
+/// The solver only ever places digits [`allows`](Self::allows) lets
+/// through, so it must never let through a digit that breaks the rule.
+///
+/// The classic rules ship as constraints too, as [`Rows`], [`Cols`] and
+/// [`Boxes`].
+///
+/// ```
+/// use sodo::{Constraint, Solver, Sudoku};
+///
+/// /// The top-left cell is bigger than the one right of it.
+/// struct Greater;
+///
+/// impl Constraint for Greater {
+///     fn name(&self) -> &'static str {
+///         "Greater"
+///     }
+///
+///     fn allows(&self, sudoku: &Sudoku, row: usize, col: usize, value: u8) -> bool {
+///         let other = |r, c| sudoku.get(r, c).and_then(|cell| cell.value());
+///         match (row, col) {
+///             (0, 0) => other(0, 1).is_none_or(|v| value > v),
+///             (0, 1) => other(0, 0).is_none_or(|v| value < v),
+///             _ => true,
+///         }
+///     }
+///
+///     fn reach(&self, _: &Sudoku, row: usize, col: usize) -> Option<Vec<(usize, usize)>> {
+///         Some(match (row, col) {
+///             (0, 0) | (0, 1) => vec![(0, 0), (0, 1)],
+///             _ => Vec::new(),
+///         })
+///     }
+/// }
+///
+/// let mut s = Sudoku::new(9).with_constraint(Greater);
+/// s.set(0, 1, 5).unwrap();
+/// assert!(!s.can_place(0, 0, 3) && s.can_place(0, 0, 7));
+/// s.set(0, 1, 0).unwrap();
+/// let solution = Solver::new().solve(s).unwrap();
+/// let value = |c| solution.get(0, c).unwrap().value();
+/// assert!(value(0) > value(1));
+/// ```
+pub trait Constraint: Send + Sync {
+    /// Returns the constraint name.
+    fn name(&self) -> &'static str;
+
+    /// Whether (row, col) may hold `value` given the other cells, whatever
+    /// it holds now.
+    fn allows(&self, sudoku: &Sudoku, row: usize, col: usize, value: u8) -> bool;
+
+    /// The digits of `candidates` that (row, col) may hold, as
+    /// [`allows`](Self::allows) says one at a time. Defaults to asking it
+    /// for each; override it when the rule can answer for all at once.
+    fn allowed(
+        &self,
+        sudoku: &Sudoku,
+        row: usize,
+        col: usize,
+        candidates: CandidateSet,
+    ) -> CandidateSet {
+        candidates
+            .into_iter()
+            .filter(|&v| self.allows(sudoku, row, col, v))
+            .collect()
+    }
+
+    /// Cells whose allowed digits may change when (row, col) does, so a
+    /// placement only rechecks those. Defaults to `None`, for any cell.
+    fn reach(&self, _sudoku: &Sudoku, _row: usize, _col: usize) -> Option<Vec<(usize, usize)>> {
+        None
+    }
+
+    /// Whether the placed digits keep the rule. Defaults to every placed
+    /// digit being allowed where it is.
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        let n = sudoku.size();
+        (0..n * n).all(|i| {
+            let (row, col) = (i / n, i % n);
+            sudoku
+                .get(row, col)
+                .and_then(|cell| cell.value())
+                .is_none_or(|v| self.allows(sudoku, row, col, v))
+        })
+    }
+
+    /// Rules out candidates the rule forbids beyond what
+    /// [`allows`](Self::allows) sees, as with
+    /// [`Sudoku::remove_candidate`]. Returns true if progress was made.
+    /// The solver calls it ahead of its strategies. Defaults to nothing.
+    fn propagate(&self, _sudoku: &mut Sudoku) -> bool {
+        false
+    }
+}
+
+/// Rows hold each digit once. Every grid applies it already, through its
+/// own row masks; this is the same rule as a [`Constraint`], for code that
+/// works through the trait alone.
+///
+/// ```
+/// use sodo::{Boxes, Cols, Constraint, Rows, Solver, Sudoku};
+///
+/// let classic = Sudoku::new(4)
+///     .with_constraint(Rows)
+///     .with_constraint(Cols)
+///     .with_constraint(Boxes);
+/// assert_eq!(Solver::new().count_solutions(&classic, None), 288);
+///
+/// let mut s = Sudoku::new(9);
+/// s.set(0, 0, 5).unwrap();
+/// assert!(!Rows.allows(&s, 0, 8, 5) && !Cols.allows(&s, 8, 0, 5));
+/// assert!(!Boxes.allows(&s, 2, 2, 5) && Rows.allows(&s, 1, 8, 5));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rows;
+
+/// Columns hold each digit once, as a [`Constraint`]; see [`Rows`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cols;
+
+/// Boxes, or jigsaw regions, hold each digit once, as a [`Constraint`];
+/// see [`Rows`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Boxes;
+
+impl Constraint for Rows {
+    fn name(&self) -> &'static str {
+        "Rows"
+    }
+
+    fn allows(&self, sudoku: &Sudoku, row: usize, col: usize, value: u8) -> bool {
+        unique_in(sudoku, House::Row(row), (row, col), value)
+    }
+
+    fn reach(&self, sudoku: &Sudoku, row: usize, _col: usize) -> Option<Vec<(usize, usize)>> {
+        Some(sudoku.house_cells(House::Row(row)))
+    }
+}
+
+impl Constraint for Cols {
+    fn name(&self) -> &'static str {
+        "Cols"
+    }
+
+    fn allows(&self, sudoku: &Sudoku, row: usize, col: usize, value: u8) -> bool {
+        unique_in(sudoku, House::Col(col), (row, col), value)
+    }
+
+    fn reach(&self, sudoku: &Sudoku, _row: usize, col: usize) -> Option<Vec<(usize, usize)>> {
+        Some(sudoku.house_cells(House::Col(col)))
+    }
+}
+
+impl Constraint for Boxes {
+    fn name(&self) -> &'static str {
+        "Boxes"
+    }
+
+    fn allows(&self, sudoku: &Sudoku, row: usize, col: usize, value: u8) -> bool {
+        let b = sudoku.box_index(row, col);
+        unique_in(sudoku, House::Box(b), (row, col), value)
+    }
+
+    fn reach(&self, sudoku: &Sudoku, row: usize, col: usize) -> Option<Vec<(usize, usize)>> {
+        Some(sudoku.house_cells(House::Box(sudoku.box_index(row, col))))
+    }
+}
+
+/// True if no cell of `house` other than `at` holds `value`.
+fn unique_in(sudoku: &Sudoku, house: House, at: (usize, usize), value: u8) -> bool {
+    sudoku
+        .house_cells(house)
+        .into_iter()
+        .all(|(r, c)| (r, c) == at || sudoku.cell(r, c).value() != Some(value))
+}
+
+/// Registered constraints, shared between clones of a grid.
+#[derive(Clone, Default)]
+pub(crate) struct Constraints(pub(crate) Vec<Arc<dyn Constraint>>);
+
+impl fmt::Debug for Constraints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|c| c.name()))
+            .finish()
+    }
+}
@@ -0,0 +1,181 @@
+//! Puzzles of the day, the same everywhere for the same key.
+
+use crate::sodo::{Cell, Sudoku};
+use crate::solver::Difficulty;
+
+impl Sudoku {
+    /// The puzzle for `key`, such as a date, at `difficulty`: one of a fixed
+    /// pool of graded 9x9 puzzles, relabelled and shuffled by a hash of the
+    /// key. The pool, hash and shuffle don't depend on the solver, the
+    /// generator or `rand`, so every client gets the same puzzle for a key
+    /// on every release of this major version.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver, Sudoku};
+    ///
+    /// let puzzle = Sudoku::daily("2026-10-14", Difficulty::Hard);
+    /// let again = Sudoku::daily("2026-10-14", Difficulty::Hard);
+    /// let tomorrow = Sudoku::daily("2026-10-15", Difficulty::Hard);
+    /// assert_eq!(puzzle.to_string_compact(), again.to_string_compact());
+    /// assert_ne!(puzzle.to_string_compact(), tomorrow.to_string_compact());
+    /// assert_eq!(Solver::new().grade(&puzzle), Difficulty::Hard);
+    /// assert!(Solver::new().has_unique_solution(&puzzle));
+    ///
+    /// // Pinned: this may only change with a major release.
+    /// assert_eq!(
+    ///     Sudoku::daily("2026-10-14", Difficulty::Easy).to_string_compact(),
+    ///     "..5..9......2..8316..4.3..9...1.5..21....27.8..4..73..9........4..6......53.91...",
+    /// );
+    /// ```
+    pub fn daily(key: impl AsRef<[u8]>, difficulty: Difficulty) -> Sudoku {
+        let pool = match difficulty {
+            Difficulty::Easy => EASY,
+            Difficulty::Medium => MEDIUM,
+            Difficulty::Hard => HARD,
+            Difficulty::Expert => EXPERT,
+        };
+        let mut rng = SplitMix(fnv1a(key.as_ref()) ^ difficulty as u64);
+        let base =
+            Sudoku::from_string(pool[rng.below(pool.len())], 9).expect("daily pool puzzle parses");
+
+        let mut digits: Vec<u8> = (1..=9).collect();
+        rng.shuffle(&mut digits);
+        let rows = rng.lines();
+        let cols = rng.lines();
+        let transpose = rng.below(2) == 1;
+
+        let mut sudoku = Sudoku::new(9);
+        for (r, &from_r) in rows.iter().enumerate() {
+            for (c, &from_c) in cols.iter().enumerate() {
+                let (fr, fc) = if transpose {
+                    (from_c, from_r)
+                } else {
+                    (from_r, from_c)
+                };
+                if let Some(v) = base.cell(fr, fc).value() {
+                    sudoku.put(r, c, Cell::Given(digits[v as usize - 1]));
+                }
+            }
+        }
+        sudoku
+    }
+
+    /// [`daily`](Self::daily) keyed by a calendar date, as `YYYY-MM-DD`.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Sudoku};
+    ///
+    /// let puzzle = Sudoku::for_date(2026, 1, 2, Difficulty::Easy);
+    /// let same = Sudoku::daily("2026-01-02", Difficulty::Easy);
+    /// assert_eq!(puzzle.to_string_compact(), same.to_string_compact());
+    /// ```
+    pub fn for_date(year: i32, month: u32, day: u32, difficulty: Difficulty) -> Sudoku {
+        Self::daily(format!("{year:04}-{month:02}-{day:02}"), difficulty)
+    }
+}
+
+/// 64-bit FNV-1a.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// SplitMix64, written out so its stream never changes under us.
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// Rows (or columns) in a new order that keeps bands together: the
+    /// bands shuffled, then the lines within each.
+    fn lines(&mut self) -> Vec<usize> {
+        let mut bands = [0, 1, 2];
+        self.shuffle(&mut bands);
+        let mut lines = Vec::with_capacity(9);
+        for band in bands {
+            let mut within = [0, 1, 2];
+            self.shuffle(&mut within);
+            lines.extend(within.iter().map(|&i| band * 3 + i));
+        }
+        lines
+    }
+}
+
+/// The pools, generated once and frozen: changing them changes every
+/// daily puzzle.
+const EASY: &[&str] = &[
+    ".2.......46.5...1....16.5......4.9.8..28.64....6.1......598........72.8........7.",
+    ".78....4.9...3.2...5.8..31.....2..3....5.7...76..8.....1.....92....5...6.8....17.",
+    ".....5....9..4....14...2.373..65......7...4......3...123.98..6.....7..9...5.....4",
+    "..846.....3..1.2..6..3.......32..5...2...7.3...4..38..........8..195..6.....261..",
+    ".1.3475.2...1...7....52..4..6..3..51.........472.......4..15....8...6.2...5.7...9",
+    "2..5....1..6.9.538....7...6.1....3........1....8....7...3.4....6.913.8..5....9.2.",
+    ".1.7.49...4...3.5..291.......6...5..89.....32......8...358..6...8.....2...1937...",
+    "2....37....16.7....38....2..245....7.........1....846....1..592......6....395...4",
+    "1.2.6...9.....4..258.........3.9..5..2.5...3..7..3.2.6.......178..4.6....5..1.8.4",
+    "...7.4.6..9.58.3....3....8.....93..29.8..7..66.........8....1....7.45.2..518.9...",
+    "....17..33.92.5.....7.....5.8...24..2......5...69.1.3.9.....6....469...7..572....",
+    "..2.1.....5.7..82..3.4...6...7..86...835..74...6...5...6...4.3..18.67.5..........",
+];
+
+const MEDIUM: &[&str] = &[
+    "...793.46.6.52...3..7............9.8.........8.6..92........3..9.4.721..21.43....",
+    ".........941........2.493.7.956...31...5.7....6...392.416.7.5......5..86.........",
+    ".....5....9.......14...2.373..65......7...4......3...123.98..6.....7..9...52....4",
+    ".9.4.....4.......661...9.....32...7.....87..4.5..........1.4..83.......29.5..6.43",
+    "...3....2....6..7.....281.......4.5.5.1...7.44..6..93...9215....8..9....62...3...",
+    "..45....1.762.....8.53.......7.....536.....844.....9.......56.....1..8..58...94..",
+    ".....4...74..9....6..1..37......851.....61....523......3....6.7987.5...3.......8.",
+    "2........9.16.73..73.....2...45.19.7...3.9......2..4...8.....92...7.46.1....5....",
+    "1..3..5..3..7....25.71...6.....92....2.....3....8......34..56.78....6..5..6..38..",
+    "...7...6.79..8..4.54..6....1....3..2.3....41.6.......3....2..7..6..4...825...9...",
+    "....17..33.92.5.....7.....5.8...24......4..5...69.1.3.9.....6....469...7..572....",
+    "..26..37....739.....1..2....2.14..9.....9.....4..7351....8..1.....367....75..148.",
+];
+
+const HARD: &[&str] = &[
+    "...79.8.6.6....713.....4...15.2.7.......5......6..9.57...9......34....852.843....",
+    ".78.....9..17.62.865..4..........7......9..6.7.4..........7.5.2..79.14..5..4..17.",
+    ".8...5.4......6..8..6.9.5........27....1.94...29........1.8.7..4.........7.2..81.",
+    "..846.....3..1.2..6..3.......32......2.5.7.3.7...938........958...95..6.....261..",
+    "8..3.....2.41..87..9.....46.6.........1.8.7....2....3..4..1..8...7..63.5.....3..9",
+    "..45....1.762....88.53.......7.....536......44.....9.......56.9...1.28..58...94..",
+    "..3....687...9.2.....1...74...2...1..94.6..3..5........3...2.....7.5...326.9.74..",
+    "2..813.4...1......738.9.1..3.....98...6...2...5......3..7.3.592...7..6...1.952..4",
+    "....6.57..6..5...2......4...1.69.7...2.5...3...5..1.4..34......8...7..2..56.13...",
+    "812........6.82.....3..1..71..6.......82.7..6.2...8.9....3..1.5...14...8......634",
+    ".....796.3..2.......7..9.....1.3.....9..4..5.....7.8.2...1..6...3...8..78657....9",
+    "..26..3.4..4..9............52...86.31...9...29.6....18........7..83..2..3..9.1...",
+];
+
+const EXPERT: &[&str] = &[
+    ".2..9.....6...8.133.7..45.2...2..9..79.....3.....19...6.......493.....8.....35.7.",
+    "3...1...99..7.62.....8....7..5.2.7...2.....6...4.839..4....8.....79.1..6....6...3",
+    "7..3.......3...12..4......7......279.....9...829......2.1.8..6..685..3...7..63..4",
+    "..846.....3..1.2..6..3.......32......2.5.7.3.7...938........9.8..195..6.....261..",
+    "8.63......5..6.87..9..28..6....3.25.....8...4.72......3..21..8...7....2.6....34.9",
+    "..4...79....2.....89.3....6.1..283..3...5...4..86...7..23..5.19.....2....817..4..",
+    ".13..49..748...........5...3.6.4.....94.6.7.2....7..4....8............23.619..48.",
+    "2....37....16.7....38....2..245....7.........1....846....1..592......6....39....4",
+    "1.2.6...9.....4..258.........3.9..5..2.5...3.....3.2.6.......178..4.6....5..1.8.4",
+    "..2.....97.65.2.4.5....1.8...4.9.......2.7.......1.7...8.3....5.6....9.82.....6..",
+    "...41...3...26.17...7..9.....1.3....29.8.6.51....7.8........6...34......8.572....",
+    ".9.6......5....8..7.14..9.....148.......9...2....73.....9.....74.8....5...5..1.86",
+];
@@ -0,0 +1,229 @@
+//! Dancing Links (Knuth's Algorithm X) search for grids whose rules are all
+//! houses.
+//!
+//! Each candidate placement is a row covering its cell and, for every house
+//! through the cell, that house's copy of the digit. A solution picks rows
+//! that cover every such column exactly once. Columns already satisfied by a
+//! placed digit are left out, and only candidates get rows, so marks and
+//! strategy eliminations carry over.
+
+use crate::sodo::{Cell, Sudoku, bit, digits};
+
+/// The linked matrix. Node 0 is the root; nodes `1..=columns` head the
+/// columns, the rest are the ones of the matrix.
+struct Dlx {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// Column header of each node.
+    column: Vec<usize>,
+    /// Matrix row of each node.
+    row: Vec<usize>,
+    /// Ones left in each column, by header.
+    size: Vec<usize>,
+    /// Placement of each matrix row, as (row, col, value).
+    placements: Vec<(usize, usize, u8)>,
+    /// Matrix rows of the partial solution.
+    chosen: Vec<usize>,
+    nodes: usize,
+}
+
+impl Dlx {
+    fn new(columns: usize) -> Self {
+        let n = columns + 1;
+        let mut dlx = Self {
+            left: (0..n).map(|i| (i + n - 1) % n).collect(),
+            right: (0..n).map(|i| (i + 1) % n).collect(),
+            up: (0..n).collect(),
+            down: (0..n).collect(),
+            column: (0..n).collect(),
+            row: vec![usize::MAX; n],
+            size: vec![0; n],
+            placements: Vec::new(),
+            chosen: Vec::new(),
+            nodes: 0,
+        };
+        dlx.size[0] = usize::MAX;
+        dlx
+    }
+
+    /// Appends a matrix row with ones in `columns`, counted from 1.
+    fn add_row(&mut self, placement: (usize, usize, u8), columns: &[usize]) {
+        let id = self.placements.len();
+        self.placements.push(placement);
+        let first = self.left.len();
+        for (k, &c) in columns.iter().enumerate() {
+            let node = first + k;
+            self.left.push(if k == 0 {
+                first + columns.len() - 1
+            } else {
+                node - 1
+            });
+            self.right.push(if k + 1 == columns.len() {
+                first
+            } else {
+                node + 1
+            });
+            self.up.push(self.up[c]);
+            self.down.push(c);
+            let last = self.up[c];
+            self.down[last] = node;
+            self.up[c] = node;
+            self.column.push(c);
+            self.row.push(id);
+            self.size[c] += 1;
+        }
+    }
+
+    fn cover(&mut self, c: usize) {
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = r;
+        self.left[r] = l;
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = j;
+                self.up[d] = j;
+                self.size[self.column[j]] += 1;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = c;
+        self.left[r] = c;
+    }
+
+    /// Counts exact covers up to `max`, calling `found` with the matrix
+    /// rows of each.
+    fn search(&mut self, count: &mut usize, max: usize, found: &mut dyn FnMut(&Self)) {
+        if self.right[0] == 0 {
+            *count += 1;
+            found(self);
+            return;
+        }
+        // The column with the fewest ones, like the solver's MRV cell.
+        let mut best = self.right[0];
+        let mut c = self.right[best];
+        while c != 0 && self.size[best] > 1 {
+            if self.size[c] < self.size[best] {
+                best = c;
+            }
+            c = self.right[c];
+        }
+        if self.size[best] == 0 {
+            return;
+        }
+
+        self.cover(best);
+        let mut i = self.down[best];
+        while i != best && *count < max {
+            self.nodes += 1;
+            self.chosen.push(self.row[i]);
+            let mut j = self.right[i];
+            while j != i {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+            self.search(count, max, found);
+            let mut j = self.left[i];
+            while j != i {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+            self.chosen.pop();
+            i = self.down[i];
+        }
+        self.uncover(best);
+    }
+}
+
+/// The exact cover problem of `sudoku`, which must pass
+/// [`Sudoku::is_exact_cover`].
+fn build(sudoku: &Sudoku) -> Dlx {
+    let n = sudoku.size();
+    let houses: Vec<_> = sudoku.houses().collect();
+    // Houses through each cell, and the digits each house still needs.
+    let mut through = vec![Vec::new(); n * n];
+    let mut needs = Vec::with_capacity(houses.len());
+    for (h, &house) in houses.iter().enumerate() {
+        let mut present = 0;
+        for (r, c) in sudoku.house_cells(house) {
+            through[r * n + c].push(h);
+            if let Some(v) = sudoku.cell(r, c).value() {
+                present |= bit(v);
+            }
+        }
+        needs.push(sudoku.full_mask() & !present);
+    }
+
+    // Columns for the empty cells first, then each house's missing digits.
+    let mut cell_column = vec![0; n * n];
+    let mut columns = 0;
+    for (i, column) in cell_column.iter_mut().enumerate() {
+        if sudoku.cell(i / n, i % n).is_empty() {
+            columns += 1;
+            *column = columns;
+        }
+    }
+    let mut digit_column = vec![vec![0; n + 1]; houses.len()];
+    for (h, &need) in needs.iter().enumerate() {
+        for v in digits(need) {
+            columns += 1;
+            digit_column[h][v as usize] = columns;
+        }
+    }
+
+    let mut dlx = Dlx::new(columns);
+    let mut ones = Vec::new();
+    for i in 0..n * n {
+        let (r, c) = (i / n, i % n);
+        if !sudoku.cell(r, c).is_empty() {
+            continue;
+        }
+        for v in digits(sudoku.candidate_mask(r, c)) {
+            ones.clear();
+            ones.push(cell_column[i]);
+            ones.extend(through[i].iter().map(|&h| digit_column[h][v as usize]));
+            dlx.add_row((r, c, v), &ones);
+        }
+    }
+    dlx
+}
+
+/// Searches `sudoku` for up to `max` solutions, returning how many were
+/// found, the first of them, and the search nodes visited.
+pub(crate) fn solve(sudoku: &Sudoku, max: usize) -> (usize, Option<Sudoku>, usize) {
+    let mut dlx = build(sudoku);
+    let mut count = 0;
+    let mut first = None;
+    dlx.search(&mut count, max, &mut |dlx| {
+        if first.is_none() {
+            let mut solution = sudoku.clone();
+            for &row in &dlx.chosen {
+                let (r, c, v) = dlx.placements[row];
+                solution.put(r, c, Cell::Filled(v));
+            }
+            first = Some(solution);
+        }
+    });
+    (count, first, dlx.nodes)
+}
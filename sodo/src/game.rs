@@ -0,0 +1,990 @@
+use crate::clock::Stopwatch;
+use crate::sodo::{CandidateMode, Cell, Mask, Sudoku, bit};
+use crate::{CandidateSet, Solver};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A player action recorded in a [`Game`]'s history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Move {
+    /// Writes a value into a cell; 0 clears it.
+    Enter { row: usize, col: usize, value: u8 },
+    /// Toggles a pencil mark on a cell.
+    Mark { row: usize, col: usize, value: u8 },
+}
+
+impl Move {
+    /// Returns the (row, col) the move targets.
+    pub fn position(self) -> (usize, usize) {
+        match self {
+            Self::Enter { row, col, .. } | Self::Mark { row, col, .. } => (row, col),
+        }
+    }
+}
+
+/// State of one cell before a move touched it.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Snapshot {
+    index: usize,
+    value: u8,
+    marks: Mask,
+}
+
+/// An applied move and everything needed to revert it.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Entry {
+    mv: Move,
+    before: Vec<Snapshot>,
+    /// Game clock when the move was made.
+    #[cfg_attr(feature = "serde", serde(default))]
+    at: Duration,
+    /// Whether peers' pencil marks were cleared automatically.
+    #[cfg_attr(feature = "serde", serde(default))]
+    auto: bool,
+}
+
+/// A move together with the game-clock time it was made at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    pub mv: Move,
+    pub at: Duration,
+}
+
+/// How far a [`Game`] has progressed, as returned by [`Game::check`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressReport {
+    /// Player entries that can't be part of the solution, as (row, col).
+    pub wrong: Vec<(usize, usize)>,
+    /// Player entries that are correct.
+    pub correct: usize,
+    /// Cells still empty.
+    pub empty: usize,
+    /// Cells the player has to fill in total.
+    pub editable: usize,
+}
+
+impl ProgressReport {
+    /// Percentage of editable cells filled correctly.
+    pub fn completion(&self) -> f64 {
+        if self.editable == 0 {
+            return 100.0;
+        }
+        self.correct as f64 * 100.0 / self.editable as f64
+    }
+
+    /// Returns true if every player entry so far is correct.
+    pub fn is_clean(&self) -> bool {
+        self.wrong.is_empty()
+    }
+}
+
+/// Pause-aware play clock.
+///
+/// There is no clock on `wasm32-unknown-unknown`; hosts there feed time in
+/// with [`advance`](Self::advance).
+#[derive(Debug, Clone, Default)]
+pub struct Timer {
+    banked: Duration,
+    running: Option<Stopwatch>,
+}
+
+impl Timer {
+    /// A timer that is already running.
+    pub fn started() -> Self {
+        Self {
+            banked: Duration::ZERO,
+            running: Some(Stopwatch::start()),
+        }
+    }
+
+    /// Time played, excluding pauses.
+    pub fn elapsed(&self) -> Duration {
+        self.banked + self.running.map_or(Duration::ZERO, |w| w.elapsed())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Starts or resumes the clock. Does nothing if it's already running.
+    pub fn resume(&mut self) {
+        self.running.get_or_insert_with(Stopwatch::start);
+    }
+
+    /// Stops the clock, keeping the time played so far.
+    pub fn pause(&mut self) {
+        if let Some(w) = self.running.take() {
+            self.banked += w.elapsed();
+        }
+    }
+
+    /// Adds time measured elsewhere.
+    pub fn advance(&mut self, by: Duration) {
+        self.banked += by;
+    }
+}
+
+/// How much a hint gives away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HintLevel {
+    /// Points at a cell that can be filled next.
+    Cell,
+    /// Reveals the cell and its value.
+    Value,
+}
+
+/// A hint handed out by [`Game::hint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hint {
+    pub level: HintLevel,
+    pub row: usize,
+    pub col: usize,
+    /// The value to place; only given for [`HintLevel::Value`].
+    pub value: Option<u8>,
+}
+
+/// A hint together with the board it was asked for.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HintRecord {
+    pub hint: Hint,
+    /// Number of moves applied when the hint was taken.
+    pub moves: usize,
+    /// Game clock when the hint was taken.
+    pub at: Duration,
+    /// The board the hint was computed on.
+    pub board: Sudoku,
+}
+
+/// What a scoring model gets to see of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    pub elapsed: Duration,
+    pub mistakes: usize,
+    pub cell_hints: usize,
+    pub value_hints: usize,
+    /// Cells the player had to fill.
+    pub cells: usize,
+}
+
+impl Outcome {
+    /// Hints taken at any level.
+    pub fn hints(&self) -> usize {
+        self.cell_hints + self.value_hints
+    }
+}
+
+/// Turns a session's outcome into a score.
+pub trait Scoring {
+    fn score(&self, outcome: &Outcome) -> u64;
+}
+
+/// Points per filled cell, minus fixed penalties for time, mistakes and
+/// each level of hint.
+///
+/// ```
+/// use sodo::{Outcome, Scoring, StandardScoring};
+/// use std::time::Duration;
+///
+/// let outcome = Outcome {
+///     elapsed: Duration::from_secs(90),
+///     mistakes: 1,
+///     cell_hints: 1,
+///     value_hints: 0,
+///     cells: 40,
+/// };
+/// assert_eq!(StandardScoring::default().score(&outcome), 4000 - 90 - 250 - 200);
+///
+/// // Penalties stop at zero.
+/// let slow = Outcome { elapsed: Duration::from_secs(10_000), ..outcome };
+/// assert_eq!(StandardScoring::default().score(&slow), 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StandardScoring {
+    pub per_cell: u64,
+    pub per_second: u64,
+    pub per_mistake: u64,
+    pub per_cell_hint: u64,
+    pub per_value_hint: u64,
+}
+
+impl Default for StandardScoring {
+    fn default() -> Self {
+        Self {
+            per_cell: 100,
+            per_second: 1,
+            per_mistake: 250,
+            per_cell_hint: 200,
+            per_value_hint: 500,
+        }
+    }
+}
+
+impl Scoring for StandardScoring {
+    fn score(&self, o: &Outcome) -> u64 {
+        (o.cells as u64 * self.per_cell)
+            .saturating_sub(o.elapsed.as_secs() * self.per_second)
+            .saturating_sub(o.mistakes as u64 * self.per_mistake)
+            .saturating_sub(o.cell_hints as u64 * self.per_cell_hint)
+            .saturating_sub(o.value_hints as u64 * self.per_value_hint)
+    }
+}
+
+/// An interactive play session: a puzzle plus the player's entries, pencil
+/// marks and a full undo/redo history.
+///
+/// Making a new move after undoing discards the undone moves, so redo never
+/// replays onto a board it wasn't recorded against.
+///
+/// ```
+/// use sodo::{Game, Sudoku};
+///
+/// let puzzle = Sudoku::from_string(
+///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+///     9,
+/// )
+/// .unwrap();
+/// let mut game = Game::new(puzzle);
+/// game.place(0, 2, 4).unwrap();
+/// game.place(0, 3, 6).unwrap();
+/// assert!(game.undo());
+/// assert_eq!(game.board().get(0, 3).unwrap().value(), None);
+/// assert!(game.redo());
+/// assert_eq!(game.board().get(0, 3).unwrap().value(), Some(6));
+///
+/// // A new move after an undo drops the move that could have been redone.
+/// game.undo();
+/// game.place(0, 5, 8).unwrap();
+/// assert!(!game.can_redo());
+/// assert_eq!(game.moves().count(), 2);
+/// assert!(game.place(0, 0, 1).is_err());
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "GameRepr", try_from = "GameRepr")
+)]
+pub struct Game {
+    puzzle: Sudoku,
+    board: Sudoku,
+    solution: Option<Sudoku>,
+    any_solution: bool,
+    auto_marks: bool,
+    marks: Vec<Mask>,
+    history: Vec<Entry>,
+    cursor: usize,
+    mistakes: usize,
+    hint_log: Vec<HintRecord>,
+    hint_limit: Option<usize>,
+    timer: Timer,
+}
+
+impl Game {
+    /// Starts a session with the clock running. Every filled cell of
+    /// `puzzle` becomes a given.
+    pub fn new(puzzle: Sudoku) -> Self {
+        let givens = givens(&puzzle);
+        let solution = Solver::new().solve(givens.clone()).ok();
+        Self::start(givens, solution)
+    }
+
+    fn start(givens: Sudoku, solution: Option<Sudoku>) -> Self {
+        let size = givens.size();
+        Self {
+            board: givens.clone().with_candidate_mode(CandidateMode::Eager),
+            solution,
+            puzzle: givens,
+            any_solution: false,
+            auto_marks: false,
+            marks: vec![0; size * size],
+            history: Vec::new(),
+            cursor: 0,
+            mistakes: 0,
+            hint_log: Vec::new(),
+            hint_limit: None,
+            timer: Timer::started(),
+        }
+    }
+
+    /// Serializes the session (puzzle, entries, marks, history, timer and
+    /// hint usage) to a compact, versioned JSON string.
+    ///
+    /// ```
+    /// use sodo::{Game, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// let mut game = Game::new(puzzle);
+    /// game.place(0, 2, 4).unwrap();
+    /// game.toggle_mark(0, 3, 6).unwrap();
+    /// let saved = game.save().unwrap();
+    /// assert!(saved.contains(r#""version":2"#));
+    ///
+    /// let mut loaded = Game::load(&saved).unwrap();
+    /// assert_eq!(loaded.board().to_string_compact(), game.board().to_string_compact());
+    /// assert!(loaded.marks(0, 3).contains(6));
+    /// assert!(loaded.undo() && loaded.undo() && !loaded.can_undo());
+    ///
+    /// // Saves of another version are refused.
+    /// let old = saved.replace(r#""version":2"#, r#""version":1"#);
+    /// assert!(Game::load(&old).is_err());
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn save(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+
+    /// Restores a session written by [`save`](Self::save).
+    ///
+    /// The clock resumes if it was running when the session was saved.
+    #[cfg(feature = "serde")]
+    pub fn load(s: &str) -> Result<Self, String> {
+        serde_json::from_str(s).map_err(|e| e.to_string())
+    }
+
+    /// Sets the intended solution entries are checked against.
+    ///
+    /// By default the first solution the solver finds is used.
+    pub fn with_solution(mut self, solution: Sudoku) -> Self {
+        self.solution = Some(solution);
+        self
+    }
+
+    /// Accept entries that agree with any solution, not just the intended
+    /// one. Matters only for puzzles with more than one solution.
+    pub fn accept_any_solution(mut self, enabled: bool) -> Self {
+        self.any_solution = enabled;
+        self
+    }
+
+    /// Remove a placed digit from its peers' pencil marks automatically.
+    /// Undo restores the removed marks.
+    ///
+    /// ```
+    /// use sodo::{Game, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// let mut game = Game::new(puzzle).auto_marks(true);
+    /// game.toggle_mark(0, 3, 4).unwrap();
+    /// game.toggle_mark(0, 3, 6).unwrap();
+    /// game.place(0, 2, 4).unwrap();
+    /// assert_eq!(game.marks(0, 3).len(), 1);
+    /// game.undo();
+    /// assert!(game.marks(0, 3).contains(4));
+    /// ```
+    pub fn auto_marks(mut self, enabled: bool) -> Self {
+        self.auto_marks = enabled;
+        self
+    }
+
+    /// Turns automatic pencil-mark removal on or off mid-session.
+    pub fn set_auto_marks(&mut self, enabled: bool) {
+        self.auto_marks = enabled;
+    }
+
+    /// Returns true if placing a digit clears it from peers' pencil marks.
+    pub fn auto_marks_enabled(&self) -> bool {
+        self.auto_marks
+    }
+
+    /// The solution entries are checked against, if the puzzle has one.
+    pub fn solution(&self) -> Option<&Sudoku> {
+        self.solution.as_ref()
+    }
+
+    /// Number of wrong values entered this session. Undoing doesn't refund them.
+    pub fn mistakes(&self) -> usize {
+        self.mistakes
+    }
+
+    /// Caps the number of hints the session may take.
+    pub fn hint_limit(mut self, limit: usize) -> Self {
+        self.hint_limit = Some(limit);
+        self
+    }
+
+    /// Changes the hint allowance mid-session; `None` removes it.
+    pub fn set_hint_limit(&mut self, limit: Option<usize>) {
+        self.hint_limit = limit;
+    }
+
+    /// Number of hints taken this session. Undoing doesn't refund them.
+    pub fn hints_used(&self) -> usize {
+        self.hint_log.len()
+    }
+
+    /// Hints still allowed, or `None` if there is no limit.
+    pub fn hints_left(&self) -> Option<usize> {
+        self.hint_limit
+            .map(|limit| limit.saturating_sub(self.hint_log.len()))
+    }
+
+    /// Every hint taken, oldest first.
+    pub fn hint_log(&self) -> &[HintRecord] {
+        &self.hint_log
+    }
+
+    /// Suggests the next logical placement and charges it to the session.
+    ///
+    /// Fails once the allowance is spent. Returns `Ok(None)`, without charging,
+    /// when no logical placement exists.
+    ///
+    /// ```
+    /// use sodo::{Game, HintLevel, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// let mut game = Game::new(puzzle).hint_limit(1);
+    /// let hint = game.hint(HintLevel::Value).unwrap().unwrap();
+    /// let solution = game.solution().unwrap();
+    /// assert_eq!(hint.value, solution.get(hint.row, hint.col).unwrap().value());
+    /// assert_eq!(game.hints_left(), Some(0));
+    /// assert!(game.hint(HintLevel::Cell).is_err());
+    ///
+    /// game.set_hint_limit(None);
+    /// assert_eq!(game.hint(HintLevel::Cell).unwrap().unwrap().value, None);
+    /// assert_eq!(game.hints_used(), 2);
+    /// assert_eq!(game.outcome().value_hints, 1);
+    /// ```
+    pub fn hint(&mut self, level: HintLevel) -> Result<Option<Hint>, String> {
+        if self.hints_left() == Some(0) {
+            return Err("No hints left".into());
+        }
+        let Some((row, col, value)) = Solver::new().hint(&self.board) else {
+            return Ok(None);
+        };
+
+        let hint = Hint {
+            level,
+            row,
+            col,
+            value: (level == HintLevel::Value).then_some(value),
+        };
+        self.hint_log.push(HintRecord {
+            hint,
+            moves: self.cursor,
+            at: self.timer.elapsed(),
+            board: self.board.clone(),
+        });
+        Ok(Some(hint))
+    }
+
+    pub fn timer(&self) -> &Timer {
+        &self.timer
+    }
+
+    pub fn timer_mut(&mut self) -> &mut Timer {
+        &mut self.timer
+    }
+
+    /// Time played, excluding pauses.
+    pub fn elapsed(&self) -> Duration {
+        self.timer.elapsed()
+    }
+
+    pub fn pause(&mut self) {
+        self.timer.pause();
+    }
+
+    pub fn resume(&mut self) {
+        self.timer.resume();
+    }
+
+    /// The session's results so far, as seen by a scoring model.
+    pub fn outcome(&self) -> Outcome {
+        Outcome {
+            elapsed: self.elapsed(),
+            mistakes: self.mistakes,
+            cell_hints: self.count_hints(HintLevel::Cell),
+            value_hints: self.count_hints(HintLevel::Value),
+            cells: self.puzzle.empty_count(),
+        }
+    }
+
+    /// Scores the session with [`StandardScoring`].
+    pub fn score(&self) -> u64 {
+        self.score_with(&StandardScoring::default())
+    }
+
+    /// Scores the session with a custom model.
+    pub fn score_with(&self, model: &impl Scoring) -> u64 {
+        model.score(&self.outcome())
+    }
+
+    /// Compares the player's entries against the solution.
+    ///
+    /// With [`accept_any_solution`](Self::accept_any_solution), entries are
+    /// judged against a solution that agrees with all of them when one exists.
+    /// Without any solution, only entries clashing with a peer count as wrong.
+    pub fn check(&self) -> ProgressReport {
+        let size = self.board.size();
+        let alternative = if self.any_solution {
+            Solver::new().solve(self.board.clone()).ok()
+        } else {
+            None
+        };
+        let reference = alternative.as_ref().or(self.solution.as_ref());
+
+        let mut report = ProgressReport {
+            wrong: Vec::new(),
+            correct: 0,
+            empty: 0,
+            editable: 0,
+        };
+        for (i, cell) in self.board.cells().enumerate() {
+            let (row, col) = (i / size, i % size);
+            match cell {
+                Cell::Given(_) => continue,
+                Cell::Empty => report.empty += 1,
+                Cell::Filled(v) => {
+                    let ok = match reference {
+                        Some(s) => s.cell(row, col).value() == Some(v),
+                        None => !self.clashes(row, col, v),
+                    };
+                    if ok {
+                        report.correct += 1;
+                    } else {
+                        report.wrong.push((row, col));
+                    }
+                }
+            }
+            report.editable += 1;
+        }
+        report
+    }
+
+    /// The original puzzle, givens only.
+    pub fn puzzle(&self) -> &Sudoku {
+        &self.puzzle
+    }
+
+    /// The current board: givens plus the player's entries.
+    pub fn board(&self) -> &Sudoku {
+        &self.board
+    }
+
+    /// Returns the pencil marks on a cell; none off the grid.
+    pub fn marks(&self, row: usize, col: usize) -> CandidateSet {
+        if self.board.get(row, col).is_none() {
+            return CandidateSet::EMPTY;
+        }
+        CandidateSet::from_bits(self.marks[row * self.board.size() + col])
+    }
+
+    /// Enters a value into an editable cell.
+    pub fn place(&mut self, row: usize, col: usize, value: u8) -> Result<(), String> {
+        self.play(Move::Enter { row, col, value })
+    }
+
+    /// Clears an editable cell.
+    pub fn clear(&mut self, row: usize, col: usize) -> Result<(), String> {
+        self.play(Move::Enter { row, col, value: 0 })
+    }
+
+    /// Toggles a pencil mark on an editable cell.
+    pub fn toggle_mark(&mut self, row: usize, col: usize, value: u8) -> Result<(), String> {
+        self.play(Move::Mark { row, col, value })
+    }
+
+    /// Applies a move and records it, discarding any undone moves.
+    pub fn play(&mut self, mv: Move) -> Result<(), String> {
+        let auto = self.auto_marks;
+        let before = self.apply(mv, auto)?;
+        if let (Move::Enter { row, col, value }, Some(s)) = (mv, &self.solution)
+            && value != 0
+            && s.cell(row, col).value() != Some(value)
+        {
+            self.mistakes += 1;
+        }
+        self.history.truncate(self.cursor);
+        self.history.push(Entry {
+            mv,
+            before,
+            at: self.timer.elapsed(),
+            auto,
+        });
+        self.cursor += 1;
+        // The clock stops on the winning move.
+        if self.board.is_solved() {
+            self.timer.pause();
+        }
+        Ok(())
+    }
+
+    /// Reverts the last move. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        let snapshots = self.history[self.cursor].before.clone();
+        for snap in snapshots.iter().rev() {
+            self.restore(*snap);
+        }
+        true
+    }
+
+    /// Re-applies the last undone move. Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(&Entry { mv, auto, .. }) = self.history.get(self.cursor) else {
+            return false;
+        };
+        // Redo only runs on the exact board the move was recorded against.
+        match self.apply(mv, auto) {
+            Ok(before) => {
+                self.history[self.cursor].before = before;
+                self.cursor += 1;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.history.len()
+    }
+
+    /// Moves currently applied, oldest first.
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.history[..self.cursor].iter().map(|e| e.mv)
+    }
+
+    /// Moves currently applied with their timestamps, oldest first.
+    pub fn frames(&self) -> impl Iterator<Item = Frame> + '_ {
+        self.history[..self.cursor]
+            .iter()
+            .map(|e| Frame { mv: e.mv, at: e.at })
+    }
+
+    /// Plays the applied moves back from the starting position.
+    pub fn replay(&self) -> Replay {
+        let mut game = self.clone();
+        while game.undo() {}
+        game.history.truncate(self.cursor);
+        Replay { game }
+    }
+
+    /// Reconstructs the board as it was after the first `moves` moves.
+    ///
+    /// ```
+    /// use sodo::{Game, Move, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// let mut game = Game::new(puzzle);
+    /// game.place(0, 2, 4).unwrap();
+    /// game.place(0, 3, 6).unwrap();
+    /// let first = game.board_at(1);
+    /// assert_eq!(first.get(0, 2).unwrap().value(), Some(4));
+    /// assert_eq!(first.get(0, 3).unwrap().value(), None);
+    /// assert_eq!(game.board_at(0).to_string_compact(), game.puzzle().to_string_compact());
+    ///
+    /// let mut replay = game.replay();
+    /// let frame = replay.step().unwrap();
+    /// assert_eq!(frame.mv, Move::Enter { row: 0, col: 2, value: 4 });
+    /// replay.seek(usize::MAX);
+    /// assert_eq!(replay.position(), 2);
+    /// assert_eq!(replay.board().to_string_compact(), game.board().to_string_compact());
+    /// ```
+    pub fn board_at(&self, moves: usize) -> Sudoku {
+        let mut replay = self.replay();
+        replay.seek(moves);
+        replay.game.board
+    }
+
+    /// Returns true once the board is completely and correctly filled.
+    pub fn is_solved(&self) -> bool {
+        self.board.is_solved()
+    }
+
+    /// Performs a move on the board, returning the prior state of touched cells.
+    fn apply(&mut self, mv: Move, auto: bool) -> Result<Vec<Snapshot>, String> {
+        let size = self.board.size();
+        let (row, col) = mv.position();
+        let cell = self.board.get(row, col).ok_or("Position out of bounds")?;
+        if cell.is_given() {
+            return Err(format!("Cell ({row},{col}) is a given"));
+        }
+
+        let index = row * size + col;
+        let mut before = vec![self.snapshot(index)];
+        match mv {
+            Move::Enter { value, .. } => {
+                self.board.set(row, col, value)?;
+                if auto && value != 0 {
+                    let peers: Vec<usize> = self
+                        .board
+                        .peers(row, col)
+                        .map(|(r, c)| r * size + c)
+                        .filter(|&i| self.marks[i] & bit(value) != 0)
+                        .collect();
+                    for i in peers {
+                        before.push(self.snapshot(i));
+                        self.marks[i] &= !bit(value);
+                    }
+                }
+            }
+            Move::Mark { value, .. } => {
+                if value == 0 || value as usize > size {
+                    return Err(format!("Value {value} out of range 1-{size}"));
+                }
+                self.marks[index] ^= bit(value);
+            }
+        }
+        Ok(before)
+    }
+
+    fn count_hints(&self, level: HintLevel) -> usize {
+        self.hint_log
+            .iter()
+            .filter(|r| r.hint.level == level)
+            .count()
+    }
+
+    /// Returns true if `v` at (row, col) repeats a value in one of its houses.
+    fn clashes(&self, row: usize, col: usize, v: u8) -> bool {
+        let mut board = self.board.clone();
+        board.put(row, col, Cell::Empty);
+        !board.can_place(row, col, v)
+    }
+
+    fn snapshot(&self, index: usize) -> Snapshot {
+        let size = self.board.size();
+        Snapshot {
+            index,
+            value: self
+                .board
+                .cell(index / size, index % size)
+                .value()
+                .unwrap_or(0),
+            marks: self.marks[index],
+        }
+    }
+
+    fn restore(&mut self, snap: Snapshot) {
+        let size = self.board.size();
+        let (row, col) = (snap.index / size, snap.index % size);
+        let cell = match snap.value {
+            0 => Cell::Empty,
+            v => Cell::Filled(v),
+        };
+        if !self.board.cell(row, col).is_given() {
+            self.board.put(row, col, cell);
+        }
+        self.marks[snap.index] = snap.marks;
+    }
+}
+
+/// Step-by-step playback of a [`Game`]'s moves, from [`Game::replay`].
+#[derive(Debug, Clone)]
+pub struct Replay {
+    game: Game,
+}
+
+impl Replay {
+    /// Number of moves in the recording.
+    pub fn len(&self) -> usize {
+        self.game.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.game.history.is_empty()
+    }
+
+    /// Number of moves played back so far.
+    pub fn position(&self) -> usize {
+        self.game.cursor
+    }
+
+    /// The board at the current position.
+    pub fn board(&self) -> &Sudoku {
+        &self.game.board
+    }
+
+    /// Pencil marks on a cell at the current position.
+    pub fn marks(&self, row: usize, col: usize) -> CandidateSet {
+        self.game.marks(row, col)
+    }
+
+    /// Plays the next move. Returns `None` at the end of the recording.
+    pub fn step(&mut self) -> Option<Frame> {
+        let e = self.game.history.get(self.game.cursor)?;
+        let frame = Frame { mv: e.mv, at: e.at };
+        self.game.redo().then_some(frame)
+    }
+
+    /// Takes back the last played move. Returns false at the start.
+    pub fn step_back(&mut self) -> bool {
+        self.game.undo()
+    }
+
+    /// Jumps to the position after `moves` moves, clamped to the recording.
+    pub fn seek(&mut self, moves: usize) {
+        while self.game.cursor > moves && self.game.undo() {}
+        while self.game.cursor < moves && self.game.redo() {}
+    }
+}
+
+/// Copies `puzzle`, rules and all, with every filled cell turned into a
+/// given.
+fn givens(puzzle: &Sudoku) -> Sudoku {
+    let size = puzzle.size();
+    let mut givens = puzzle.clone();
+    givens.clear_eliminations();
+    for (i, cell) in puzzle.cells().enumerate() {
+        if let Cell::Filled(v) = cell {
+            givens.put(i / size, i % size, Cell::Given(v));
+        }
+    }
+    givens
+}
+
+/// Bumped whenever [`GameRepr`] changes incompatibly.
+#[cfg(feature = "serde")]
+const SAVE_VERSION: u32 = 2;
+
+/// Serialized form of a [`Game`]. The puzzle keeps its rules; the board and
+/// solution are compact strings laid over it.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct GameRepr {
+    version: u32,
+    puzzle: Sudoku,
+    board: String,
+    solution: Option<String>,
+    marks: Vec<Mask>,
+    history: Vec<Entry>,
+    cursor: usize,
+    mistakes: usize,
+    #[serde(default)]
+    hint_log: Vec<HintRecord>,
+    #[serde(default)]
+    hint_limit: Option<usize>,
+    elapsed_ms: u64,
+    running: bool,
+    any_solution: bool,
+    auto_marks: bool,
+}
+
+#[cfg(feature = "serde")]
+impl From<Game> for GameRepr {
+    fn from(g: Game) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            board: g.board.to_string_compact(),
+            solution: g.solution.as_ref().map(Sudoku::to_string_compact),
+            puzzle: g.puzzle,
+            marks: g.marks,
+            history: g.history,
+            cursor: g.cursor,
+            mistakes: g.mistakes,
+            hint_log: g.hint_log,
+            hint_limit: g.hint_limit,
+            elapsed_ms: g.timer.elapsed().as_millis() as u64,
+            running: g.timer.is_running(),
+            any_solution: g.any_solution,
+            auto_marks: g.auto_marks,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GameRepr> for Game {
+    type Error = String;
+
+    fn try_from(repr: GameRepr) -> Result<Self, String> {
+        if repr.version != SAVE_VERSION {
+            return Err(format!("Unsupported save version {}", repr.version));
+        }
+        let puzzle = givens(&repr.puzzle);
+        let size = puzzle.size();
+        let board = Sudoku::from_string(&repr.board, size)?;
+        let solution = match repr.solution {
+            Some(s) => {
+                let values = Sudoku::from_string(&s, size)?;
+                let mut solution = puzzle.clone();
+                for (i, v) in values.cells().enumerate() {
+                    if let (Some(v), Cell::Empty) = (v.value(), puzzle.cell(i / size, i % size)) {
+                        solution.put(i / size, i % size, Cell::Filled(v));
+                    }
+                }
+                Some(solution)
+            }
+            None => None,
+        };
+
+        let cells = size * size;
+        if repr.marks.len() != cells {
+            return Err(format!("Expected {cells} pencil-mark entries"));
+        }
+        if repr.cursor > repr.history.len() {
+            return Err("History cursor out of range".into());
+        }
+        if repr
+            .history
+            .iter()
+            .flat_map(|e| &e.before)
+            .any(|s| s.index >= cells || s.value as usize > size)
+        {
+            return Err("History refers to cells outside the grid".into());
+        }
+
+        let mut game = Self::start(puzzle.clone(), solution);
+        for (i, (p, b)) in puzzle.cells().zip(board.cells()).enumerate() {
+            match (p.value(), b.value()) {
+                (Some(g), v) if v != Some(g) => {
+                    return Err(format!(
+                        "Board changes given at ({},{})",
+                        i / size,
+                        i % size
+                    ));
+                }
+                (None, Some(v)) => game.board.put(i / size, i % size, Cell::Filled(v)),
+                _ => {}
+            }
+        }
+
+        let mut timer = Timer::default();
+        timer.advance(Duration::from_millis(repr.elapsed_ms));
+        if repr.running {
+            timer.resume();
+        }
+
+        Ok(Self {
+            marks: repr.marks,
+            history: repr.history,
+            cursor: repr.cursor,
+            mistakes: repr.mistakes,
+            hint_log: repr.hint_log,
+            hint_limit: repr.hint_limit,
+            timer,
+            any_solution: repr.any_solution,
+            auto_marks: repr.auto_marks,
+            ..game
+        })
+    }
+}
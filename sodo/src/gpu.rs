@@ -0,0 +1,272 @@
+//! Experimental GPU backend for mass solution counting.
+//!
+//! Batches of 9x9 boards are uploaded to a compute shader that runs one
+//! depth-first search per board. Everything the GPU can't handle — other
+//! grid sizes, boards that blow the per-board step budget, or hosts without
+//! an adapter — is counted on the CPU instead, so results are always complete.
+
+use crate::{Solver, Sudoku};
+use std::sync::mpsc;
+use wgpu::util::DeviceExt;
+
+/// Boards uploaded per batch (keeps the state buffer well under default limits).
+const CHUNK: usize = 1 << 15;
+
+/// Search steps a board may take in total before deferring to the CPU.
+const MAX_STEPS: u32 = 1 << 20;
+
+/// Search steps per board per dispatch. Drivers cap how long one invocation
+/// may loop, so long searches are split across many short dispatches.
+const QUOTA: u32 = 256;
+
+/// Dispatches recorded per submission between checks for remaining work.
+const ROUNDS: usize = 16;
+
+/// Sentinel written by the shader when a board exceeded `MAX_STEPS`.
+const ABORTED: u32 = u32::MAX;
+
+const WORKGROUP_SIZE: u32 = 64;
+
+// Per-board slot layout in the state buffer; mirrors `gpu.wgsl`.
+const STRIDE: usize = 248;
+const FOUND: usize = 244;
+const STATUS: usize = 246;
+const RUNNING: u32 = 1;
+
+/// A connected GPU device with the counting pipeline compiled.
+///
+/// Creating one is expensive; reuse it for every batch.
+pub struct GpuCounter {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl GpuCounter {
+    /// Connects to the default GPU adapter and compiles the counting shader.
+    pub fn new() -> Result<Self, String> {
+        pollster::block_on(Self::connect())
+    }
+
+    async fn connect() -> Result<Self, String> {
+        let instance = wgpu::Instance::default();
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await
+            .map_err(|e| e.to_string())?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let scope = device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("sodo-count"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("gpu.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("sodo-count"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        if let Some(e) = scope.pop().await {
+            return Err(e.to_string());
+        }
+
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+        })
+    }
+
+    /// Counts solutions of each puzzle, stopping at `limit` per puzzle.
+    ///
+    /// Puzzles the GPU can't finish are counted on the CPU.
+    pub fn count(&self, puzzles: &[Sudoku], limit: usize) -> Result<Vec<usize>, String> {
+        let limit = limit.clamp(1, ABORTED as usize - 1);
+        let mut counts = vec![0; puzzles.len()];
+        let gpu: Vec<usize> = (0..puzzles.len())
+            .filter(|&i| classic(&puzzles[i]))
+            .collect();
+
+        for chunk in gpu.chunks(CHUNK) {
+            let boards: Vec<&Sudoku> = chunk.iter().map(|&i| &puzzles[i]).collect();
+            for (&i, raw) in chunk.iter().zip(self.dispatch(&boards, limit as u32)?) {
+                counts[i] = match raw {
+                    ABORTED => cpu_count(&puzzles[i], limit),
+                    n => n as usize,
+                };
+            }
+        }
+
+        for (i, p) in puzzles.iter().enumerate().filter(|(_, p)| !classic(p)) {
+            counts[i] = cpu_count(p, limit);
+        }
+
+        Ok(counts)
+    }
+
+    /// Runs the search for at most `CHUNK` 9x9 boards to completion.
+    fn dispatch(&self, boards: &[&Sudoku], limit: u32) -> Result<Vec<u32>, String> {
+        let n = boards.len() as u32;
+        let mut slots = vec![0u32; boards.len() * STRIDE];
+        for (slot, board) in slots.chunks_exact_mut(STRIDE).zip(boards) {
+            for (word, cell) in slot.iter_mut().zip(board.cells()) {
+                *word = u32::from(cell.value().unwrap_or(0));
+            }
+            // Duplicate givens have no solutions; the shader assumes a valid start.
+            slot[STATUS] = if board.is_valid() { RUNNING } else { 0 };
+        }
+
+        let state = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("sodo-state"),
+                contents: &to_bytes(&slots),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            });
+        let active = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("sodo-active"),
+                contents: &to_bytes(&[0]),
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_SRC
+                    | wgpu::BufferUsages::COPY_DST,
+            });
+        let params = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("sodo-params"),
+                contents: &to_bytes(&[n, limit, MAX_STEPS, QUOTA]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sodo-count"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: state.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: active.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: params.as_entire_binding(),
+                },
+            ],
+        });
+
+        // Every board gives up after MAX_STEPS, so this always terminates.
+        loop {
+            self.queue.write_buffer(&active, 0, &to_bytes(&[0]));
+            let mut encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            for _ in 0..ROUNDS {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(n.div_ceil(WORKGROUP_SIZE), 1, 1);
+            }
+            self.queue.submit([encoder.finish()]);
+
+            if self.read(&active)?[0] == 0 {
+                break;
+            }
+        }
+
+        let slots = self.read(&state)?;
+        Ok(slots.chunks_exact(STRIDE).map(|slot| slot[FOUND]).collect())
+    }
+
+    /// Copies a storage buffer back to the host.
+    fn read(&self, buffer: &wgpu::Buffer) -> Result<Vec<u32>, String> {
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sodo-readback"),
+            size: buffer.size(),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(buffer, 0, &readback, 0, buffer.size());
+        self.queue.submit([encoder.finish()]);
+
+        let (tx, rx) = mpsc::channel();
+        readback.map_async(wgpu::MapMode::Read, .., move |r| {
+            let _ = tx.send(r);
+        });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|e| e.to_string())?;
+        rx.recv()
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+
+        let words = readback
+            .get_mapped_range(..)
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        readback.unmap();
+        Ok(words)
+    }
+}
+
+fn to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|w| w.to_le_bytes()).collect()
+}
+
+/// Counts solutions for many puzzles, using the GPU when one is available.
+///
+/// Falls back to the CPU for the whole batch if no adapter can be opened.
+///
+/// Puzzles the shader can't count, such as a Sukaku, go to the CPU:
+///
+/// ```
+/// use sodo::{CandidateSet, Sudoku, gpu::count_solutions_batch};
+///
+/// let solution = "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+/// let plain = Sudoku::from_string(&(".".repeat(27) + &solution[27..]), 9).unwrap();
+/// // Pencilmarks pin the top row to the solution's digits.
+/// let sets = (solution.bytes().map(|b| b - b'0').enumerate())
+///     .map(|(i, d)| if i < 9 { CandidateSet::from_iter([d]) } else { CandidateSet::full(9) })
+///     .collect();
+/// let sukaku = plain.clone().with_pencilmarks(sets).unwrap();
+/// assert_eq!(count_solutions_batch(&[plain, sukaku], 1000), [144, 2]);
+/// ```
+pub fn count_solutions_batch(puzzles: &[Sudoku], limit: usize) -> Vec<usize> {
+    GpuCounter::new()
+        .and_then(|gpu| gpu.count(puzzles, limit))
+        .unwrap_or_else(|_| puzzles.iter().map(|p| cpu_count(p, limit)).collect())
+}
+
+/// The shader only knows plain 9x9 grids with 3x3 boxes, and rebuilds
+/// candidates from the placed digits alone.
+fn classic(puzzle: &Sudoku) -> bool {
+    puzzle.size() == 9
+        && puzzle.box_rows() == 3
+        && puzzle.variants().is_empty()
+        && puzzle.regions().is_none()
+        && puzzle.cages().is_empty()
+        && puzzle.lines().is_empty()
+        && puzzle.marks().is_empty()
+        && puzzle.clues().is_empty()
+        && puzzle.constraints().is_empty()
+        && !puzzle.has_pencilmarks()
+        && puzzle.eliminated() == 0
+}
+
+fn cpu_count(puzzle: &Sudoku, limit: usize) -> usize {
+    Solver::new().count_solutions(puzzle, Some(limit))
+}
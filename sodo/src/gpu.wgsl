@@ -0,0 +1,167 @@
+// Counts solutions of 9x9 boards, one invocation per board.
+//
+// Each invocation resumes an iterative depth-first search (MRV heuristic)
+// from its slot in `state`, runs at most `quota` steps, and saves the search
+// back. Drivers cap how long a single invocation may loop, so the host keeps
+// dispatching until `pending` stays zero. Boards that exceed `max_steps` in
+// total report 0xFFFFFFFF so the host can finish them on the CPU.
+
+struct Params {
+    count: u32,
+    limit: u32,
+    max_steps: u32,
+    quota: u32,
+}
+
+// Slot layout, in u32 words.
+const GRID: u32 = 0u;
+const CELLS: u32 = 81u;
+const REST: u32 = 162u;
+const DEPTH: u32 = 243u;
+const FOUND: u32 = 244u;
+const STEPS: u32 = 245u;
+const STATUS: u32 = 246u;
+const STRIDE: u32 = 248u;
+
+const RUNNING: u32 = 1u;
+const DONE: u32 = 2u;
+
+const FULL: u32 = 0x1FFu;
+const ABORTED: u32 = 0xFFFFFFFFu;
+
+@group(0) @binding(0) var<storage, read_write> state: array<u32>;
+@group(0) @binding(1) var<storage, read_write> pending: atomic<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+fn box_of(cell: u32) -> u32 {
+    return (cell / 27u) * 3u + (cell % 9u) / 3u;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let b = id.x;
+    if (b >= params.count) {
+        return;
+    }
+    let base = b * STRIDE;
+    if (state[base + STATUS] != RUNNING) {
+        return;
+    }
+
+    var grid: array<u32, 81>;
+    var rows: array<u32, 9>;
+    var cols: array<u32, 9>;
+    var boxes: array<u32, 9>;
+    var stack_cell: array<u32, 81>;
+    var stack_rest: array<u32, 81>;
+
+    for (var i = 0u; i < 81u; i++) {
+        let v = state[base + GRID + i];
+        grid[i] = v;
+        if (v != 0u) {
+            let bit = 1u << (v - 1u);
+            rows[i / 9u] |= bit;
+            cols[i % 9u] |= bit;
+            boxes[box_of(i)] |= bit;
+        }
+    }
+
+    var depth = state[base + DEPTH];
+    var found = state[base + FOUND];
+    var steps = state[base + STEPS];
+    for (var d = 0u; d < depth; d++) {
+        stack_cell[d] = state[base + CELLS + d];
+        stack_rest[d] = state[base + REST + d];
+    }
+
+    var status = RUNNING;
+    for (var q = 0u; q < params.quota; q++) {
+        // Pick the empty cell with the fewest candidates.
+        var best = 81u;
+        var best_mask = 0u;
+        var best_n = 10u;
+        for (var i = 0u; i < 81u; i++) {
+            if (grid[i] == 0u) {
+                let m = FULL & ~(rows[i / 9u] | cols[i % 9u] | boxes[box_of(i)]);
+                let n = countOneBits(m);
+                if (n < best_n) {
+                    best = i;
+                    best_mask = m;
+                    best_n = n;
+                    if (n == 0u) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if (best == 81u) {
+            found++;
+            if (found >= params.limit) {
+                status = DONE;
+                break;
+            }
+        } else if (best_n > 0u) {
+            stack_cell[depth] = best;
+            stack_rest[depth] = best_mask;
+            depth++;
+        }
+
+        // Advance to the next untried candidate, unwinding exhausted levels.
+        loop {
+            if (depth == 0u) {
+                break;
+            }
+            let top = depth - 1u;
+            let cell = stack_cell[top];
+            let old = grid[cell];
+            if (old != 0u) {
+                let bit = ~(1u << (old - 1u));
+                rows[cell / 9u] &= bit;
+                cols[cell % 9u] &= bit;
+                boxes[box_of(cell)] &= bit;
+                grid[cell] = 0u;
+            }
+            let m = stack_rest[top];
+            if (m == 0u) {
+                depth--;
+                continue;
+            }
+            let v = firstTrailingBit(m) + 1u;
+            stack_rest[top] = m & (m - 1u);
+            let bit = 1u << (v - 1u);
+            grid[cell] = v;
+            rows[cell / 9u] |= bit;
+            cols[cell % 9u] |= bit;
+            boxes[box_of(cell)] |= bit;
+            break;
+        }
+
+        if (depth == 0u) {
+            status = DONE;
+            break;
+        }
+        steps++;
+        if (steps >= params.max_steps) {
+            found = ABORTED;
+            status = DONE;
+            break;
+        }
+    }
+
+    for (var i = 0u; i < 81u; i++) {
+        state[base + GRID + i] = grid[i];
+    }
+    for (var d = 0u; d < depth; d++) {
+        state[base + CELLS + d] = stack_cell[d];
+        state[base + REST + d] = stack_rest[d];
+    }
+    state[base + DEPTH] = depth;
+    state[base + FOUND] = found;
+    state[base + STEPS] = steps;
+    state[base + STATUS] = status;
+
+    if (status == RUNNING) {
+        atomicAdd(&pending, 1u);
+    }
+}
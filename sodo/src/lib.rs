@@ -15,10 +15,59 @@
 //! assert!(solution.is_solved());
 //! ```
 
+mod analysis;
+#[cfg(feature = "async")]
+mod background;
+mod candidate;
+mod chain;
+mod clock;
+mod clue;
+mod config;
+mod constraint;
+mod daily;
+mod dlx;
+mod game;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+mod line;
+mod multi;
+mod options;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "puzzles")]
+pub mod puzzles;
+#[cfg(feature = "sat")]
+mod sat;
+mod session;
 mod sodo;
 mod solver;
 mod strategy;
+mod transform;
+mod validation;
 
-pub use sodo::{Cell, Sudoku};
-pub use solver::{Difficulty, Solver, Stats};
-pub use strategy::{Strategy, all as all_strategies};
+pub use analysis::{Analysis, Rating, SkillProfile, TimeEstimate, UnavoidableSet};
+#[cfg(feature = "async")]
+pub use background::{GenerateOptions, GenerateProgress};
+pub use candidate::{CandidateSet, Digits};
+pub use chain::{Aic, XChain, XyChain};
+pub use clue::{Clue, ClueKind, Direction};
+pub use config::VariantConfig;
+pub use constraint::{Boxes, Cols, Constraint, Rows};
+pub use game::{
+    Frame, Game, Hint, HintLevel, HintRecord, Move, Outcome, ProgressReport, Replay, Scoring,
+    StandardScoring, Timer,
+};
+pub use line::{Line, LineKind};
+pub use multi::MultiSudoku;
+pub use options::{CancelToken, OnBudget, Progress, ProgressHook, SolveError, SolveOptions};
+pub use session::SolveSession;
+pub use sodo::{
+    Cage, CandidateMode, Cell, Conflict, House, MAX_SIZE, Mark, Sudoku, SymmetryKind, Variant,
+};
+pub use solver::{
+    Backend, CellOrder, Difficulty, Solver, Stats, Stuck, StuckReason, UniquePuzzle, ValueOrder,
+};
+pub use strategy::{
+    Finding, PatternOverlay, Step, Strategy, all as all_strategies, extreme as extreme_strategies,
+};
+pub use validation::{Problem, ValidationReport};
@@ -0,0 +1,152 @@
+//! Line constraints drawn through cells, such as thermometers and arrows.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// What a [`Line`] asks of the digits along it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LineKind {
+    /// Digits strictly increase from the bulb, the first cell.
+    Thermo,
+    /// The first `pill` cells, read as a decimal number, equal the sum of
+    /// the rest. A one-cell pill is the usual circle; two cells allow sums
+    /// past the largest digit. Digits may repeat along the shaft.
+    Arrow { pill: usize },
+}
+
+impl LineKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Thermo => "thermo",
+            Self::Arrow { .. } => "arrow",
+        }
+    }
+}
+
+/// A path of cells with a rule along it, see [`Sudoku::with_lines`].
+///
+/// Lines read and print as the kind, then the cells from the start as
+/// one-based `rXcY` joined by `-`. An arrow's pill cells are joined by `+`
+/// instead:
+///
+/// ```
+/// use sodo::{Line, LineKind};
+///
+/// let thermo: Line = "thermo r1c1-r2c2-r2c3".parse().unwrap();
+/// assert_eq!(thermo.kind, LineKind::Thermo);
+/// assert_eq!(thermo.cells, [(0, 0), (1, 1), (1, 2)]);
+/// assert_eq!(thermo.to_string(), "thermo r1c1-r2c2-r2c3");
+///
+/// let arrow: Line = "arrow r1c1+r1c2-r2c3".parse().unwrap();
+/// assert_eq!(arrow.kind, LineKind::Arrow { pill: 2 });
+/// ```
+///
+/// [`Sudoku::with_lines`]: crate::Sudoku::with_lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct Line {
+    pub kind: LineKind,
+    /// Cells in path order, as (row, col).
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Line {
+    /// A thermometer from its bulb along `cells`.
+    pub fn thermo(cells: Vec<(usize, usize)>) -> Self {
+        Self {
+            kind: LineKind::Thermo,
+            cells,
+        }
+    }
+
+    /// An arrow whose `pill` (circle) cells sum up the `shaft` cells.
+    pub fn arrow(pill: Vec<(usize, usize)>, shaft: Vec<(usize, usize)>) -> Self {
+        Self {
+            kind: LineKind::Arrow { pill: pill.len() },
+            cells: pill.into_iter().chain(shaft).collect(),
+        }
+    }
+
+    /// Cells in the pill of an arrow; zero for other lines.
+    pub(crate) fn pill(&self) -> usize {
+        match self.kind {
+            LineKind::Arrow { pill } => pill,
+            LineKind::Thermo => 0,
+        }
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.kind.name())?;
+        for (i, &(r, c)) in self.cells.iter().enumerate() {
+            if i > 0 {
+                f.write_str(if i < self.pill() { "+" } else { "-" })?;
+            }
+            write!(f, "r{}c{}", r + 1, c + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Line {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (kind, path) = s
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or(format!("Expected a kind and a path in '{s}'"))?;
+        let mut steps = path.trim().split('-');
+        let head: Vec<&str> = steps.next().unwrap_or_default().split('+').collect();
+        let kind = match kind.to_ascii_lowercase().as_str() {
+            "thermo" if head.len() == 1 => LineKind::Thermo,
+            "arrow" => LineKind::Arrow { pill: head.len() },
+            "thermo" => return Err("Only arrows have pills".into()),
+            _ => return Err(format!("Unknown line kind '{kind}'")),
+        };
+        let cells = head
+            .into_iter()
+            .chain(steps)
+            .map(parse_cell)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { kind, cells })
+    }
+}
+
+/// Parses a one-based `rXcY` into a zero-based (row, col).
+pub(crate) fn parse_cell(s: &str) -> Result<(usize, usize), String> {
+    let bad = || format!("Invalid cell '{s}'");
+    let lower = s.trim().to_ascii_lowercase();
+    let (r, c) = lower
+        .strip_prefix('r')
+        .and_then(|rest| rest.split_once('c'))
+        .ok_or_else(bad)?;
+    let r: usize = r.parse().map_err(|_| bad())?;
+    let c: usize = c.parse().map_err(|_| bad())?;
+    if r == 0 || c == 0 {
+        return Err(bad());
+    }
+    Ok((r - 1, c - 1))
+}
+
+impl From<Line> for String {
+    fn from(line: Line) -> Self {
+        line.to_string()
+    }
+}
+
+impl TryFrom<String> for Line {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, String> {
+        s.parse()
+    }
+}
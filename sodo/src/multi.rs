@@ -0,0 +1,457 @@
+//! Overlapping multi-grid puzzles, such as Samurai.
+
+use crate::sodo::{Cell, House, Mask, Sudoku, digits, value_char};
+use crate::solver::{Difficulty, Solver, Stats};
+use rand::{Rng, seq::SliceRandom};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Several grids laid out on one board, sharing the cells where they
+/// overlap. Each grid keeps its own rules; a shared cell holds the same
+/// digit in every grid covering it.
+///
+/// ```
+/// use sodo::MultiSudoku;
+///
+/// let mut samurai = MultiSudoku::samurai();
+/// samurai.set(6, 6, 5).unwrap();
+/// // The centre grid's top-left box is the first grid's bottom-right one.
+/// assert_eq!(samurai.grid(2).get(0, 0).unwrap().value(), Some(5));
+/// assert!(!samurai.can_place(8, 8, 5));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MultiSudoku {
+    grids: Vec<Sudoku>,
+    /// Board position of each grid's top-left cell, as (row, col).
+    offsets: Vec<(usize, usize)>,
+}
+
+impl MultiSudoku {
+    /// Places empty `size`x`size` grids at the given board offsets. Where
+    /// grids overlap, the shared cells must line up with whole boxes in
+    /// each, or their boxes could disagree.
+    pub fn new(size: usize, offsets: Vec<(usize, usize)>) -> Result<Self, String> {
+        let (rows, cols) = Sudoku::box_shape(size).ok_or(format!("Invalid grid size {size}"))?;
+        if offsets.is_empty() {
+            return Err("No grids".into());
+        }
+        for (i, &(r, c)) in offsets.iter().enumerate() {
+            for &(r2, c2) in &offsets[..i] {
+                let overlaps = r.abs_diff(r2) < size && c.abs_diff(c2) < size;
+                if overlaps && (r.abs_diff(r2) % rows != 0 || c.abs_diff(c2) % cols != 0) {
+                    return Err(format!("Grid {} overlaps another off its box lines", i + 1));
+                }
+                if (r, c) == (r2, c2) {
+                    return Err(format!("Grid {} repeats an offset", i + 1));
+                }
+            }
+        }
+        Ok(Self {
+            grids: vec![Sudoku::new(size); offsets.len()],
+            offsets,
+        })
+    }
+
+    /// Samurai (Gattai-5): four 9x9 grids at the corners of a 21x21 board,
+    /// and a fifth in the centre sharing a corner box with each. Grids are
+    /// numbered top-left, top-right, centre, bottom-left, bottom-right.
+    pub fn samurai() -> Self {
+        let offsets = vec![(0, 0), (0, 12), (6, 6), (12, 0), (12, 12)];
+        Self::new(9, offsets).expect("Samurai layout is valid")
+    }
+
+    /// Replaces the contents with one string per grid, in
+    /// [`Sudoku::from_string`] form. Shared cells may be given in any of
+    /// their grids but must agree.
+    ///
+    /// On error the grids are left partially loaded.
+    pub fn load<S: AsRef<str>>(&mut self, grids: &[S]) -> Result<(), String> {
+        if grids.len() != self.grids.len() {
+            return Err(format!(
+                "Expected {} grids, got {}",
+                self.grids.len(),
+                grids.len()
+            ));
+        }
+        let n = self.size();
+        self.grids.iter_mut().for_each(Sudoku::clear);
+        for (g, s) in grids.iter().enumerate() {
+            let parsed =
+                Sudoku::from_string(s.as_ref(), n).map_err(|e| format!("Grid {}: {e}", g + 1))?;
+            let (top, left) = self.offsets[g];
+            for r in 0..n {
+                for c in 0..n {
+                    let cell = parsed.cell(r, c);
+                    if cell.is_empty() {
+                        continue;
+                    }
+                    match self.get(top + r, left + c).and_then(Cell::value) {
+                        Some(v) if Some(v) != cell.value() => {
+                            return Err(format!(
+                                "Grid {} disagrees at shared cell ({r},{c})",
+                                g + 1
+                            ));
+                        }
+                        _ => self.write(top + r, left + c, cell),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Side length of each grid.
+    pub fn size(&self) -> usize {
+        self.grids[0].size()
+    }
+
+    /// Board dimensions, as (rows, cols).
+    pub fn board_size(&self) -> (usize, usize) {
+        let n = self.size();
+        let rows = self.offsets.iter().map(|o| o.0 + n).max().unwrap_or(0);
+        let cols = self.offsets.iter().map(|o| o.1 + n).max().unwrap_or(0);
+        (rows, cols)
+    }
+
+    /// The component grids, in layout order.
+    pub fn grids(&self) -> &[Sudoku] {
+        &self.grids
+    }
+
+    /// The `i`th grid. Panics if out of range.
+    pub fn grid(&self, i: usize) -> &Sudoku {
+        &self.grids[i]
+    }
+
+    /// Board position of each grid's top-left cell, as (row, col).
+    pub fn offsets(&self) -> &[(usize, usize)] {
+        &self.offsets
+    }
+
+    /// Grids covering a board cell, with the cell's position in each.
+    pub fn covering(&self, row: usize, col: usize) -> Vec<(usize, usize, usize)> {
+        let n = self.size();
+        self.offsets
+            .iter()
+            .enumerate()
+            .filter(|&(_, &(top, left))| {
+                (top..top + n).contains(&row) && (left..left + n).contains(&col)
+            })
+            .map(|(g, &(top, left))| (g, row - top, col - left))
+            .collect()
+    }
+
+    /// The board cell at (row, col); `None` off the board or outside every
+    /// grid.
+    pub fn get(&self, row: usize, col: usize) -> Option<Cell> {
+        let &(g, r, c) = self.covering(row, col).first()?;
+        Some(self.grids[g].cell(r, c))
+    }
+
+    /// Sets a board cell in every grid covering it. Use 0 to clear.
+    pub fn set(&mut self, row: usize, col: usize, val: u8) -> Result<(), String> {
+        let covering = self.covering(row, col);
+        if covering.is_empty() {
+            return Err("Position outside every grid".into());
+        }
+        for (g, r, c) in covering {
+            self.grids[g].set(r, c, val)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a cell in every grid covering it.
+    fn write(&mut self, row: usize, col: usize, cell: Cell) {
+        for (g, r, c) in self.covering(row, col) {
+            self.grids[g].put(r, c, cell);
+        }
+    }
+
+    /// Checks if `val` fits at a board cell in every grid covering it.
+    pub fn can_place(&self, row: usize, col: usize, val: u8) -> bool {
+        let covering = self.covering(row, col);
+        !covering.is_empty()
+            && covering
+                .into_iter()
+                .all(|(g, r, c)| self.grids[g].can_place(r, c, val))
+    }
+
+    /// Candidates of an empty board cell: digits every covering grid allows.
+    pub(crate) fn candidate_mask(&self, row: usize, col: usize) -> Mask {
+        self.covering(row, col)
+            .into_iter()
+            .fold(Mask::MAX, |m, (g, r, c)| {
+                m & self.grids[g].candidate_mask(r, c)
+            })
+    }
+
+    /// Board cells covered by some grid, row-major.
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        let (rows, cols) = self.board_size();
+        (0..rows)
+            .flat_map(|r| (0..cols).map(move |c| (r, c)))
+            .filter(|&(r, c)| !self.covering(r, c).is_empty())
+            .collect()
+    }
+
+    /// True if every grid is valid and shared cells agree.
+    pub fn is_valid(&self) -> bool {
+        self.grids.iter().all(Sudoku::is_valid)
+            && self.cells().into_iter().all(|(row, col)| {
+                let mut values = self
+                    .covering(row, col)
+                    .into_iter()
+                    .map(|(g, r, c)| self.grids[g].cell(r, c).value());
+                let first = values.next().flatten();
+                values.all(|v| v == first)
+            })
+    }
+
+    /// Returns true if every grid is solved and shared cells agree.
+    pub fn is_solved(&self) -> bool {
+        self.grids.iter().all(Sudoku::is_complete) && self.is_valid()
+    }
+
+    /// Counts empty board cells, each shared cell once.
+    pub fn empty_count(&self) -> usize {
+        self.cells()
+            .into_iter()
+            .filter(|&(r, c)| self.get(r, c).is_some_and(Cell::is_empty))
+            .count()
+    }
+
+    /// Carries placements and eliminations across shared cells, so each
+    /// grid sees what the others worked out. Returns true on any change.
+    pub(crate) fn sync(&mut self) -> bool {
+        let mut changed = false;
+        for (row, col) in self.cells() {
+            let covering = self.covering(row, col);
+            if covering.len() < 2 {
+                continue;
+            }
+            let placed = covering
+                .iter()
+                .find_map(|&(g, r, c)| self.grids[g].cell(r, c).value());
+            match placed {
+                Some(v) => {
+                    for &(g, r, c) in &covering {
+                        if self.grids[g].cell(r, c).is_empty() {
+                            self.grids[g].put(r, c, Cell::Filled(v));
+                            changed = true;
+                        }
+                    }
+                }
+                None => {
+                    let allowed = self.candidate_mask(row, col);
+                    for &(g, r, c) in &covering {
+                        changed |= self.grids[g].eliminate(r, c, !allowed);
+                    }
+                }
+            }
+        }
+        changed
+    }
+}
+
+impl fmt::Display for MultiSudoku {
+    /// The board, with blanks where no grid reaches.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (rows, cols) = self.board_size();
+        for r in 0..rows {
+            let line: String = (0..cols)
+                .map(|c| match self.get(r, c) {
+                    Some(cell) => format!("{} ", value_char(cell.value().unwrap_or(0))),
+                    None => "  ".into(),
+                })
+                .collect();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl Solver {
+    /// Solves every grid of a multi-grid puzzle together: strategies run on
+    /// each grid in turn, with what they find shared across overlaps, then
+    /// backtracking over the whole board if enabled.
+    pub fn solve_multi(&mut self, mut puzzle: MultiSudoku) -> Result<MultiSudoku, String> {
+        if !puzzle.is_valid() {
+            return Err("Invalid initial state".into());
+        }
+
+        loop {
+            let before = (puzzle.empty_count(), eliminated(&puzzle));
+            for g in 0..puzzle.grids.len() {
+                self.apply_strategies(&mut puzzle.grids[g], &mut Stats::default());
+            }
+            puzzle.sync();
+            if !puzzle.is_valid() {
+                return Err("No solution found".into());
+            }
+            if (puzzle.empty_count(), eliminated(&puzzle)) == before {
+                break;
+            }
+        }
+
+        if puzzle.is_solved() || self.backtrack && multi_backtrack(&mut puzzle) {
+            Ok(puzzle)
+        } else {
+            Err("No solution found".into())
+        }
+    }
+
+    /// Generates a Samurai puzzle. The centre grid is filled first, then
+    /// each corner grid around the box it shares, so the five always agree.
+    /// Cells are dug only while the whole board keeps one solution, and the
+    /// clues left are [`Cell::Given`].
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let mut solver = Solver::new().seed(1);
+    /// let samurai = solver.generate_samurai(Difficulty::Medium).unwrap();
+    /// let clues = samurai.cells().into_iter().filter_map(|(r, c)| samurai.get(r, c));
+    /// assert!(clues.filter(|cell| !cell.is_empty()).all(|cell| cell.is_given()));
+    /// assert!(solver.solve_multi(samurai).unwrap().is_solved());
+    /// ```
+    pub fn generate_samurai(&mut self, difficulty: Difficulty) -> Result<MultiSudoku, String> {
+        let mut rng = self.rng();
+        let mut samurai = MultiSudoku::samurai();
+        let n = samurai.size();
+
+        // Boxes on a diagonal share no row or column, so any digits in
+        // them leave a grid completable. Each corner grid's shared box is
+        // on one of its diagonals.
+        let plan = [
+            (2, [0, 4, 8]),
+            (0, [8, 4, 0]),
+            (1, [6, 4, 2]),
+            (3, [2, 4, 6]),
+            (4, [0, 4, 8]),
+        ];
+        for (g, boxes) in plan {
+            let mut grid = samurai.grids[g].clone();
+            for b in boxes {
+                if grid
+                    .house_cells(House::Box(b))
+                    .iter()
+                    .all(|&(r, c)| grid.cell(r, c).is_empty())
+                {
+                    self.fill_box(&mut grid, b)?;
+                }
+            }
+            let grid = self.solve(grid)?;
+            let (top, left) = samurai.offsets[g];
+            for r in 0..n {
+                for c in 0..n {
+                    samurai.write(top + r, left + c, grid.cell(r, c));
+                }
+            }
+        }
+
+        let mut cells = samurai.cells();
+        let to_remove = Self::removal_target(cells.len(), difficulty, &mut rng);
+        cells.shuffle(&mut rng);
+        let (rows, cols) = samurai.board_size();
+        let solution = samurai.clone();
+        let mut removed = 0;
+        for (r, c) in cells {
+            if removed >= to_remove {
+                break;
+            }
+            if samurai.get(r, c).is_none_or(Cell::is_empty) {
+                continue;
+            }
+            let mut dug = vec![(r, c)];
+            // The board is symmetric under a half turn, like the grids.
+            let (sr, sc) = (rows - 1 - r, cols - 1 - c);
+            if removed + 1 < to_remove
+                && rng.random_bool(0.7)
+                && (sr, sc) != (r, c)
+                && samurai.get(sr, sc).is_some_and(|cell| !cell.is_empty())
+            {
+                dug.push((sr, sc));
+            }
+            // Clear the cells only if the board keeps one solution without
+            // them, else put them back for good.
+            for &(r, c) in &dug {
+                samurai.write(r, c, Cell::Empty);
+            }
+            if multi_count(&mut samurai.clone(), 2) == 1 {
+                removed += dug.len();
+            } else {
+                for &(r, c) in &dug {
+                    samurai.write(r, c, solution.get(r, c).unwrap_or(Cell::Empty));
+                }
+            }
+        }
+
+        for (r, c) in samurai.cells() {
+            if let Some(v) = samurai.get(r, c).and_then(Cell::value) {
+                samurai.write(r, c, Cell::Given(v));
+            }
+        }
+        Ok(samurai)
+    }
+}
+
+/// Candidates ruled out across all grids.
+fn eliminated(puzzle: &MultiSudoku) -> usize {
+    puzzle.grids.iter().map(Sudoku::eliminated).sum()
+}
+
+/// The empty board cell with the fewest candidates, and its candidates.
+fn multi_branch(puzzle: &MultiSudoku) -> Option<(usize, usize, Mask)> {
+    let mut best = None;
+    let mut fewest = u32::MAX;
+    for (r, c) in puzzle.cells() {
+        if puzzle.get(r, c).is_some_and(Cell::is_empty) {
+            let mask = puzzle.candidate_mask(r, c);
+            if mask.count_ones() < fewest {
+                fewest = mask.count_ones();
+                best = Some((r, c, mask));
+                if fewest <= 1 {
+                    break;
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Tries the candidates of the most constrained board cell, placing each in
+/// every grid that covers it.
+fn multi_backtrack(puzzle: &mut MultiSudoku) -> bool {
+    let Some((r, c, mask)) = multi_branch(puzzle) else {
+        return puzzle.is_solved();
+    };
+
+    for v in digits(mask) {
+        puzzle.write(r, c, Cell::Filled(v));
+        if multi_backtrack(puzzle) {
+            return true;
+        }
+        puzzle.write(r, c, Cell::Empty);
+    }
+    false
+}
+
+/// Counts the solutions of a valid board, stopping once `max` are found.
+fn multi_count(puzzle: &mut MultiSudoku, max: usize) -> usize {
+    let Some((r, c, mask)) = multi_branch(puzzle) else {
+        return usize::from(puzzle.is_solved());
+    };
+
+    let mut count = 0;
+    for v in digits(mask) {
+        puzzle.write(r, c, Cell::Filled(v));
+        count += multi_count(puzzle, max - count);
+        puzzle.write(r, c, Cell::Empty);
+        if count >= max {
+            break;
+        }
+    }
+    count
+}
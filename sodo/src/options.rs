@@ -0,0 +1,265 @@
+//! Limits that make long solves and generation abortable, and progress
+//! reports while they run.
+
+use crate::clock::Stopwatch;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Message of a search stopped by its [`Budget`], before the call turns it
+/// into [`SolveError::Aborted`].
+pub(crate) const ABORTED: &str = "Aborted";
+
+/// Backtracking nodes between clock readings.
+const CLOCK_EVERY: usize = 256;
+
+/// Backtracking nodes between progress reports.
+pub(crate) const REPORT_EVERY: usize = 4096;
+
+/// Where a [`SolveOptions`] call is, as passed to its progress hook.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Logical iterations of the solve in progress.
+    pub iterations: usize,
+    /// Backtracking nodes of the solve in progress.
+    pub backtracks: usize,
+    /// Clues removed so far when generating, out of `target`.
+    pub removed: usize,
+    pub target: usize,
+    /// Time since the call started (zero on targets without a clock).
+    pub elapsed: Duration,
+}
+
+/// Why a [`SolveOptions`] call failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// A bound was hit or the call was cancelled.
+    Aborted,
+    /// Anything else, such as a broken grid or no solution.
+    Failed(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Aborted => f.write_str(ABORTED),
+            Self::Failed(e) => f.write_str(e),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<SolveError> for String {
+    fn from(e: SolveError) -> Self {
+        e.to_string()
+    }
+}
+
+/// What a generation stopped by its [`SolveOptions`] gives back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OnBudget {
+    /// Fail with [`SolveError::Aborted`].
+    #[default]
+    Fail,
+    /// The puzzle closest to the asked difficulty so far, which may be one
+    /// still being dug. Fails with [`SolveError::Aborted`] if there is none
+    /// yet.
+    BestSoFar,
+}
+
+/// A progress hook, shared between clones of its [`SolveOptions`].
+pub type ProgressHook = Arc<Mutex<dyn FnMut(Progress) + Send>>;
+
+/// A flag for stopping a solve or generation from another thread. Clones
+/// share the flag.
+///
+/// ```
+/// use sodo::{CancelToken, SolveError, SolveOptions, Solver, Sudoku};
+///
+/// let token = CancelToken::new();
+/// token.cancel();
+/// let options = SolveOptions::new().cancel_token(token);
+/// let err = Solver::new()
+///     .solve_with_options(Sudoku::new(9), &options)
+///     .unwrap_err();
+/// assert_eq!(err, SolveError::Aborted);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every holder of the token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Bounds on one call of [`Solver::solve_with_options`] or
+/// [`Solver::generate_with_options`], and a hook to hear how it goes. A
+/// call that runs past its bounds fails with [`SolveError::Aborted`], or for
+/// a generation, may settle for the best puzzle so far (see [`OnBudget`]).
+///
+/// The hook hears of each logical iteration, every few thousand
+/// backtracking nodes, and each clue removed:
+///
+/// ```
+/// use sodo::{Difficulty, SolveOptions, Solver};
+/// use std::sync::{Arc, Mutex};
+///
+/// let removed = Arc::new(Mutex::new(0));
+/// let seen = removed.clone();
+/// let options = SolveOptions::new().progress(move |p| *seen.lock().unwrap() = p.removed);
+/// let puzzle = Solver::new()
+///     .generate_with_options(9, Difficulty::Easy, &options)
+///     .unwrap();
+/// assert_eq!(*removed.lock().unwrap(), puzzle.empty_count());
+/// ```
+///
+/// [`Solver::solve_with_options`]: crate::Solver::solve_with_options
+/// [`Solver::generate_with_options`]: crate::Solver::generate_with_options
+#[derive(Clone, Default)]
+pub struct SolveOptions {
+    /// Time allowed from the start of the call. Never reached on targets
+    /// without a clock.
+    pub deadline: Option<Duration>,
+    /// Logical iterations per solve, in place of
+    /// [`Solver::max_iterations`](crate::Solver::max_iterations).
+    pub max_iterations: Option<usize>,
+    pub cancel_token: Option<CancelToken>,
+    pub progress: Option<ProgressHook>,
+    /// Attempts a generation may make at the asked difficulty: removal
+    /// passes, or solution grids under a clue mask or required technique.
+    /// Running out counts as hitting a bound.
+    pub max_attempts: Option<usize>,
+    pub on_budget: OnBudget,
+}
+
+impl SolveOptions {
+    /// No bounds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn max_iterations(mut self, n: usize) -> Self {
+        self.max_iterations = Some(n);
+        self
+    }
+
+    pub fn max_attempts(mut self, n: usize) -> Self {
+        self.max_attempts = Some(n);
+        self
+    }
+
+    pub fn on_budget(mut self, policy: OnBudget) -> Self {
+        self.on_budget = policy;
+        self
+    }
+
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Calls `hook` as the call makes progress.
+    pub fn progress(mut self, hook: impl FnMut(Progress) + Send + 'static) -> Self {
+        self.progress = Some(Arc::new(Mutex::new(hook)));
+        self
+    }
+}
+
+impl fmt::Debug for SolveOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SolveOptions")
+            .field("deadline", &self.deadline)
+            .field("max_iterations", &self.max_iterations)
+            .field("cancel_token", &self.cancel_token)
+            .field("progress", &self.progress.is_some())
+            .field("max_attempts", &self.max_attempts)
+            .field("on_budget", &self.on_budget)
+            .finish()
+    }
+}
+
+/// [`SolveOptions`] in force, from the start of a call.
+pub(crate) struct Budget {
+    clock: Stopwatch,
+    deadline: Option<Duration>,
+    pub(crate) max_iterations: Option<usize>,
+    pub(crate) max_attempts: Option<usize>,
+    pub(crate) on_budget: OnBudget,
+    cancel: Option<CancelToken>,
+    /// Set once a bound is hit, so every level of a search unwinds.
+    spent: AtomicBool,
+    progress: Option<ProgressHook>,
+    /// The last report, for the fields an update leaves alone.
+    last: Mutex<Progress>,
+}
+
+impl Budget {
+    pub(crate) fn start(options: &SolveOptions) -> Self {
+        Self {
+            clock: Stopwatch::start(),
+            deadline: options.deadline,
+            max_iterations: options.max_iterations,
+            max_attempts: options.max_attempts,
+            on_budget: options.on_budget,
+            cancel: options.cancel_token.clone(),
+            spent: AtomicBool::new(false),
+            progress: options.progress.clone(),
+            last: Mutex::new(Progress::default()),
+        }
+    }
+
+    /// Applies `update` to the last report and passes it to the hook.
+    pub(crate) fn report(&self, update: impl FnOnce(&mut Progress)) {
+        let Some(hook) = &self.progress else {
+            return;
+        };
+        let progress = {
+            let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+            update(&mut last);
+            last.elapsed = self.clock.elapsed();
+            *last
+        };
+        (hook.lock().unwrap_or_else(|e| e.into_inner()))(progress);
+    }
+
+    /// True once a bound is hit. `nodes` paces the clock readings.
+    pub(crate) fn exhausted(&self, nodes: usize) -> bool {
+        if self.spent.load(Ordering::Relaxed) {
+            return true;
+        }
+        let hit = self.cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+            || nodes.is_multiple_of(CLOCK_EVERY)
+                && self.deadline.is_some_and(|d| self.clock.elapsed() >= d);
+        if hit {
+            self.spent.store(true, Ordering::Relaxed);
+        }
+        hit
+    }
+
+    /// True if a bound was hit.
+    pub(crate) fn spent(&self) -> bool {
+        self.spent.load(Ordering::Relaxed)
+    }
+
+    /// Marks a bound as hit.
+    pub(crate) fn spend(&self) {
+        self.spent.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,167 @@
+//! Parallel backtracking for single hard puzzles.
+//!
+//! The top of the search tree is forked into rayon tasks, whose work-stealing
+//! deques keep every core busy even when branches are wildly unbalanced. Below
+//! the split depth each task searches sequentially, checking a shared flag so
+//! the remaining tasks stop as soon as one finds a solution. Counting works
+//! the same way, with a shared tally in place of the flag. Every task checks
+//! the call's [`Budget`] along with the flag, and stops once it is spent.
+
+use crate::options::{Budget, REPORT_EVERY};
+use crate::sodo::{Cell, Sudoku};
+use crate::{Solver, Stats};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Number of branching levels forked into tasks before searching sequentially.
+const SPLIT_DEPTH: usize = 6;
+
+struct Shared<'a> {
+    done: AtomicBool,
+    nodes: AtomicUsize,
+    budget: Option<&'a Budget>,
+    /// Backtracking nodes before the search, for progress reports.
+    base: usize,
+}
+
+impl<'a> Shared<'a> {
+    fn new(budget: Option<&'a Budget>, base: usize) -> Self {
+        Self {
+            done: AtomicBool::new(false),
+            nodes: AtomicUsize::new(0),
+            budget,
+            base,
+        }
+    }
+
+    /// True once a task has finished the search or the budget is spent.
+    fn stopped(&self) -> bool {
+        self.done.load(Ordering::Relaxed) || self.budget.is_some_and(Budget::spent)
+    }
+
+    /// Counts a node, checking the budget and reporting progress on the way.
+    fn visit(&self) {
+        let nodes = self.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(budget) = self.budget {
+            budget.exhausted(nodes);
+            if nodes.is_multiple_of(REPORT_EVERY) {
+                budget.report(|p| p.backtracks = self.base + nodes);
+            }
+        }
+    }
+}
+
+/// Finds a solution by parallel backtracking, adding visited nodes to
+/// `stats`. `None` if there is none or `budget` ran out first.
+pub(crate) fn solve(sudoku: Sudoku, stats: &mut Stats, budget: Option<&Budget>) -> Option<Sudoku> {
+    let shared = Shared::new(budget, stats.backtracks);
+    let solution = fork(sudoku, 0, &shared);
+    stats.backtracks += shared.nodes.into_inner();
+    solution
+}
+
+fn fork(sudoku: Sudoku, depth: usize, shared: &Shared) -> Option<Sudoku> {
+    if shared.stopped() {
+        return None;
+    }
+    let Some(branch) = Solver::find_branch(&sudoku) else {
+        return sudoku.is_valid().then_some(sudoku);
+    };
+
+    if depth >= SPLIT_DEPTH {
+        let mut sudoku = sudoku;
+        return search(&mut sudoku, shared).then_some(sudoku);
+    }
+
+    // Forced moves don't open new branches, so they don't count towards the depth.
+    let next = depth + usize::from(branch.len() > 1);
+    branch.into_par_iter().find_map_any(|(r, c, v)| {
+        let mut child = sudoku.clone();
+        child.put(r, c, Cell::Filled(v));
+        shared.visit();
+
+        let found = fork(child, next, shared);
+        if found.is_some() {
+            shared.done.store(true, Ordering::Relaxed);
+        }
+        found
+    })
+}
+
+/// Sequential MRV backtracking that gives up once another task has
+/// succeeded or the budget is spent.
+fn search(sudoku: &mut Sudoku, shared: &Shared) -> bool {
+    if shared.stopped() {
+        return false;
+    }
+    let Some(branch) = Solver::find_branch(sudoku) else {
+        return sudoku.is_valid();
+    };
+
+    for (r, c, v) in branch {
+        sudoku.put(r, c, Cell::Filled(v));
+        shared.visit();
+        if search(sudoku, shared) {
+            return true;
+        }
+        sudoku.put(r, c, Cell::Empty);
+    }
+
+    false
+}
+
+/// Counts solutions by parallel backtracking, stopping once `max` are found
+/// or `budget` runs out, when the count falls short.
+pub(crate) fn count(sudoku: &Sudoku, max: usize, budget: Option<&Budget>) -> usize {
+    let shared = Shared::new(budget, 0);
+    let found = AtomicUsize::new(0);
+    count_fork(sudoku.clone(), 0, max, &found, &shared);
+    found.into_inner().min(max)
+}
+
+/// Adds a solution to the tally, finishing the count once it reaches `max`.
+fn tally(max: usize, found: &AtomicUsize, shared: &Shared) {
+    if found.fetch_add(1, Ordering::Relaxed) + 1 >= max {
+        shared.done.store(true, Ordering::Relaxed);
+    }
+}
+
+fn count_fork(mut sudoku: Sudoku, depth: usize, max: usize, found: &AtomicUsize, shared: &Shared) {
+    if depth >= SPLIT_DEPTH {
+        return count_search(&mut sudoku, max, found, shared);
+    }
+    if shared.stopped() {
+        return;
+    }
+    let Some(branch) = Solver::find_branch(&sudoku) else {
+        tally(max, found, shared);
+        return;
+    };
+
+    let next = depth + usize::from(branch.len() > 1);
+    branch.into_par_iter().for_each(|(r, c, v)| {
+        let mut child = sudoku.clone();
+        child.put(r, c, Cell::Filled(v));
+        shared.visit();
+        count_fork(child, next, max, found, shared);
+    });
+}
+
+/// Sequential counting that gives up once the tally reaches `max` or the
+/// budget is spent.
+fn count_search(sudoku: &mut Sudoku, max: usize, found: &AtomicUsize, shared: &Shared) {
+    if shared.stopped() {
+        return;
+    }
+    let Some(branch) = Solver::find_branch(sudoku) else {
+        tally(max, found, shared);
+        return;
+    };
+
+    for (r, c, v) in branch {
+        sudoku.put(r, c, Cell::Filled(v));
+        shared.visit();
+        count_search(sudoku, max, found, shared);
+        sudoku.put(r, c, Cell::Empty);
+    }
+}
@@ -0,0 +1,139 @@
+//! Curated reference puzzles, for tests, benchmarks and demos.
+//!
+//! ```
+//! use sodo::{Solver, puzzles};
+//!
+//! let hardest = puzzles::get("inkala-2012").unwrap();
+//! assert!(Solver::new().solve(hardest.sudoku()).unwrap().is_solved());
+//! assert!(puzzles::tagged("17-clue").count() >= 2);
+//! ```
+
+use crate::sodo::Sudoku;
+
+/// A named puzzle with its known properties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle {
+    pub name: &'static str,
+    pub size: usize,
+    /// Compact string, `.` for empty cells.
+    pub grid: &'static str,
+    /// Solution count, capped at 2.
+    pub solutions: usize,
+    pub tags: &'static [&'static str],
+}
+
+impl Puzzle {
+    /// Parses the grid; every entry here parses.
+    pub fn sudoku(&self) -> Sudoku {
+        Sudoku::from_string(self.grid, self.size).expect("reference puzzle parses")
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag)
+    }
+}
+
+/// Every reference puzzle.
+pub const ALL: &[Puzzle] = &[
+    Puzzle {
+        name: "wikipedia",
+        size: 9,
+        grid: "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        solutions: 1,
+        tags: &["classic", "symmetric"],
+    },
+    Puzzle {
+        name: "inkala-2012",
+        size: 9,
+        grid: "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        solutions: 1,
+        tags: &["hardest", "minimal"],
+    },
+    Puzzle {
+        name: "ai-escargot",
+        size: 9,
+        grid: "1....7.9..3..2...8..96..5....53..9...1..8...26....4...3......1..4......7..7...3..",
+        solutions: 1,
+        tags: &["hardest", "minimal"],
+    },
+    Puzzle {
+        name: "easter-monster",
+        size: 9,
+        grid: "1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1",
+        solutions: 1,
+        tags: &["hardest", "minimal"],
+    },
+    Puzzle {
+        name: "norvig-hardest",
+        size: 9,
+        grid: "..53.....8......2..7..1.5..4....53...1..7...6..32...8..6.5....9..4....3......97..",
+        solutions: 1,
+        tags: &["hardest", "minimal"],
+    },
+    Puzzle {
+        name: "royle-17-1",
+        size: 9,
+        grid: ".......1.4.........2...........5.4.7..8...3....1.9....3..4..2...5.1........8.6...",
+        solutions: 1,
+        tags: &["17-clue", "minimal"],
+    },
+    Puzzle {
+        name: "royle-17-2",
+        size: 9,
+        grid: ".......1.4.........2...........5.6.4..8...3....1.9....3..4..2...5.1........8.7...",
+        solutions: 1,
+        tags: &["17-clue", "minimal"],
+    },
+    Puzzle {
+        name: "mini-4x4",
+        size: 4,
+        grid: "..3.4.....1....2",
+        solutions: 1,
+        tags: &["small", "minimal"],
+    },
+    Puzzle {
+        name: "norvig-two-solutions",
+        size: 9,
+        grid: ".....6....59.....82....8....45........3........6..3.54...325..6..................",
+        solutions: 2,
+        tags: &["regression", "multiple-solutions"],
+    },
+    Puzzle {
+        name: "mini-4x4-two-solutions",
+        size: 4,
+        grid: "1...3.......2..4",
+        solutions: 2,
+        tags: &["regression", "small", "multiple-solutions"],
+    },
+    Puzzle {
+        name: "norvig-impossible",
+        size: 9,
+        grid: ".....5.8....6.1.43..........1.5........1.6...3.......553.....61........4.........",
+        solutions: 0,
+        tags: &["regression", "unsolvable"],
+    },
+    Puzzle {
+        name: "duplicate-in-row",
+        size: 9,
+        grid: "11...............................................................................",
+        solutions: 0,
+        tags: &["regression", "invalid"],
+    },
+    Puzzle {
+        name: "empty",
+        size: 9,
+        grid: ".................................................................................",
+        solutions: 2,
+        tags: &["regression", "multiple-solutions"],
+    },
+];
+
+/// Looks up a puzzle by name.
+pub fn get(name: &str) -> Option<&'static Puzzle> {
+    ALL.iter().find(|p| p.name == name)
+}
+
+/// Puzzles carrying `tag`, in catalogue order.
+pub fn tagged(tag: &str) -> impl Iterator<Item = &'static Puzzle> + '_ {
+    ALL.iter().filter(move |p| p.has_tag(tag))
+}
@@ -0,0 +1,497 @@
+//! Complete search by SAT, for grids whose rules stall the strategies.
+//!
+//! Each (row, col, value) is a boolean variable. Cells, houses, cages, move
+//! rules, marks and thermometers become clauses up front; a cage's sum picks
+//! one of the digit sets that add up to it. Arrows, clues and
+//! [`Constraint`](crate::Constraint)s are checked on each model instead, and
+//! a model that breaks one is ruled out for the cells it reads. The clauses
+//! go to a small CDCL solver: watched literals, first-UIP learning,
+//! activity-driven decisions with saved phases, and restarts.
+
+use crate::clue::ClueKind;
+use crate::line::LineKind;
+use crate::sodo::{Cell, House, Mask, Sudoku, bit, digits};
+
+/// A variable, shifted left once, with the low bit set if negated.
+type Lit = u32;
+
+const UNSET: i8 = 0;
+const TRUE: i8 = 1;
+const FALSE: i8 = -1;
+
+fn var(lit: Lit) -> usize {
+    (lit >> 1) as usize
+}
+
+/// The CDCL solver.
+#[derive(Default)]
+struct Cdcl {
+    clauses: Vec<Vec<Lit>>,
+    /// Clauses watching each literal, to visit when it turns false.
+    watches: Vec<Vec<usize>>,
+    /// Value of each variable.
+    assigns: Vec<i8>,
+    level: Vec<usize>,
+    /// Clause that implied each variable, its first literal.
+    reason: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    /// Trail length at each decision.
+    limits: Vec<usize>,
+    head: usize,
+    activity: Vec<f64>,
+    bump: f64,
+    /// Last value of each variable, for the next decision on it.
+    phase: Vec<bool>,
+    seen: Vec<bool>,
+    unsat: bool,
+    decisions: usize,
+}
+
+impl Cdcl {
+    fn new(vars: usize) -> Self {
+        Self {
+            watches: vec![Vec::new(); 2 * vars],
+            assigns: vec![UNSET; vars],
+            level: vec![0; vars],
+            reason: vec![None; vars],
+            activity: vec![0.0; vars],
+            bump: 1.0,
+            phase: vec![false; vars],
+            seen: vec![false; vars],
+            ..Self::default()
+        }
+    }
+
+    fn value(&self, lit: Lit) -> i8 {
+        let v = self.assigns[var(lit)];
+        if lit & 1 == 0 { v } else { -v }
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let v = var(lit);
+        self.assigns[v] = if lit & 1 == 0 { TRUE } else { FALSE };
+        self.level[v] = self.limits.len();
+        self.reason[v] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Adds a clause at the root, dropping literals settled there.
+    fn add(&mut self, lits: impl IntoIterator<Item = Lit>) {
+        self.backtrack(0);
+        let mut clause = Vec::new();
+        for lit in lits {
+            match self.value(lit) {
+                TRUE => return,
+                FALSE => {}
+                _ if clause.contains(&(lit ^ 1)) => return,
+                _ if !clause.contains(&lit) => clause.push(lit),
+                _ => {}
+            }
+        }
+        match clause[..] {
+            [] => self.unsat = true,
+            [lit] => {
+                self.enqueue(lit, None);
+                if self.propagate().is_some() {
+                    self.unsat = true;
+                }
+            }
+            _ => {
+                self.attach(clause);
+            }
+        }
+    }
+
+    fn attach(&mut self, clause: Vec<Lit>) -> usize {
+        let id = self.clauses.len();
+        self.watches[clause[0] as usize].push(id);
+        self.watches[clause[1] as usize].push(id);
+        self.clauses.push(clause);
+        id
+    }
+
+    /// Runs unit propagation, returning a clause left with every literal
+    /// false.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.head < self.trail.len() {
+            let falsified = self.trail[self.head] ^ 1;
+            self.head += 1;
+            let mut watching = std::mem::take(&mut self.watches[falsified as usize]);
+            let mut kept = 0;
+            let mut conflict = None;
+            for i in 0..watching.len() {
+                let id = watching[i];
+                if conflict.is_some() {
+                    watching[kept] = id;
+                    kept += 1;
+                    continue;
+                }
+                let clause = &mut self.clauses[id];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                let value = |lit: Lit| {
+                    let v = self.assigns[var(lit)];
+                    if lit & 1 == 0 { v } else { -v }
+                };
+                if value(first) == TRUE {
+                    watching[kept] = id;
+                    kept += 1;
+                    continue;
+                }
+                if let Some(k) = (2..clause.len()).find(|&k| value(clause[k]) != FALSE) {
+                    clause.swap(1, k);
+                    let lit = clause[1];
+                    self.watches[lit as usize].push(id);
+                    continue;
+                }
+                watching[kept] = id;
+                kept += 1;
+                if value(first) == FALSE {
+                    conflict = Some(id);
+                } else {
+                    self.enqueue(first, Some(id));
+                }
+            }
+            watching.truncate(kept);
+            self.watches[falsified as usize] = watching;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Learns the first-UIP clause of `conflict`, with the literal it
+    /// asserts first, and returns it with the level to jump back to.
+    fn analyze(&mut self, mut conflict: usize) -> (Vec<Lit>, usize) {
+        let current = self.limits.len();
+        let mut learnt = vec![0];
+        let mut open = 0;
+        let mut index = self.trail.len();
+        let mut implied = None;
+        loop {
+            let skip = usize::from(implied.is_some());
+            for k in skip..self.clauses[conflict].len() {
+                let lit = self.clauses[conflict][k];
+                let v = var(lit);
+                if !self.seen[v] && self.level[v] > 0 {
+                    self.seen[v] = true;
+                    self.bump(v);
+                    if self.level[v] == current {
+                        open += 1;
+                    } else {
+                        learnt.push(lit);
+                    }
+                }
+            }
+            loop {
+                index -= 1;
+                if self.seen[var(self.trail[index])] {
+                    break;
+                }
+            }
+            let lit = self.trail[index];
+            self.seen[var(lit)] = false;
+            open -= 1;
+            if open == 0 {
+                learnt[0] = lit ^ 1;
+                break;
+            }
+            implied = Some(lit);
+            conflict = self.reason[var(lit)].expect("implied literal has a reason");
+        }
+        for &lit in &learnt[1..] {
+            self.seen[var(lit)] = false;
+        }
+
+        // Watch the deepest of the rest second, so the jump leaves it false.
+        let mut back = 0;
+        if learnt.len() > 1 {
+            let deepest = (1..learnt.len())
+                .max_by_key(|&k| self.level[var(learnt[k])])
+                .unwrap_or(1);
+            learnt.swap(1, deepest);
+            back = self.level[var(learnt[1])];
+        }
+        (learnt, back)
+    }
+
+    fn bump(&mut self, v: usize) {
+        self.activity[v] += self.bump;
+        if self.activity[v] > 1e100 {
+            self.activity.iter_mut().for_each(|a| *a *= 1e-100);
+            self.bump *= 1e-100;
+        }
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.limits.len() <= level {
+            return;
+        }
+        let start = self.limits[level];
+        for &lit in &self.trail[start..] {
+            let v = var(lit);
+            self.phase[v] = lit & 1 == 0;
+            self.assigns[v] = UNSET;
+            self.reason[v] = None;
+        }
+        self.trail.truncate(start);
+        self.limits.truncate(level);
+        self.head = start;
+    }
+
+    /// The unset variable with the highest activity.
+    fn pick(&self) -> Option<usize> {
+        (0..self.assigns.len())
+            .filter(|&v| self.assigns[v] == UNSET)
+            .max_by(|&a, &b| self.activity[a].total_cmp(&self.activity[b]))
+    }
+
+    /// Searches for a model, left in `assigns`.
+    fn solve(&mut self) -> bool {
+        if self.unsat {
+            return false;
+        }
+        let mut conflicts = 0;
+        let mut restart = 100;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                if self.limits.is_empty() {
+                    self.unsat = true;
+                    return false;
+                }
+                let (learnt, back) = self.analyze(conflict);
+                self.backtrack(back);
+                let first = learnt[0];
+                if learnt.len() == 1 {
+                    self.enqueue(first, None);
+                } else {
+                    let id = self.attach(learnt);
+                    self.enqueue(first, Some(id));
+                }
+                self.bump /= 0.95;
+                conflicts += 1;
+                continue;
+            }
+            if conflicts >= restart {
+                self.backtrack(0);
+                restart += restart / 2;
+                continue;
+            }
+            let Some(v) = self.pick() else {
+                return true;
+            };
+            self.decisions += 1;
+            self.limits.push(self.trail.len());
+            let lit = (v as Lit) << 1 | Lit::from(!self.phase[v]);
+            self.enqueue(lit, None);
+        }
+    }
+}
+
+/// Clauses for `sudoku`, short of the rules checked on each model.
+struct Encoding {
+    cdcl: Cdcl,
+    n: usize,
+}
+
+/// The literal for (r, c) holding `v` in a grid `n` wide.
+fn lit(n: usize, r: usize, c: usize, v: u8) -> Lit {
+    (((r * n + c) * n + usize::from(v) - 1) as Lit) << 1
+}
+
+impl Encoding {
+    fn new(sudoku: &Sudoku) -> Self {
+        let n = sudoku.size();
+        let lit = |r, c, v| lit(n, r, c, v);
+        let cages = sudoku.cages().len();
+        let mut enc = Self {
+            cdcl: Cdcl::new(n * n * n),
+            n,
+        };
+
+        for r in 0..n {
+            for c in 0..n {
+                let mask = match sudoku.cell(r, c).value() {
+                    Some(v) => bit(v),
+                    None => sudoku.candidate_mask(r, c),
+                };
+                let values: Vec<u8> = digits(mask).collect();
+                enc.cdcl.add(values.iter().map(|&v| lit(r, c, v)));
+                for v in 1..=n as u8 {
+                    if !values.contains(&v) {
+                        enc.cdcl.add([lit(r, c, v) ^ 1]);
+                    }
+                }
+                enc.at_most_one(values.iter().map(|&v| lit(r, c, v)).collect());
+                for (pr, pc) in sudoku.move_peers(r, c) {
+                    if (pr, pc) > (r, c) {
+                        for v in 1..=n as u8 {
+                            enc.cdcl.add([lit(r, c, v) ^ 1, lit(pr, pc, v) ^ 1]);
+                        }
+                    }
+                }
+            }
+        }
+
+        for house in sudoku.houses().chain((0..cages).map(House::Cage)) {
+            let cells = sudoku.house_cells(house);
+            for v in 1..=n as u8 {
+                let lits: Vec<Lit> = cells.iter().map(|&(r, c)| lit(r, c, v)).collect();
+                if cells.len() == n {
+                    enc.cdcl.add(lits.iter().copied());
+                }
+                enc.at_most_one(lits);
+            }
+        }
+
+        // A cage holds one of the digit sets that add up to its sum, held as
+        // an extra variable each: no cell takes a digit outside the set.
+        for cage in sudoku.cages() {
+            let mut sets = Vec::new();
+            digit_sets(1, n as u8, cage.cells.len(), cage.sum, 0, &mut sets);
+            let first = enc.cdcl.assigns.len();
+            enc.grow(sets.len());
+            enc.cdcl
+                .add((0..sets.len()).map(|i| ((first + i) as Lit) << 1));
+            for (i, &set) in sets.iter().enumerate() {
+                let chosen = ((first + i) as Lit) << 1;
+                for v in (1..=n as u8).filter(|&v| set & bit(v) == 0) {
+                    for &(r, c) in &cage.cells {
+                        enc.cdcl.add([chosen ^ 1, lit(r, c, v) ^ 1]);
+                    }
+                }
+            }
+        }
+
+        for line in sudoku.lines() {
+            if line.kind == LineKind::Thermo {
+                for pair in line.cells.windows(2) {
+                    let ((ar, ac), (br, bc)) = (pair[0], pair[1]);
+                    for v in 1..=n as u8 {
+                        for w in 1..=v {
+                            enc.cdcl.add([lit(ar, ac, v) ^ 1, lit(br, bc, w) ^ 1]);
+                        }
+                    }
+                }
+            }
+        }
+        enc
+    }
+
+    fn grow(&mut self, vars: usize) {
+        let cdcl = &mut self.cdcl;
+        let total = cdcl.assigns.len() + vars;
+        cdcl.watches.resize(2 * total, Vec::new());
+        cdcl.assigns.resize(total, UNSET);
+        cdcl.level.resize(total, 0);
+        cdcl.reason.resize(total, None);
+        cdcl.activity.resize(total, 0.0);
+        cdcl.phase.resize(total, false);
+        cdcl.seen.resize(total, false);
+    }
+
+    fn at_most_one(&mut self, lits: Vec<Lit>) {
+        for (i, &a) in lits.iter().enumerate() {
+            for &b in &lits[i + 1..] {
+                self.cdcl.add([a ^ 1, b ^ 1]);
+            }
+        }
+    }
+
+    /// The grid the current model fills in.
+    fn model(&self, sudoku: &Sudoku) -> Sudoku {
+        let mut grid = sudoku.clone();
+        for r in 0..self.n {
+            for c in 0..self.n {
+                if grid.cell(r, c).is_empty()
+                    && let Some(v) =
+                        (1..=self.n as u8).find(|&v| self.cdcl.value(lit(self.n, r, c, v)) == TRUE)
+                {
+                    grid.put(r, c, Cell::Filled(v));
+                }
+            }
+        }
+        grid
+    }
+
+    /// Rules out the digits `model` gives `cells`.
+    fn block(&mut self, model: &Sudoku, cells: &[(usize, usize)]) {
+        let lits: Vec<Lit> = cells
+            .iter()
+            .filter_map(|&(r, c)| Some(lit(self.n, r, c, model.cell(r, c).value()?) ^ 1))
+            .collect();
+        self.cdcl.add(lits);
+    }
+
+    /// Cells of a rule `model` breaks among those checked on each model.
+    fn broken(model: &Sudoku) -> Option<Vec<(usize, usize)>> {
+        let value = |&(r, c): &(usize, usize)| u32::from(model.cell(r, c).value().unwrap_or(0));
+        for line in model.lines() {
+            if let LineKind::Arrow { pill } = line.kind {
+                let (head, shaft) = line.cells.split_at(pill);
+                let target = head.iter().fold(0, |t, cell| t * 10 + value(cell));
+                if shaft.iter().map(value).sum::<u32>() != target {
+                    return Some(line.cells.clone());
+                }
+            }
+        }
+        for (i, clue) in model.clues().iter().enumerate() {
+            let cells = model.clue_cells(i);
+            let kept = match clue.kind {
+                ClueKind::LittleKiller => cells.iter().map(value).sum::<u32>() == clue.value,
+            };
+            if !kept {
+                return Some(cells.to_vec());
+            }
+        }
+        if model.constraints().iter().any(|c| !c.is_valid(model)) {
+            let n = model.size();
+            return Some((0..n * n).map(|i| (i / n, i % n)).collect());
+        }
+        None
+    }
+}
+
+/// Collects the sets of `count` distinct digits from `from` to `n` that add
+/// up to `sum`, each on top of `set`.
+fn digit_sets(from: u8, n: u8, count: usize, sum: u32, set: Mask, out: &mut Vec<Mask>) {
+    if count == 0 {
+        if sum == 0 {
+            out.push(set);
+        }
+        return;
+    }
+    for v in from..=n {
+        if u32::from(v) > sum {
+            break;
+        }
+        digit_sets(v + 1, n, count - 1, sum - u32::from(v), set | bit(v), out);
+    }
+}
+
+/// Searches `sudoku` for up to `max` solutions, returning how many were
+/// found, the first of them, and the decisions made.
+pub(crate) fn solve(sudoku: &Sudoku, max: usize) -> (usize, Option<Sudoku>, usize) {
+    let mut enc = Encoding::new(sudoku);
+    let n = sudoku.size();
+    let empty: Vec<(usize, usize)> = (0..n * n)
+        .map(|i| (i / n, i % n))
+        .filter(|&(r, c)| sudoku.cell(r, c).is_empty())
+        .collect();
+    let mut count = 0;
+    let mut first = None;
+    while count < max && enc.cdcl.solve() {
+        let model = enc.model(sudoku);
+        if let Some(cells) = Encoding::broken(&model) {
+            enc.block(&model, &cells);
+            continue;
+        }
+        count += 1;
+        enc.block(&model, &empty);
+        first.get_or_insert(model);
+    }
+    (count, first, enc.cdcl.decisions)
+}
@@ -0,0 +1,240 @@
+//! Placing and taking back digits while candidates stay up to date.
+
+use crate::candidate::CandidateSet;
+use crate::sodo::{CandidateMode, Cell, Mask, Sudoku};
+
+/// A digit placed with [`SolveSession::place`] and what it changed.
+#[derive(Debug, Clone)]
+struct Placement {
+    row: usize,
+    col: usize,
+    value: u8,
+    /// Cells filled by propagation after it, in order.
+    forced: Vec<(usize, usize)>,
+    /// Cached candidates it overwrote, as (index, mask), oldest first.
+    saved: Vec<(usize, Mask)>,
+    /// Dead cells before it.
+    dead: usize,
+}
+
+/// A grid being filled in one digit at a time, keeping every cell's
+/// candidates between calls. A placement recomputes only the cells it can
+/// reach, then fills any of them left with a single candidate, and so on
+/// until nothing is forced. [`retract`](Self::retract) takes a placement
+/// back along with everything it forced, restoring the saved candidates
+/// instead of deriving them again.
+///
+/// ```
+/// use sodo::{SolveSession, Sudoku};
+///
+/// let puzzle = Sudoku::from_string(
+///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+///     9,
+/// )
+/// .unwrap();
+/// let mut session = SolveSession::new(puzzle);
+/// assert!(session.candidates(0, 2).contains(4));
+/// assert!(session.candidates(1, 1).contains(4));
+///
+/// session.place(0, 2, 4).unwrap();
+/// assert!(!session.candidates(1, 1).contains(4));
+///
+/// session.retract(0, 2).unwrap();
+/// assert!(session.candidates(1, 1).contains(4));
+/// assert!(session.sudoku().get(0, 2).unwrap().is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SolveSession {
+    sudoku: Sudoku,
+    /// Candidates per cell, row-major; 0 for filled cells.
+    cands: Vec<Mask>,
+    /// Empty cells without a candidate.
+    dead: usize,
+    placements: Vec<Placement>,
+}
+
+impl SolveSession {
+    /// Starts a session on `sudoku`. Candidates ruled out by strategies are
+    /// dropped, so the session's follow from the rules alone.
+    pub fn new(mut sudoku: Sudoku) -> Self {
+        sudoku.set_candidate_mode(CandidateMode::Lazy);
+        sudoku.clear_eliminations();
+        let n = sudoku.size();
+        let cands: Vec<Mask> = (0..n * n)
+            .map(|i| sudoku.candidate_mask(i / n, i % n))
+            .collect();
+        let dead = (0..n * n)
+            .filter(|&i| cands[i] == 0 && sudoku.cell(i / n, i % n).is_empty())
+            .count();
+        Self {
+            sudoku,
+            cands,
+            dead,
+            placements: Vec::new(),
+        }
+    }
+
+    /// The grid as placed so far.
+    pub fn sudoku(&self) -> &Sudoku {
+        &self.sudoku
+    }
+
+    pub fn into_sudoku(self) -> Sudoku {
+        self.sudoku
+    }
+
+    /// Candidates for a cell; empty if it is filled or out of bounds.
+    pub fn candidates(&self, row: usize, col: usize) -> CandidateSet {
+        let n = self.sudoku.size();
+        if row >= n || col >= n {
+            return CandidateSet::EMPTY;
+        }
+        CandidateSet::from_bits(self.cands[row * n + col])
+    }
+
+    /// True if some empty cell has no candidates left, so the placements
+    /// so far lead nowhere.
+    pub fn is_contradicted(&self) -> bool {
+        self.dead > 0
+    }
+
+    /// Digits placed with [`place`](Self::place) and not yet retracted, as
+    /// (row, col, value) in the order they were placed.
+    pub fn placements(&self) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        self.placements.iter().map(|p| (p.row, p.col, p.value))
+    }
+
+    /// Places `value` at (row, col), which must be one of its candidates,
+    /// and propagates. Returns how many cells the placement forced.
+    /// Propagation stops at a contradiction (see
+    /// [`is_contradicted`](Self::is_contradicted)), which the caller can
+    /// undo with [`retract`](Self::retract).
+    pub fn place(&mut self, row: usize, col: usize, value: u8) -> Result<usize, String> {
+        let n = self.sudoku.size();
+        if row >= n || col >= n {
+            return Err("Position out of bounds".into());
+        }
+        if !self.sudoku.cell(row, col).is_empty() {
+            return Err(format!("Cell ({row},{col}) is not empty"));
+        }
+        if !self.candidates(row, col).contains(value) {
+            return Err(format!("{value} is not a candidate at ({row},{col})"));
+        }
+
+        let mut placement = Placement {
+            row,
+            col,
+            value,
+            forced: Vec::new(),
+            saved: Vec::new(),
+            dead: self.dead,
+        };
+        let mut queue = self.fill(row, col, value, &mut placement.saved);
+        while let Some(i) = queue.pop() {
+            if self.dead > 0 {
+                break;
+            }
+            let (r, c) = (i / n, i % n);
+            let Some(v) = CandidateSet::from_bits(self.cands[i]).single() else {
+                continue;
+            };
+            if self.sudoku.cell(r, c).is_empty() {
+                queue.extend(self.fill(r, c, v, &mut placement.saved));
+                placement.forced.push((r, c));
+            }
+        }
+        let forced = placement.forced.len();
+        self.placements.push(placement);
+        Ok(forced)
+    }
+
+    /// Takes back the digit placed at (row, col) and every cell it forced.
+    /// Later placements are kept, and propagate again from the freed grid.
+    ///
+    /// A later placement made past a contradiction may no longer fit: the
+    /// freed grid can force its cell first. Then the session is left as it
+    /// was and the error returned.
+    ///
+    /// ```
+    /// use sodo::{SolveSession, Sudoku};
+    ///
+    /// let mut session = SolveSession::new(Sudoku::new(4));
+    /// for (r, c, v) in [(2, 2, 4), (2, 3, 2), (0, 3, 4), (3, 1, 1), (3, 0, 4)] {
+    ///     session.place(r, c, v).unwrap();
+    /// }
+    /// assert!(session.is_contradicted());
+    /// let before = session.sudoku().to_string_compact();
+    /// assert!(session.retract(2, 2).is_err());
+    /// assert_eq!(session.sudoku().to_string_compact(), before);
+    /// assert_eq!(session.placements().count(), 5);
+    /// ```
+    pub fn retract(&mut self, row: usize, col: usize) -> Result<(), String> {
+        let k = self
+            .placements
+            .iter()
+            .rposition(|p| (p.row, p.col) == (row, col))
+            .ok_or_else(|| format!("Cell ({row},{col}) was not placed in this session"))?;
+        let later: Vec<_> = self.placements[k + 1..]
+            .iter()
+            .map(|p| (p.row, p.col, p.value))
+            .collect();
+        let before = self.clone();
+        while self.placements.len() > k {
+            let placement = self.placements.pop().expect("placements past k");
+            self.undo(placement);
+        }
+        for (r, c, v) in later {
+            if let Err(e) = self.place(r, c, v) {
+                *self = before;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills (row, col) and refreshes the cells it reaches, saving their
+    /// old candidates. Returns the cells whose candidates changed.
+    fn fill(
+        &mut self,
+        row: usize,
+        col: usize,
+        value: u8,
+        saved: &mut Vec<(usize, Mask)>,
+    ) -> Vec<usize> {
+        let n = self.sudoku.size();
+        self.sudoku.put(row, col, Cell::Filled(value));
+        let cells = self
+            .sudoku
+            .reach(row, col)
+            .unwrap_or_else(|| (0..n * n).map(|i| (i / n, i % n)).collect());
+        let mut changed = Vec::new();
+        for (r, c) in cells {
+            let i = r * n + c;
+            let mask = self.sudoku.candidate_mask(r, c);
+            if mask == self.cands[i] {
+                continue;
+            }
+            saved.push((i, self.cands[i]));
+            let empty = self.sudoku.cell(r, c).is_empty();
+            if empty && mask == 0 {
+                self.dead += 1;
+            } else if empty && self.cands[i] == 0 {
+                self.dead -= 1;
+            }
+            self.cands[i] = mask;
+            changed.push(i);
+        }
+        changed
+    }
+
+    fn undo(&mut self, placement: Placement) {
+        for &(r, c) in placement.forced.iter().rev() {
+            self.sudoku.put(r, c, Cell::Empty);
+        }
+        self.sudoku.put(placement.row, placement.col, Cell::Empty);
+        for &(i, mask) in placement.saved.iter().rev() {
+            self.cands[i] = mask;
+        }
+        self.dead = placement.dead;
+    }
+}
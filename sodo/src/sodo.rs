@@ -1,9 +1,24 @@
-use std::collections::HashSet;
+use crate::candidate::CandidateSet;
+use crate::clue::{Clue, ClueKind};
+#[cfg(feature = "serde")]
+use crate::config::VariantConfig;
+use crate::constraint::{Constraint, Constraints};
+use crate::line::{Line, LineKind};
 use std::fmt;
+use std::sync::Arc;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+/// Bitmask over digits: bit `v - 1` is set for value `v`.
+pub(crate) type Mask = u64;
+
+/// Largest supported grid size (one bit per digit in a [`Mask`]).
+pub const MAX_SIZE: usize = Mask::BITS as usize;
+
+/// Flag bit marking a packed cell as a given clue.
+const GIVEN: u8 = 0x80;
+
 /// A single cell in a Sudoku grid.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -32,58 +47,843 @@ impl Cell {
     pub fn is_given(self) -> bool {
         matches!(self, Self::Given(_))
     }
+
+    /// Packs the cell into one byte: value in the low bits, given flag on top.
+    #[inline]
+    fn pack(self) -> u8 {
+        match self {
+            Self::Empty => 0,
+            Self::Given(v) => v | GIVEN,
+            Self::Filled(v) => v,
+        }
+    }
+
+    #[inline]
+    fn unpack(b: u8) -> Self {
+        match (b & !GIVEN, b & GIVEN != 0) {
+            (0, _) => Self::Empty,
+            (v, true) => Self::Given(v),
+            (v, false) => Self::Filled(v),
+        }
+    }
+}
+
+/// How a [`Sudoku`] keeps track of cell candidates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CandidateMode {
+    /// Derive candidates from the house masks on every lookup. Placements stay
+    /// cheap, which suits one-shot and batch solving.
+    #[default]
+    Lazy,
+    /// Keep a candidate mask per cell, refreshed for all peers on every
+    /// placement. Lookups are a single load, which suits interactive use.
+    Eager,
+}
+
+/// A row, column, box or variant region, by index. Boxes are numbered
+/// row-major.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum House {
+    Row(usize),
+    Col(usize),
+    Box(usize),
+    /// The main diagonal (0) or the anti-diagonal (1), under
+    /// [`Variant::Diagonal`].
+    Diagonal(usize),
+    /// A box-sized window between the boxes, under [`Variant::Hyper`],
+    /// numbered row-major.
+    Window(usize),
+    /// The cells at one position within every box, under
+    /// [`Variant::DisjointGroups`], numbered by that position row-major.
+    Group(usize),
+    /// A Killer cage, by index into [`Sudoku::cages`]. Cages are usually
+    /// smaller than a house, so [`Sudoku::houses`] leaves them out; they
+    /// show up in [`Sudoku::conflicts`] and hints.
+    Cage(usize),
+}
+
+/// An extra rule on top of rows, columns and boxes, see
+/// [`Sudoku::with_variant`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Variant {
+    /// X-Sudoku: both main diagonals hold every digit once.
+    Diagonal,
+    /// Hyper Sudoku (windoku): box-sized windows, inset one cell from the
+    /// boxes and one apart, hold every digit once. A 9x9 grid has four, at
+    /// rows and columns 2-4 and 6-8.
+    Hyper,
+    /// Disjoint groups: the cells at the same position within each box,
+    /// such as every box's centre, hold every digit once. Positions follow
+    /// the regular boxes even on a jigsaw grid.
+    DisjointGroups,
+    /// Anti-knight: cells a chess knight's move apart never hold the same
+    /// digit.
+    AntiKnight,
+    /// Anti-king: cells a chess king's move apart, including diagonally
+    /// touching ones, never hold the same digit.
+    AntiKing,
+}
+
+impl Variant {
+    /// (row, col) steps to the cells this rule keeps apart; empty for the
+    /// rules that add houses instead.
+    pub(crate) fn moves(self) -> &'static [(isize, isize)] {
+        match self {
+            Self::Diagonal | Self::Hyper | Self::DisjointGroups => &[],
+            Self::AntiKnight => &[
+                (-2, -1),
+                (-2, 1),
+                (-1, -2),
+                (-1, 2),
+                (1, -2),
+                (1, 2),
+                (2, -1),
+                (2, 1),
+            ],
+            Self::AntiKing => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// A Killer Sudoku cage: cells that hold distinct digits adding up to `sum`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cage {
+    pub sum: u32,
+    /// Cells as (row, col).
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// A mark drawn in a cell that narrows the digits it may hold, see
+/// [`Sudoku::with_marks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Mark {
+    /// Usually drawn as a circle.
+    Odd,
+    /// Usually drawn as a square.
+    Even,
+}
+
+impl Mark {
+    /// Digits the mark allows.
+    pub(crate) fn mask(self) -> Mask {
+        const ODD: Mask = Mask::MAX / 3;
+        match self {
+            Self::Odd => ODD,
+            Self::Even => !ODD,
+        }
+    }
+}
+
+/// Symmetry of the pattern of filled cells, strongest kind first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SymmetryKind {
+    /// Invariant under every rotation and reflection of the square.
+    Dihedral,
+    /// Invariant under quarter turns.
+    Rotational90,
+    /// Invariant under a half turn, the classic newspaper symmetry.
+    Rotational180,
+    /// Mirrored across the main or anti-diagonal.
+    Diagonal,
+    /// Mirrored across the horizontal or vertical axis.
+    Mirror,
+    None,
+}
+
+/// A digit appearing more than once in a house.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Conflict {
+    pub house: House,
+    pub value: u8,
+    /// Every cell in the house holding `value`, as (row, col).
+    pub cells: Vec<(usize, usize)>,
 }
 
 /// A Sudoku puzzle grid.
+///
+/// Cells are stored packed, one byte each, and every row, column and box keeps
+/// a bitmask of the digits it contains so candidate lookups don't rescan the grid.
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "SudokuRepr", try_from = "SudokuRepr")
+)]
 pub struct Sudoku {
-    pub grid: Vec<Vec<Cell>>,
-    pub size: usize,
-    pub box_size: usize,
+    cells: Vec<u8>,
+    size: usize,
+    box_rows: usize,
+    box_cols: usize,
+    rows: Vec<Mask>,
+    cols: Vec<Mask>,
+    boxes: Vec<Mask>,
+    variants: Vec<Variant>,
+    /// Irregular boxes of a jigsaw grid; `None` for regular ones.
+    regions: Option<Arc<Regions>>,
+    /// Killer cages; `None` for none.
+    cages: Option<Arc<Cages>>,
+    /// Line constraints; `None` for none.
+    lines: Option<Arc<Lines>>,
+    /// Clues outside the grid; `None` for none.
+    clues: Option<Arc<Clues>>,
+    /// Cell marks, row-major; `None` for none.
+    marks: Option<Arc<Vec<Option<Mark>>>>,
+    /// Digits each cell may hold, row-major, from
+    /// [`Sudoku::with_pencilmarks`]; `None` for every digit.
+    pencilmarks: Option<Arc<Vec<Mask>>>,
+    /// Rules registered with [`Sudoku::with_constraint`].
+    constraints: Constraints,
+    /// Houses added by the variants, each with the digits it contains.
+    extra: Vec<(House, Mask)>,
+    /// Per-cell candidates; only populated in [`CandidateMode::Eager`].
+    cands: Vec<Mask>,
+    /// Candidates ruled out by strategies, per cell; empty until the first
+    /// elimination and dropped whenever a value is taken back out.
+    removed: Vec<Mask>,
 }
 
 impl Sudoku {
-    /// Creates an empty Sudoku of the given size.
+    /// Creates an empty Sudoku of the given size, with boxes shaped by
+    /// [`box_shape`](Self::box_shape).
     pub fn new(size: usize) -> Self {
-        let box_size = (size as f64).sqrt() as usize;
-        assert!(box_size * box_size == size, "Size must be a perfect square");
+        let (rows, cols) = Self::box_shape(size)
+            .expect("Size must be a perfect square or split into rectangular boxes");
+        Self::with_boxes(rows, cols)
+    }
+
+    /// Creates an empty Sudoku whose boxes are `box_rows` tall and `box_cols`
+    /// wide, e.g. 2x3 for a 6x6 grid. The grid size is their product.
+    pub fn with_boxes(box_rows: usize, box_cols: usize) -> Self {
+        let size = box_rows * box_cols;
+        assert!(size > 0, "Box dimensions must be positive");
+        assert!(size <= MAX_SIZE, "Size must be at most {MAX_SIZE}");
 
         Self {
-            grid: vec![vec![Cell::Empty; size]; size],
+            cells: vec![0; size * size],
             size,
-            box_size,
+            box_rows,
+            box_cols,
+            rows: vec![0; size],
+            cols: vec![0; size],
+            boxes: vec![0; size],
+            variants: Vec::new(),
+            regions: None,
+            cages: None,
+            lines: None,
+            clues: None,
+            marks: None,
+            pencilmarks: None,
+            constraints: Constraints::default(),
+            extra: Vec::new(),
+            cands: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+
+    /// Adds a variant rule, which then holds everywhere: validity,
+    /// candidates, strategies, and the solver. Regions it adds count as
+    /// houses; move rules make the cells they reach peers.
+    ///
+    /// ```
+    /// use sodo::{Sudoku, Variant};
+    ///
+    /// let mut x = Sudoku::new(9).with_variant(Variant::Diagonal);
+    /// x.set(0, 0, 5).unwrap();
+    /// assert!(!x.can_place(8, 8, 5));
+    /// ```
+    pub fn with_variant(mut self, variant: Variant) -> Self {
+        if self.variants.contains(&variant) {
+            return self;
+        }
+        self.variants.push(variant);
+        let houses = match variant {
+            Variant::Diagonal => vec![House::Diagonal(0), House::Diagonal(1)],
+            Variant::Hyper => {
+                let across = (self.size / self.box_rows - 1) * (self.size / self.box_cols - 1);
+                (0..across).map(House::Window).collect()
+            }
+            Variant::DisjointGroups => (0..self.size).map(House::Group).collect(),
+            Variant::AntiKnight | Variant::AntiKing => Vec::new(),
+        };
+        for house in houses {
+            let mask = self
+                .house_cells(house)
+                .into_iter()
+                .filter_map(|(r, c)| self.cell(r, c).value())
+                .fold(0, |m, v| m | bit(v));
+            self.extra.push((house, mask));
+        }
+        self.rebuild_candidates();
+        self
+    }
+
+    /// Variant rules in force, in the order added.
+    pub fn variants(&self) -> &[Variant] {
+        &self.variants
+    }
+
+    /// Replaces the boxes with irregular regions for jigsaw Sudoku. `layout`
+    /// gives each cell's region as one character per cell, row-major; any
+    /// `size` distinct characters will do, each used for `size` cells.
+    ///
+    /// ```
+    /// use sodo::Sudoku;
+    ///
+    /// let layout = "1112\n1322\n3342\n3444".replace('\n', "");
+    /// let mut s = Sudoku::new(4).with_regions(&layout).unwrap();
+    /// s.set(0, 0, 1).unwrap();
+    /// assert!(!s.can_place(1, 0, 1));
+    /// assert!(s.can_place(1, 1, 1));
+    /// ```
+    pub fn with_regions(mut self, layout: &str) -> Result<Self, String> {
+        let n = self.size;
+        let len = layout.chars().count();
+        if len != n * n {
+            return Err(format!("Expected {} layout chars, got {len}", n * n));
+        }
+        let mut ids: Vec<char> = Vec::new();
+        let mut cells: Vec<Vec<(usize, usize)>> = Vec::new();
+        let mut of = Vec::with_capacity(n * n);
+        for (i, ch) in layout.chars().enumerate() {
+            let id = match ids.iter().position(|&x| x == ch) {
+                Some(id) => id,
+                None if ids.len() < n => {
+                    ids.push(ch);
+                    cells.push(Vec::new());
+                    ids.len() - 1
+                }
+                None => return Err(format!("More than {n} regions in layout")),
+            };
+            cells[id].push((i / n, i % n));
+            of.push(id as u8);
+        }
+        if let Some(id) = (0..n).find(|&id| cells.get(id).is_none_or(|c| c.len() != n)) {
+            let got = cells.get(id).map_or(0, Vec::len);
+            return Err(format!("Region {} has {got} cells, expected {n}", id + 1));
+        }
+
+        self.regions = Some(Arc::new(Regions { of, cells }));
+        self.boxes.fill(0);
+        for r in 0..n {
+            for c in 0..n {
+                if let Some(v) = self.cell(r, c).value() {
+                    let b = self.box_index(r, c);
+                    self.boxes[b] |= bit(v);
+                }
+            }
+        }
+        self.rebuild_candidates();
+        Ok(self)
+    }
+
+    /// The jigsaw layout from [`with_regions`](Self::with_regions), with
+    /// regions renamed `1`, `2`, ... in order of first cell; `None` for
+    /// regular boxes.
+    pub fn regions(&self) -> Option<String> {
+        let regions = self.regions.as_ref()?;
+        Some(regions.of.iter().map(|&id| value_char(id + 1)).collect())
+    }
+
+    /// Adds Killer cages. Cells may be left out of every cage, but none can
+    /// be in two, and each cage must have a sum its size can reach with
+    /// distinct digits. Cages are checked like houses, and candidates only
+    /// keep digits that can still make up a cage's sum.
+    ///
+    /// ```
+    /// use sodo::{Cage, Sudoku};
+    ///
+    /// let cage = Cage { sum: 3, cells: vec![(0, 0), (0, 1)] };
+    /// let s = Sudoku::new(9).with_cages(vec![cage]).unwrap();
+    /// assert!(s.can_place(0, 0, 2));
+    /// assert!(!s.can_place(0, 0, 3));
+    /// ```
+    pub fn with_cages(mut self, cages: Vec<Cage>) -> Result<Self, String> {
+        let n = self.size;
+        let mut of = vec![None; n * n];
+        for (i, cage) in cages.iter().enumerate() {
+            if cage.cells.is_empty() || cage.cells.len() > n {
+                return Err(format!("Cage {} has {} cells", i + 1, cage.cells.len()));
+            }
+            for &(r, c) in &cage.cells {
+                if r >= n || c >= n {
+                    return Err(format!("Cage {} has a cell out of bounds", i + 1));
+                }
+                if of[r * n + c].replace(i).is_some() {
+                    return Err(format!("Cell ({r},{c}) is in two cages"));
+                }
+            }
+            if combos(self.full_mask(), cage.cells.len(), cage.sum).is_none() {
+                return Err(format!(
+                    "Cage {} can't add up to {} in {} cells",
+                    i + 1,
+                    cage.sum,
+                    cage.cells.len()
+                ));
+            }
+        }
+
+        self.cages = (!cages.is_empty()).then(|| Arc::new(Cages { list: cages, of }));
+        self.rebuild_candidates();
+        Ok(self)
+    }
+
+    /// Killer cages, from [`with_cages`](Self::with_cages).
+    pub fn cages(&self) -> &[Cage] {
+        self.cages.as_ref().map_or(&[], |k| &k.list)
+    }
+
+    /// Adds line constraints such as thermometers and arrows. Each line
+    /// needs at least two cells, each touching the one before (diagonals
+    /// count), with none repeated; a thermometer can be no longer than the
+    /// grid is wide. An arrow has a pill of one or two cells and a shaft of
+    /// at least one, which may leave from either pill cell.
+    ///
+    /// ```
+    /// use sodo::{Line, Sudoku};
+    ///
+    /// let thermo = Line::thermo(vec![(0, 0), (0, 1), (0, 2)]);
+    /// let mut s = Sudoku::new(9).with_lines(vec![thermo]).unwrap();
+    /// assert!(!s.can_place(0, 0, 8));
+    /// s.set(0, 1, 5).unwrap();
+    /// assert!(!s.can_place(0, 2, 5) && s.can_place(0, 2, 6));
+    /// ```
+    pub fn with_lines(mut self, lines: Vec<Line>) -> Result<Self, String> {
+        let n = self.size;
+        let mut on = vec![Vec::new(); n * n];
+        for (i, line) in lines.iter().enumerate() {
+            let cells = &line.cells;
+            if cells.len() < 2 || line.kind == LineKind::Thermo && cells.len() > n {
+                return Err(format!("Line {} has {} cells", i + 1, cells.len()));
+            }
+            let pill = line.pill();
+            if line.kind != LineKind::Thermo && !(1..=2).contains(&pill) || pill >= cells.len() {
+                return Err(format!("Arrow {} has a pill of {pill} cells", i + 1));
+            }
+            for (k, &(r, c)) in cells.iter().enumerate() {
+                if r >= n || c >= n {
+                    return Err(format!("Line {} has a cell out of bounds", i + 1));
+                }
+                if cells[..k].contains(&(r, c)) {
+                    return Err(format!("Line {} crosses ({r},{c}) twice", i + 1));
+                }
+                let touches =
+                    |&(pr, pc): &(usize, usize)| pr.abs_diff(r) <= 1 && pc.abs_diff(c) <= 1;
+                let before = match k {
+                    0 => &[][..],
+                    _ if k == pill => &cells[..k],
+                    _ => &cells[k - 1..k],
+                };
+                if k > 0 && !before.iter().any(touches) {
+                    return Err(format!("Line {} jumps to ({r},{c})", i + 1));
+                }
+                on[r * n + c].push((i, k));
+            }
+        }
+
+        self.lines = (!lines.is_empty()).then(|| Arc::new(Lines { list: lines, on }));
+        self.rebuild_candidates();
+        Ok(self)
+    }
+
+    /// Line constraints, from [`with_lines`](Self::with_lines).
+    pub fn lines(&self) -> &[Line] {
+        self.lines.as_ref().map_or(&[], |l| &l.list)
+    }
+
+    /// Adds clues outside the grid, such as Little Killer sums. Each must
+    /// sit off an edge, its direction leading into the grid from `start`;
+    /// a Little Killer looks along a diagonal.
+    ///
+    /// ```
+    /// use sodo::{Clue, Direction, Sudoku};
+    ///
+    /// let clue = Clue::little_killer((0, 7), Direction::Se, 3);
+    /// let mut s = Sudoku::new(9).with_clues(vec![clue]).unwrap();
+    /// assert!(!s.can_place(0, 7, 3));
+    /// s.set(0, 7, 1).unwrap();
+    /// assert!(s.can_place(1, 8, 2) && !s.can_place(1, 8, 3));
+    /// ```
+    pub fn with_clues(mut self, clues: Vec<Clue>) -> Result<Self, String> {
+        let n = self.size;
+        let mut on = vec![Vec::new(); n * n];
+        let mut cells = Vec::with_capacity(clues.len());
+        for (i, clue) in clues.iter().enumerate() {
+            if !clue.is_outside(n) {
+                return Err(format!("Clue {} doesn't look in from outside", i + 1));
+            }
+            let len = clue.cells(n).len() as u32;
+            match clue.kind {
+                ClueKind::LittleKiller if !clue.direction.is_diagonal() => {
+                    return Err(format!(
+                        "Little Killer {} must look along a diagonal",
+                        i + 1
+                    ));
+                }
+                ClueKind::LittleKiller if !(len..=len * n as u32).contains(&clue.value) => {
+                    return Err(format!(
+                        "Little Killer {} can't add up to {} in {len} cells",
+                        i + 1,
+                        clue.value
+                    ));
+                }
+                ClueKind::LittleKiller => {}
+            }
+            for (r, c) in clue.cells(n) {
+                on[r * n + c].push(i);
+            }
+            cells.push(clue.cells(n));
+        }
+
+        self.clues = (!clues.is_empty()).then(|| {
+            Arc::new(Clues {
+                list: clues,
+                cells,
+                on,
+            })
+        });
+        self.rebuild_candidates();
+        Ok(self)
+    }
+
+    /// Outside clues, from [`with_clues`](Self::with_clues).
+    pub fn clues(&self) -> &[Clue] {
+        self.clues.as_ref().map_or(&[], |k| &k.list)
+    }
+
+    /// Cells clue `i` looks along.
+    pub(crate) fn clue_cells(&self, i: usize) -> &[(usize, usize)] {
+        self.clues.as_ref().map_or(&[], |k| &k.cells[i])
+    }
+
+    /// True if a clue looks along (row, col).
+    pub(crate) fn on_clue(&self, row: usize, col: usize) -> bool {
+        self.clues
+            .as_ref()
+            .is_some_and(|k| !k.on[row * self.size + col].is_empty())
+    }
+
+    /// Adds a rule of your own, which then holds like the built-in ones; see
+    /// [`Constraint`]. Such rules aren't serialized.
+    pub fn with_constraint(mut self, constraint: impl Constraint + 'static) -> Self {
+        self.constraints.0.push(Arc::new(constraint));
+        self.rebuild_candidates();
+        self
+    }
+
+    /// Rules from [`with_constraint`](Self::with_constraint), in the order
+    /// added.
+    pub fn constraints(&self) -> &[Arc<dyn Constraint>] {
+        &self.constraints.0
+    }
+
+    /// Marks cells odd or even, as given by (row, col) and the mark. A cell
+    /// may be marked once.
+    ///
+    /// ```
+    /// use sodo::{Mark, Sudoku};
+    ///
+    /// let s = Sudoku::new(9).with_marks(vec![((0, 0), Mark::Even)]).unwrap();
+    /// assert!(s.can_place(0, 0, 4));
+    /// assert!(!s.can_place(0, 0, 5));
+    /// ```
+    pub fn with_marks(mut self, marks: Vec<((usize, usize), Mark)>) -> Result<Self, String> {
+        let n = self.size;
+        let mut of = vec![None; n * n];
+        for &((r, c), mark) in &marks {
+            if r >= n || c >= n {
+                return Err(format!("Mark at ({r},{c}) is out of bounds"));
+            }
+            if of[r * n + c].replace(mark).is_some() {
+                return Err(format!("Cell ({r},{c}) is marked twice"));
+            }
         }
+
+        self.marks = (!marks.is_empty()).then(|| Arc::new(of));
+        self.rebuild_candidates();
+        Ok(self)
+    }
+
+    /// Cell marks, from [`with_marks`](Self::with_marks), row-major.
+    pub fn marks(&self) -> Vec<((usize, usize), Mark)> {
+        let Some(of) = &self.marks else {
+            return Vec::new();
+        };
+        let n = self.size;
+        (0..n * n)
+            .filter_map(|i| Some(((i / n, i % n), of[i]?)))
+            .collect()
+    }
+
+    /// The mark in (row, col), if any.
+    #[inline]
+    pub fn mark(&self, row: usize, col: usize) -> Option<Mark> {
+        self.marks.as_ref()?.get(row * self.size + col).copied()?
+    }
+
+    /// Limits each cell to the digits of its set, row-major, as in a
+    /// Sukaku: a puzzle given as candidates rather than digits. Like marks,
+    /// the limits hold as rules, so clearing a cell restores its
+    /// candidates only up to them.
+    pub fn with_pencilmarks(mut self, sets: Vec<CandidateSet>) -> Result<Self, String> {
+        let n = self.size;
+        if sets.len() != n * n {
+            return Err(format!("Expected {} candidate sets", n * n));
+        }
+        if let Some(i) = sets
+            .iter()
+            .position(|s| !s.is_subset(CandidateSet::full(n)))
+        {
+            return Err(format!("Candidates at ({},{}) exceed {n}", i / n, i % n));
+        }
+        self.pencilmarks = Some(Arc::new(sets.into_iter().map(CandidateSet::bits).collect()));
+        self.rebuild_candidates();
+        Ok(self)
+    }
+
+    /// Parses a Sukaku: an empty grid with candidates given per cell. Takes
+    /// either one whitespace-separated group of digits per cell, or `size`
+    /// characters per cell, the `k`-th being digit `k` or `0`/`.` where the
+    /// digit is ruled out, as written by [`to_pencilmarks`](Self::to_pencilmarks).
+    ///
+    /// ```
+    /// use sodo::{Solver, Sudoku};
+    ///
+    /// let sukaku = Sudoku::from_pencilmarks(
+    ///     "1 234 234 234  34 34 12 12  1234 1234 1234 1234  1234 1234 1234 1234",
+    ///     4,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(sukaku.empty_count(), 16);
+    /// assert_eq!(sukaku.candidates(0, 1).iter().collect::<Vec<_>>(), [2, 3, 4]);
+    /// assert!(Solver::new().solve(sukaku).unwrap().is_solved());
+    /// ```
+    pub fn from_pencilmarks(s: &str, size: usize) -> Result<Self, String> {
+        if Self::box_shape(size).is_none() {
+            return Err(format!("Invalid grid size {size}"));
+        }
+        let cells = size * size;
+        let groups: Vec<&str> = s.split_whitespace().collect();
+        let parse = |group: &str| -> Result<CandidateSet, String> {
+            group
+                .chars()
+                .map(|ch| parse_char(ch, size).ok_or_else(|| format!("Invalid char '{ch}'")))
+                .collect()
+        };
+        let sets = if groups.len() == cells {
+            groups.into_iter().map(parse).collect::<Result<_, _>>()?
+        } else {
+            let chars: Vec<char> = groups.concat().chars().collect();
+            if chars.len() != cells * size {
+                return Err(format!(
+                    "Expected {cells} candidate groups or {} characters",
+                    cells * size
+                ));
+            }
+            let mut sets = Vec::with_capacity(cells);
+            for cell in chars.chunks(size) {
+                let mut set = CandidateSet::EMPTY;
+                for (k, &ch) in cell.iter().enumerate() {
+                    match ch {
+                        '0' | '.' => {}
+                        _ if parse_char(ch, size) == Some(k as u8 + 1) => {
+                            set.insert(k as u8 + 1);
+                        }
+                        _ => return Err(format!("Unexpected '{ch}' for digit {}", k + 1)),
+                    }
+                }
+                sets.push(set);
+            }
+            sets
+        };
+        Self::new(size).with_pencilmarks(sets)
+    }
+
+    /// Every cell's candidates, `size` characters per cell: digit `k` in
+    /// place `k` if it is a candidate, `.` if not. A filled cell shows only
+    /// its digit. [`from_pencilmarks`](Self::from_pencilmarks) reads it back.
+    pub fn to_pencilmarks(&self) -> String {
+        let n = self.size;
+        let mut out = String::with_capacity(n * n * n);
+        for i in 0..n * n {
+            let mask = match self.cell(i / n, i % n).value() {
+                Some(v) => bit(v),
+                None => self.candidate_mask(i / n, i % n),
+            };
+            out.extend((1..=n as u8).map(|v| {
+                if mask & bit(v) != 0 {
+                    value_char(v)
+                } else {
+                    '.'
+                }
+            }));
+        }
+        out
+    }
+
+    /// Digits the pencilmarks allow in (row, col).
+    #[inline]
+    pub(crate) fn pencil_mask(&self, row: usize, col: usize) -> Mask {
+        self.pencilmarks
+            .as_ref()
+            .map_or(Mask::MAX, |p| p[row * self.size + col])
+    }
+
+    /// True if the grid has pencilmarks, from
+    /// [`with_pencilmarks`](Self::with_pencilmarks).
+    #[cfg(feature = "gpu")]
+    pub(crate) fn has_pencilmarks(&self) -> bool {
+        self.pencilmarks.is_some()
+    }
+
+    /// True if a line passes through (row, col).
+    pub(crate) fn on_line(&self, row: usize, col: usize) -> bool {
+        self.lines
+            .as_ref()
+            .is_some_and(|l| !l.on[row * self.size + col].is_empty())
+    }
+
+    /// Other cells on a line through (row, col) whose rule keeps their
+    /// digits apart from its own.
+    pub(crate) fn line_peers(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let Some(lines) = &self.lines else {
+            return Vec::new();
+        };
+        let mut out = Vec::new();
+        for &(i, _) in &lines.on[row * self.size + col] {
+            let line = &lines.list[i];
+            let apart = match line.kind {
+                LineKind::Thermo => true,
+                LineKind::Arrow { .. } => false,
+            };
+            for &p in line.cells.iter().filter(|_| apart) {
+                if p != (row, col) && !out.contains(&p) {
+                    out.push(p);
+                }
+            }
+        }
+        out
+    }
+
+    /// Index of the cage holding (row, col).
+    #[inline]
+    pub(crate) fn cage_of(&self, row: usize, col: usize) -> Option<usize> {
+        self.cages.as_ref()?.of[row * self.size + col]
+    }
+
+    /// Default box dimensions (rows, cols) for a grid size: square boxes for
+    /// perfect squares, otherwise the most square split with wider boxes, such
+    /// as 2x3 for 6 or 3x4 for 12. `None` for sizes that don't split, like 7.
+    pub fn box_shape(size: usize) -> Option<(usize, usize)> {
+        if size == 0 || size > MAX_SIZE {
+            return None;
+        }
+        let rows = (1..=size)
+            .take_while(|r| r * r <= size)
+            .filter(|r| size.is_multiple_of(*r))
+            .last()?;
+        (rows > 1 || size == 1).then_some((rows, size / rows))
     }
 
     /// Parses a Sudoku from a string representation.
     pub fn from_string(s: &str, size: usize) -> Result<Self, String> {
+        if Self::box_shape(size).is_none() {
+            return Err(format!("Invalid grid size {size}"));
+        }
         let mut sudoku = Self::new(size);
-        let chars: Vec<char> = s.chars().collect();
+        sudoku.load(s)?;
+        Ok(sudoku)
+    }
+
+    /// Replaces the grid contents with a parsed string, reusing this grid's buffers.
+    ///
+    /// On error the grid is left partially loaded.
+    pub fn load(&mut self, s: &str) -> Result<(), String> {
+        let size = self.size;
         let expected = size * size;
+        let len = s.chars().count();
 
-        if chars.len() != expected {
-            return Err(format!("Expected {expected} chars, got {}", chars.len()));
+        if len != expected {
+            return Err(format!("Expected {expected} chars, got {len}"));
         }
 
-        for (i, &ch) in chars.iter().enumerate() {
+        self.clear();
+        for (i, ch) in s.chars().enumerate() {
             let (r, c) = (i / size, i % size);
-            sudoku.grid[r][c] = match ch {
+            let cell = match ch {
                 '0' | '.' | ' ' => Cell::Empty,
                 _ => Cell::Given(
                     parse_char(ch, size)
                         .ok_or_else(|| format!("Invalid char '{ch}' at ({r},{c})"))?,
                 ),
             };
+            self.put(r, c, cell);
         }
 
-        Ok(sudoku)
+        Ok(())
+    }
+
+    /// Empties every cell, keeping the rules; candidates are what the rules
+    /// alone allow.
+    pub fn clear(&mut self) {
+        self.removed = Vec::new();
+        self.cells.fill(0);
+        self.rows.fill(0);
+        self.cols.fill(0);
+        self.boxes.fill(0);
+        self.extra.iter_mut().for_each(|(_, m)| *m = 0);
+        self.rebuild_candidates();
+    }
+
+    /// Returns the grid size (9 for a classic puzzle).
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the box width, which is the box side for square boxes (3 for a
+    /// classic puzzle).
+    #[inline]
+    pub fn box_size(&self) -> usize {
+        self.box_cols
+    }
+
+    /// Returns the box height in rows.
+    #[inline]
+    pub fn box_rows(&self) -> usize {
+        self.box_rows
+    }
+
+    /// Returns the box width in columns.
+    #[inline]
+    pub fn box_cols(&self) -> usize {
+        self.box_cols
     }
 
     /// Returns the cell at (row, col), if in bounds.
     #[inline]
     pub fn get(&self, row: usize, col: usize) -> Option<Cell> {
-        self.grid.get(row).and_then(|r| r.get(col).copied())
+        (row < self.size && col < self.size).then(|| self.cell(row, col))
+    }
+
+    /// Iterates over all cells in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.cells.iter().map(|&b| Cell::unpack(b))
     }
 
     /// Sets the cell value. Use 0 to clear.
@@ -91,78 +891,615 @@ impl Sudoku {
         if row >= self.size || col >= self.size {
             return Err("Position out of bounds".into());
         }
-        if val > self.size as u8 {
+        if val as usize > self.size {
             return Err(format!("Value {val} exceeds max {}", self.size));
         }
 
-        self.grid[row][col] = if val == 0 {
+        let cell = if val == 0 {
             Cell::Empty
         } else {
             Cell::Filled(val)
         };
+        self.put(row, col, cell);
         Ok(())
     }
 
+    /// Returns the cell at (row, col) without bounds checking against `size`.
+    #[inline]
+    pub(crate) fn cell(&self, row: usize, col: usize) -> Cell {
+        Cell::unpack(self.cells[row * self.size + col])
+    }
+
+    /// Writes a cell and keeps the house masks in sync.
+    pub(crate) fn put(&mut self, row: usize, col: usize, cell: Cell) {
+        let i = row * self.size + col;
+        let old = Cell::unpack(self.cells[i]);
+        self.cells[i] = cell.pack();
+
+        if old.value() == cell.value() {
+            return;
+        }
+
+        let b = self.box_index(row, col);
+        for i in 0..self.extra.len() {
+            let house = self.extra[i].0;
+            if !self.in_house(house, row, col) {
+                continue;
+            }
+            if let Some(v) = old.value()
+                && !self
+                    .house_cells(house)
+                    .into_iter()
+                    .any(|(r, c)| self.cell(r, c).value() == Some(v))
+            {
+                self.extra[i].1 &= !bit(v);
+            }
+            if let Some(v) = cell.value() {
+                self.extra[i].1 |= bit(v);
+            }
+        }
+        if let Some(v) = old.value() {
+            // Another copy of `v` may remain in an invalid grid, so rescan
+            // instead of blindly clearing the bit.
+            let bit = bit(v);
+            if !self.row_cells(row).any(|c| c.value() == Some(v)) {
+                self.rows[row] &= !bit;
+            }
+            if !self.col_cells(col).any(|c| c.value() == Some(v)) {
+                self.cols[col] &= !bit;
+            }
+            if !self.box_cells(b).any(|c| c.value() == Some(v)) {
+                self.boxes[b] &= !bit;
+            }
+        }
+        if let Some(v) = cell.value() {
+            self.rows[row] |= bit(v);
+            self.cols[col] |= bit(v);
+            self.boxes[b] |= bit(v);
+        }
+
+        // Eliminations may have relied on the value being taken out.
+        if old.value().is_some() && !self.removed.is_empty() {
+            self.removed = Vec::new();
+            self.rebuild_candidates();
+        } else if !self.cands.is_empty() {
+            self.refresh_peers(row, col);
+        }
+    }
+
+    /// Rules out the digits in `mask` for an empty cell. Returns true if any
+    /// of them was still a candidate.
+    pub(crate) fn eliminate(&mut self, row: usize, col: usize, mask: Mask) -> bool {
+        let hit = self.candidate_mask(row, col) & mask;
+        if hit == 0 {
+            return false;
+        }
+        let i = row * self.size + col;
+        if self.removed.is_empty() {
+            self.removed = vec![0; self.cells.len()];
+        }
+        self.removed[i] |= hit;
+        if let Some(c) = self.cands.get_mut(i) {
+            *c &= !hit;
+        }
+        true
+    }
+
+    /// Digits ruled out for a cell by [`eliminate`](Self::eliminate).
+    pub(crate) fn ruled_out(&self, row: usize, col: usize) -> Mask {
+        self.removed
+            .get(row * self.size + col)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Candidates ruled out so far by [`eliminate`](Self::eliminate).
+    pub(crate) fn eliminated(&self) -> usize {
+        self.removed.iter().map(|m| m.count_ones() as usize).sum()
+    }
+
+    /// Rules out `value` as a candidate for an empty cell, for custom
+    /// [`Strategy`](crate::Strategy) implementations. Returns true if it was
+    /// still a candidate. The elimination holds until a value is cleared.
+    pub fn remove_candidate(&mut self, row: usize, col: usize, value: u8) -> bool {
+        if row >= self.size || col >= self.size || value == 0 || value as usize > self.size {
+            return false;
+        }
+        self.eliminate(row, col, bit(value))
+    }
+
+    /// Returns how candidates are tracked.
+    #[inline]
+    pub fn candidate_mode(&self) -> CandidateMode {
+        if self.cands.is_empty() {
+            CandidateMode::Lazy
+        } else {
+            CandidateMode::Eager
+        }
+    }
+
+    /// Forgets every [`eliminate`](Self::eliminate), as clearing a value does.
+    pub(crate) fn clear_eliminations(&mut self) {
+        if !self.removed.is_empty() {
+            self.removed = Vec::new();
+            self.rebuild_candidates();
+        }
+    }
+
+    /// Switches how candidates are tracked; lookups return the same values either way.
+    pub fn set_candidate_mode(&mut self, mode: CandidateMode) {
+        match mode {
+            CandidateMode::Lazy => self.cands = Vec::new(),
+            CandidateMode::Eager if self.cands.is_empty() => {
+                self.cands = vec![0; self.size * self.size];
+                self.rebuild_candidates();
+            }
+            CandidateMode::Eager => {}
+        }
+    }
+
+    /// Builder-style variant of [`Sudoku::set_candidate_mode`].
+    pub fn with_candidate_mode(mut self, mode: CandidateMode) -> Self {
+        self.set_candidate_mode(mode);
+        self
+    }
+
+    /// Recomputes every cached candidate; nothing to do in lazy mode.
+    fn rebuild_candidates(&mut self) {
+        if !self.cands.is_empty() {
+            let n = self.size;
+            self.cands = (0..n * n)
+                .map(|i| self.derive_candidates(i / n, i % n))
+                .collect();
+        }
+    }
+
+    /// Recomputes cached candidates for every cell sharing a house with (row, col).
+    fn refresh_peers(&mut self, row: usize, col: usize) {
+        let n = self.size;
+        match self.reach(row, col) {
+            Some(cells) => {
+                for (r, c) in cells {
+                    self.cands[r * n + c] = self.derive_candidates(r, c);
+                }
+            }
+            None => self.rebuild_candidates(),
+        }
+    }
+
+    /// Cells whose candidates may change when (row, col) does, itself
+    /// included and possibly repeated; `None` when any cell may.
+    pub(crate) fn reach(&self, row: usize, col: usize) -> Option<Vec<(usize, usize)>> {
+        let n = self.size;
+        let b = self.box_index(row, col);
+        let mut cells = Vec::with_capacity(3 * n);
+        for k in 0..n {
+            cells.push((row, k));
+            cells.push((k, col));
+            cells.push(self.box_cell(b, k));
+        }
+        for house in self.extra_houses(row, col) {
+            cells.extend(self.house_cells(house));
+        }
+        if let Some(i) = self.cage_of(row, col) {
+            cells.extend(self.house_cells(House::Cage(i)));
+        }
+        cells.extend(self.move_peers(row, col));
+        // Lines and clues reach along their whole length, even where digits
+        // may repeat.
+        if let Some(lines) = &self.lines {
+            for &(i, _) in &lines.on[row * n + col] {
+                cells.extend(&lines.list[i].cells);
+            }
+        }
+        if let Some(clues) = &self.clues {
+            for &i in &clues.on[row * n + col] {
+                cells.extend(&clues.cells[i]);
+            }
+        }
+        for k in &self.constraints.0 {
+            cells.extend(k.reach(self, row, col)?);
+        }
+        Some(cells)
+    }
+
+    /// Iterates over the cells sharing a house with (row, col), each once.
+    pub fn peers(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> {
+        let n = self.size;
+        let b = self.box_index(row, col);
+        let row_peers = (0..n).filter(move |&c| c != col).map(move |c| (row, c));
+        let col_peers = (0..n).filter(move |&r| r != row).map(move |r| (r, col));
+        let box_peers: Vec<(usize, usize)> = (0..n)
+            .map(|k| self.box_cell(b, k))
+            .filter(|&(r, c)| r != row && c != col)
+            .collect();
+        let mut extra_peers: Vec<(usize, usize)> = Vec::new();
+        let mut houses = self.extra_houses(row, col);
+        houses.extend(self.cage_of(row, col).map(House::Cage));
+        for house in houses {
+            for (r, c) in self.house_cells(house) {
+                if r != row
+                    && c != col
+                    && self.box_index(r, c) != b
+                    && !extra_peers.contains(&(r, c))
+                {
+                    extra_peers.push((r, c));
+                }
+            }
+        }
+        for (r, c) in self.move_peers(row, col).chain(self.line_peers(row, col)) {
+            if r != row && c != col && self.box_index(r, c) != b && !extra_peers.contains(&(r, c)) {
+                extra_peers.push((r, c));
+            }
+        }
+        row_peers
+            .chain(col_peers)
+            .chain(box_peers)
+            .chain(extra_peers)
+    }
+
+    /// Cells a move rule such as [`Variant::AntiKnight`] keeps apart from
+    /// (row, col). May repeat cells when several rules reach them.
+    pub(crate) fn move_peers(
+        &self,
+        row: usize,
+        col: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let n = self.size;
+        self.variants
+            .iter()
+            .flat_map(|v| v.moves())
+            .filter_map(move |&(dr, dc)| {
+                let r = row.checked_add_signed(dr).filter(|&r| r < n)?;
+                let c = col.checked_add_signed(dc).filter(|&c| c < n)?;
+                Some((r, c))
+            })
+    }
+
+    /// True if every rule is one digit per house, so solving is an exact
+    /// cover problem: no cages, lines, clues, move rules or constraints.
+    pub(crate) fn is_exact_cover(&self) -> bool {
+        self.cages().is_empty()
+            && self.lines().is_empty()
+            && self.clues().is_empty()
+            && self.constraints().is_empty()
+            && self.variants.iter().all(|v| v.moves().is_empty())
+    }
+
+    /// Variant houses containing (row, col).
+    pub(crate) fn extra_houses(&self, row: usize, col: usize) -> Vec<House> {
+        self.extra
+            .iter()
+            .map(|&(h, _)| h)
+            .filter(|&h| self.in_house(h, row, col))
+            .collect()
+    }
+
+    /// Every house containing (row, col): row, column, box, then variant houses.
+    pub(crate) fn houses_at(&self, row: usize, col: usize) -> Vec<House> {
+        let mut houses = vec![
+            House::Row(row),
+            House::Col(col),
+            House::Box(self.box_index(row, col)),
+        ];
+        houses.extend(self.extra_houses(row, col));
+        houses
+    }
+
+    /// True if `house` contains (row, col).
+    pub(crate) fn in_house(&self, house: House, row: usize, col: usize) -> bool {
+        match house {
+            House::Row(r) => r == row,
+            House::Col(c) => c == col,
+            House::Box(b) => self.box_index(row, col) == b,
+            House::Diagonal(0) => row == col,
+            House::Diagonal(_) => row + col == self.size - 1,
+            House::Window(w) => {
+                let (top, left) = self.window_origin(w);
+                (top..top + self.box_rows).contains(&row)
+                    && (left..left + self.box_cols).contains(&col)
+            }
+            House::Group(g) => row % self.box_rows * self.box_cols + col % self.box_cols == g,
+            House::Cage(i) => self.cage_of(row, col) == Some(i),
+        }
+    }
+
+    /// Top-left cell of a [`House::Window`].
+    fn window_origin(&self, w: usize) -> (usize, usize) {
+        let across = self.size / self.box_cols - 1;
+        (
+            1 + w / across * (self.box_rows + 1),
+            1 + w % across * (self.box_cols + 1),
+        )
+    }
+
+    /// Every row, then every column, then every box, then any variant houses.
+    /// Cages are not included.
+    pub fn houses(&self) -> impl Iterator<Item = House> + use<> {
+        let n = self.size;
+        let extra: Vec<House> = self.extra.iter().map(|&(h, _)| h).collect();
+        (0..n)
+            .map(House::Row)
+            .chain((0..n).map(House::Col))
+            .chain((0..n).map(House::Box))
+            .chain(extra)
+    }
+
+    /// Cells of a house, as (row, col) in row-major order. A cage's cells
+    /// come in the order given.
+    pub fn house_cells(&self, house: House) -> Vec<(usize, usize)> {
+        let n = self.size;
+        if let House::Cage(i) = house {
+            return self.cages().get(i).map_or(Vec::new(), |k| k.cells.clone());
+        }
+        (0..n)
+            .map(|k| match house {
+                House::Row(r) => (r, k),
+                House::Col(c) => (k, c),
+                House::Box(b) => self.box_cell(b, k),
+                House::Diagonal(0) => (k, k),
+                House::Diagonal(_) => (k, n - 1 - k),
+                House::Window(w) => {
+                    let (top, left) = self.window_origin(w);
+                    (top + k / self.box_cols, left + k % self.box_cols)
+                }
+                House::Group(g) => self.shape().box_cell(k, g),
+                House::Cage(_) => unreachable!(),
+            })
+            .collect()
+    }
+
     /// Checks if the puzzle satisfies all Sudoku constraints.
     pub fn is_valid(&self) -> bool {
-        self.valid_rows() && self.valid_cols() && self.valid_boxes()
+        self.valid_rows()
+            && self.valid_cols()
+            && self.valid_boxes()
+            && self.valid_extra()
+            && self.valid_cages()
+            && self.valid_moves()
+            && self.valid_lines()
+            && self.valid_marks()
+            && self.valid_pencilmarks()
+            && self.valid_clues()
+            && self.constraints.0.iter().all(|k| k.is_valid(self))
     }
 
     /// Validates all rows.
     pub fn valid_rows(&self) -> bool {
-        (0..self.size).all(|r| self.valid_unit(self.row_cells(r)))
+        (0..self.size).all(|r| valid_unit(self.row_cells(r)))
     }
 
     /// Validates all columns.
     pub fn valid_cols(&self) -> bool {
-        (0..self.size).all(|c| self.valid_unit(self.col_cells(c)))
+        (0..self.size).all(|c| valid_unit(self.col_cells(c)))
     }
 
     /// Validates all boxes.
     pub fn valid_boxes(&self) -> bool {
-        let bs = self.box_size;
-        (0..bs).all(|br| (0..bs).all(|bc| self.valid_unit(self.box_cells(br, bc))))
+        (0..self.size).all(|b| valid_unit(self.box_cells(b)))
     }
 
-    fn valid_unit(&self, cells: impl Iterator<Item = Cell>) -> bool {
-        let mut seen = HashSet::new();
-        cells.filter_map(|c| c.value()).all(|v| seen.insert(v))
+    /// Validates the variant houses.
+    fn valid_extra(&self) -> bool {
+        self.extra.iter().all(|&(h, _)| {
+            valid_unit(
+                self.house_cells(h)
+                    .into_iter()
+                    .map(|(r, c)| self.cell(r, c)),
+            )
+        })
     }
 
-    /// Checks if a value can be placed at (row, col).
-    pub fn can_place(&self, row: usize, col: usize, val: u8) -> bool {
-        if row >= self.size || col >= self.size || val == 0 || val > self.size as u8 {
-            return false;
+    /// Validates the lines: digits rise along every thermometer, and no
+    /// arrow's shaft adds up past its pill, which a full arrow must match.
+    fn valid_lines(&self) -> bool {
+        self.lines().iter().all(|line| self.line_holds(line))
+    }
+
+    /// True if the digits placed on `line` keep to its rule so far.
+    pub(crate) fn line_holds(&self, line: &Line) -> bool {
+        match line.kind {
+            LineKind::Arrow { pill } => {
+                let values: Vec<Option<u8>> = line
+                    .cells
+                    .iter()
+                    .map(|&(r, c)| self.cell(r, c).value())
+                    .collect();
+                let (head, shaft) = values.split_at(pill);
+                let Some(target) = head
+                    .iter()
+                    .try_fold(0, |t, v| Some(t * 10 + usize::from((*v)?)))
+                else {
+                    return true;
+                };
+                let low: usize = shaft.iter().map(|v| v.map_or(1, usize::from)).sum();
+                low <= target && (low == target || shaft.contains(&None))
+            }
+            LineKind::Thermo => {
+                let mut values = line
+                    .cells
+                    .iter()
+                    .filter_map(|&(r, c)| self.cell(r, c).value());
+                let mut last = 0;
+                values.all(|v| {
+                    let rising = v > last;
+                    last = v;
+                    rising
+                })
+            }
         }
+    }
 
-        let target = Some(val);
+    /// Validates the clues: no Little Killer's digits add up past its value,
+    /// which a full diagonal must match.
+    fn valid_clues(&self) -> bool {
+        (0..self.clues().len()).all(|i| self.clue_total(i).is_none())
+    }
 
-        // Row
-        if self.grid[row].iter().any(|c| c.value() == target) {
-            return false;
+    /// What clue `i`'s digits add up to so far, if that already breaks it:
+    /// more than its value counting one for each empty cell, or anything
+    /// else once the diagonal is full.
+    pub(crate) fn clue_total(&self, i: usize) -> Option<u32> {
+        let clue = &self.clues()[i];
+        match clue.kind {
+            ClueKind::LittleKiller => {
+                let values = self
+                    .clue_cells(i)
+                    .iter()
+                    .map(|&(r, c)| self.cell(r, c).value());
+                let total: u32 = values.clone().flatten().map(u32::from).sum();
+                let empty = values.filter(|v| v.is_none()).count() as u32;
+                (total + empty > clue.value || empty == 0 && total != clue.value).then_some(total)
+            }
         }
+    }
 
-        // Column
-        if (0..self.size).any(|r| self.grid[r][col].value() == target) {
-            return false;
+    /// Validates the marks: every marked cell holds a digit it allows.
+    fn valid_marks(&self) -> bool {
+        self.marks().into_iter().all(|((r, c), mark)| {
+            self.cell(r, c)
+                .value()
+                .is_none_or(|v| mark.mask() & bit(v) != 0)
+        })
+    }
+
+    /// Validates the pencilmarks: every cell holds a digit they allow.
+    fn valid_pencilmarks(&self) -> bool {
+        let n = self.size;
+        self.pencilmarks.is_none()
+            || (0..n * n).all(|i| {
+                self.cell(i / n, i % n)
+                    .value()
+                    .is_none_or(|v| self.pencil_mask(i / n, i % n) & bit(v) != 0)
+            })
+    }
+
+    /// Validates the move rules: no digit repeated a move apart.
+    fn valid_moves(&self) -> bool {
+        let n = self.size;
+        (0..n * n).all(|i| {
+            let (row, col) = (i / n, i % n);
+            let Some(v) = self.cell(row, col).value() else {
+                return true;
+            };
+            self.move_peers(row, col)
+                .all(|(r, c)| self.cell(r, c).value() != Some(v))
+        })
+    }
+
+    /// Validates the cages: no repeats, and no more than the sum, which a
+    /// full cage must hit exactly.
+    pub fn valid_cages(&self) -> bool {
+        (0..self.cages().len()).all(|i| {
+            let cells = self.house_cells(House::Cage(i));
+            valid_unit(cells.iter().map(|&(r, c)| self.cell(r, c))) && self.cage_total(i).is_none()
+        })
+    }
+
+    /// What cage `i` adds up to so far, if that already breaks its sum.
+    pub(crate) fn cage_total(&self, i: usize) -> Option<u32> {
+        let cage = &self.cages()[i];
+        let values = cage.cells.iter().map(|&(r, c)| self.cell(r, c).value());
+        let total: u32 = values.clone().flatten().map(u32::from).sum();
+        let full = values.clone().all(|v| v.is_some());
+        (total > cage.sum || full && total != cage.sum).then_some(total)
+    }
+
+    /// Classifies the symmetry of the givens' pattern (which cells hold
+    /// them, not their values), so a player's entries don't count. A grid
+    /// with no [`Cell::Given`] at all, such as a generated puzzle, uses its
+    /// filled cells instead. When several apply, the strongest is returned.
+    pub fn symmetry(&self) -> SymmetryKind {
+        let n = self.size;
+        let givens = self.cells().any(Cell::is_given);
+        let clue = |r, c| {
+            let cell = self.cell(r, c);
+            if givens {
+                cell.is_given()
+            } else {
+                !cell.is_empty()
+            }
+        };
+        let maps = |t: fn(usize, usize, usize) -> (usize, usize)| {
+            (0..n * n).all(|i| {
+                let (r, c) = (i / n, i % n);
+                let (tr, tc) = t(n - 1, r, c);
+                clue(r, c) == clue(tr, tc)
+            })
+        };
+
+        let quarter = maps(|m, r, c| (c, m - r));
+        let main_diag = maps(|_, r, c| (c, r));
+        let anti_diag = maps(|m, r, c| (m - c, m - r));
+        if quarter && main_diag {
+            SymmetryKind::Dihedral
+        } else if quarter {
+            SymmetryKind::Rotational90
+        } else if maps(|m, r, c| (m - r, m - c)) {
+            SymmetryKind::Rotational180
+        } else if main_diag || anti_diag {
+            SymmetryKind::Diagonal
+        } else if maps(|m, r, c| (m - r, c)) || maps(|m, r, c| (r, m - c)) {
+            SymmetryKind::Mirror
+        } else {
+            SymmetryKind::None
         }
+    }
 
-        // Box
-        let (br, bc) = self.box_origin(row, col);
-        for r in br..br + self.box_size {
-            for c in bc..bc + self.box_size {
-                if self.grid[r][c].value() == target {
-                    return false;
+    /// Lists each digit repeated within a house or cage, with the cells
+    /// involved. For every rule [`is_valid`](Self::is_valid) checks, see
+    /// [`validate_detailed`](Self::validate_detailed).
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut out = Vec::new();
+        let cages = (0..self.cages().len()).map(House::Cage);
+        for house in self.houses().chain(cages) {
+            let cells = self.house_cells(house);
+            let (mut seen, mut dup): (Mask, Mask) = (0, 0);
+            for &(r, c) in &cells {
+                if let Some(v) = self.cell(r, c).value() {
+                    dup |= seen & bit(v);
+                    seen |= bit(v);
                 }
             }
+            for value in digits(dup) {
+                out.push(Conflict {
+                    house,
+                    value,
+                    cells: cells
+                        .iter()
+                        .copied()
+                        .filter(|&(r, c)| self.cell(r, c).value() == Some(value))
+                        .collect(),
+                });
+            }
+        }
+        out
+    }
+
+    /// Checks if a value can be placed at (row, col).
+    pub fn can_place(&self, row: usize, col: usize, val: u8) -> bool {
+        if row >= self.size || col >= self.size || val == 0 || val as usize > self.size {
+            return false;
         }
 
-        true
+        self.used_mask(row, col) & bit(val) == 0
+            && self.cage_mask(row, col)
+                & self.line_mask(row, col)
+                & self.mark_mask(row, col)
+                & self.pencil_mask(row, col)
+                & self.clue_mask(row, col)
+                & bit(val)
+                != 0
+            && self
+                .constraints
+                .0
+                .iter()
+                .all(|k| k.allows(self, row, col, val))
     }
 
     /// Returns true if all cells are filled.
     pub fn is_complete(&self) -> bool {
-        self.grid.iter().flatten().all(|c| !c.is_empty())
+        self.cells.iter().all(|&b| b != 0)
     }
 
     /// Returns true if complete and valid.
@@ -172,122 +1509,521 @@ impl Sudoku {
 
     /// Counts empty cells.
     pub fn empty_count(&self) -> usize {
-        self.grid.iter().flatten().filter(|c| c.is_empty()).count()
+        self.cells.iter().filter(|&&b| b == 0).count()
     }
 
     /// Finds the first empty cell.
     pub fn first_empty(&self) -> Option<(usize, usize)> {
-        for r in 0..self.size {
-            for c in 0..self.size {
-                if self.grid[r][c].is_empty() {
-                    return Some((r, c));
-                }
-            }
-        }
-        None
+        self.cells
+            .iter()
+            .position(|&b| b == 0)
+            .map(|i| (i / self.size, i % self.size))
+    }
+
+    /// Finds the first empty cell that no digit can fill.
+    pub(crate) fn first_blocked(&self) -> Option<(usize, usize)> {
+        let n = self.size;
+        (0..n * n)
+            .map(|i| (i / n, i % n))
+            .find(|&(r, c)| self.cell(r, c).is_empty() && self.candidate_mask(r, c) == 0)
     }
 
     /// Returns possible values for an empty cell.
-    pub fn candidates(&self, row: usize, col: usize) -> HashSet<u8> {
-        if !self.grid[row][col].is_empty() {
-            return HashSet::new();
+    pub fn candidates(&self, row: usize, col: usize) -> CandidateSet {
+        CandidateSet::from_bits(self.candidate_mask(row, col))
+    }
+
+    /// Returns the candidate bitmask for a cell (0 if the cell is filled).
+    #[inline]
+    pub(crate) fn candidate_mask(&self, row: usize, col: usize) -> Mask {
+        match self.cands.get(row * self.size + col) {
+            Some(&mask) => mask,
+            None => self.derive_candidates(row, col),
+        }
+    }
+
+    /// Computes a cell's candidates from the house masks.
+    #[inline]
+    fn derive_candidates(&self, row: usize, col: usize) -> Mask {
+        if !self.cell(row, col).is_empty() {
+            return 0;
         }
+        let mask = self.full_mask()
+            & !self.used_mask(row, col)
+            & !self.ruled_out(row, col)
+            & self.cage_mask(row, col)
+            & self.line_mask(row, col)
+            & self.mark_mask(row, col)
+            & self.pencil_mask(row, col)
+            & self.clue_mask(row, col);
+        self.constraint_mask(row, col, mask)
+    }
 
-        let mut cands: HashSet<u8> = (1..=self.size as u8).collect();
+    /// The digits of `mask` the registered constraints allow in (row, col),
+    /// each asked only about what the others left.
+    #[inline]
+    fn constraint_mask(&self, row: usize, col: usize, mask: Mask) -> Mask {
+        self.constraints.0.iter().fold(mask, |m, k| {
+            if m == 0 {
+                return 0;
+            }
+            m & k.allowed(self, row, col, CandidateSet::from_bits(m)).bits()
+        })
+    }
 
-        // Row
-        for c in &self.grid[row] {
-            if let Some(v) = c.value() {
-                cands.remove(&v);
+    /// Digits the clues looking along (row, col) allow there, whatever the
+    /// cell holds now. For a Little Killer that is what its value leaves
+    /// once the others' digits are placed and the open ones take 1 to the
+    /// largest digit each.
+    #[inline]
+    fn clue_mask(&self, row: usize, col: usize) -> Mask {
+        let Some(clues) = &self.clues else {
+            return Mask::MAX;
+        };
+        let n = self.size;
+        let mut mask = Mask::MAX;
+        for &i in &clues.on[row * n + col] {
+            let clue = &clues.list[i];
+            match clue.kind {
+                ClueKind::LittleKiller => {
+                    let (mut total, mut open) = (0, 0);
+                    for &(r, c) in clues.cells[i].iter().filter(|&&p| p != (row, col)) {
+                        match self.cell(r, c).value() {
+                            Some(v) => total += usize::from(v),
+                            None => open += 1,
+                        }
+                    }
+                    let rest = (clue.value as usize).saturating_sub(total);
+                    mask &= span(
+                        rest.saturating_sub(open * n).max(1),
+                        rest.saturating_sub(open).min(n),
+                    );
+                }
             }
         }
+        mask
+    }
 
-        // Column
-        for r in 0..self.size {
-            if let Some(v) = self.grid[r][col].value() {
-                cands.remove(&v);
+    /// Digits the mark in (row, col) allows; all of them without one.
+    #[inline]
+    fn mark_mask(&self, row: usize, col: usize) -> Mask {
+        self.mark(row, col).map_or(Mask::MAX, Mark::mask)
+    }
+
+    /// Digits the lines through (row, col) allow there, whatever the cell
+    /// holds now. On a thermometer that is the range left between the
+    /// digits placed before and after it, one step per cell.
+    #[inline]
+    fn line_mask(&self, row: usize, col: usize) -> Mask {
+        let Some(lines) = &self.lines else {
+            return Mask::MAX;
+        };
+        let n = self.size;
+        let mut allowed = Mask::MAX;
+        for &(i, k) in &lines.on[row * n + col] {
+            let line = &lines.list[i];
+            match line.kind {
+                LineKind::Thermo => {
+                    let (mut lo, mut hi) = (k + 1, n + k + 1 - line.cells.len());
+                    for (j, &(r, c)) in line.cells.iter().enumerate() {
+                        match self.cell(r, c).value().map(usize::from) {
+                            Some(v) if j < k => lo = lo.max(v + k - j),
+                            Some(v) if j > k => hi = hi.min(v.saturating_sub(j - k)),
+                            _ => {}
+                        }
+                    }
+                    allowed &= span(lo, hi);
+                }
+                LineKind::Arrow { pill } => allowed &= self.arrow_mask(&line.cells, pill, k),
             }
         }
+        allowed
+    }
+
+    /// [`line_mask`](Self::line_mask) for position `k` of an arrow: digits
+    /// that keep the pill's number within reach of the shaft's sum, with
+    /// every other empty cell anywhere from 1 to `size`.
+    fn arrow_mask(&self, cells: &[(usize, usize)], pill: usize, k: usize) -> Mask {
+        let n = self.size;
+        let value = |j: usize| {
+            let (r, c) = cells[j];
+            self.cell(r, c).value().map(usize::from).filter(|_| j != k)
+        };
+        // Possible digits of each pill cell, as ranges.
+        let digit = |j: usize| value(j).map_or((1, n), |v| (v, v));
+        let (mut low, mut high) = (0, 0);
+        for j in pill..cells.len() {
+            if j != k {
+                let (lo, hi) = digit(j);
+                low += lo;
+                high += hi;
+            }
+        }
+        if k >= pill {
+            // The shaft cell makes up what the pill asks beyond the rest.
+            let (mut least, mut most) = (0, 0);
+            for j in 0..pill {
+                let (lo, hi) = digit(j);
+                least = least * 10 + lo;
+                most = most * 10 + hi;
+            }
+            return span(
+                least.saturating_sub(high).max(1),
+                most.saturating_sub(low).min(n),
+            );
+        }
+        // A pill cell takes digits whose number some shaft sum can reach.
+        let other = (0..pill).find(|&j| j != k).map(digit);
+        (1..=n)
+            .filter(|&v| match (other, k) {
+                (None, _) => (low..=high).contains(&v),
+                (Some((lo, hi)), 0) => (lo..=hi).any(|u| (low..=high).contains(&(v * 10 + u))),
+                (Some((lo, hi)), _) => (lo..=hi).any(|t| (low..=high).contains(&(t * 10 + v))),
+            })
+            .fold(0, |m, v| m | bit(v as u8))
+    }
 
-        // Box
-        let (br, bc) = self.box_origin(row, col);
-        for r in br..br + self.box_size {
-            for c in bc..bc + self.box_size {
-                if let Some(v) = self.grid[r][c].value() {
-                    cands.remove(&v);
+    /// Digits the cage of (row, col) allows there, whatever the cell holds
+    /// now: those that leave its other empty cells a set of distinct digits
+    /// making up the rest of the sum. All digits outside a cage.
+    #[inline]
+    fn cage_mask(&self, row: usize, col: usize) -> Mask {
+        let Some(i) = self.cage_of(row, col) else {
+            return Mask::MAX;
+        };
+        let cage = &self.cages()[i];
+        let (mut placed, mut total, mut open) = (0, 0, 0);
+        for &(r, c) in &cage.cells {
+            match self.cell(r, c).value() {
+                Some(v) if (r, c) != (row, col) => {
+                    placed |= bit(v);
+                    total += u32::from(v);
                 }
+                _ => open += 1,
             }
         }
+        cage.sum
+            .checked_sub(total)
+            .and_then(|rest| combos(self.full_mask() & !placed, open, rest))
+            .unwrap_or(0)
+    }
 
-        cands
+    /// Digits already present in the houses of a cell.
+    #[inline]
+    fn used_mask(&self, row: usize, col: usize) -> Mask {
+        let used = self.rows[row] | self.cols[col] | self.boxes[self.box_index(row, col)];
+        let used = self
+            .extra
+            .iter()
+            .filter(|&&(h, _)| self.in_house(h, row, col))
+            .fold(used, |m, &(_, x)| m | x);
+        self.move_peers(row, col)
+            .filter_map(|(r, c)| self.cell(r, c).value())
+            .fold(used, |m, v| m | bit(v))
+    }
+
+    /// Mask with every digit of this grid set.
+    #[inline]
+    pub(crate) fn full_mask(&self) -> Mask {
+        Mask::MAX
+            .checked_shr((MAX_SIZE - self.size) as u32)
+            .unwrap_or(0)
     }
 
     fn row_cells(&self, r: usize) -> impl Iterator<Item = Cell> + '_ {
-        self.grid[r].iter().copied()
+        (0..self.size).map(move |c| self.cell(r, c))
     }
 
     fn col_cells(&self, c: usize) -> impl Iterator<Item = Cell> + '_ {
-        (0..self.size).map(move |r| self.grid[r][c])
+        (0..self.size).map(move |r| self.cell(r, c))
+    }
+
+    fn box_cells(&self, b: usize) -> impl Iterator<Item = Cell> + '_ {
+        (0..self.size).map(move |k| {
+            let (r, c) = self.box_cell(b, k);
+            self.cell(r, c)
+        })
+    }
+
+    #[inline]
+    pub(crate) fn box_index(&self, r: usize, c: usize) -> usize {
+        match &self.regions {
+            Some(g) => g.of[r * self.size + c] as usize,
+            None => self.shape().box_index(r, c),
+        }
     }
 
-    fn box_cells(&self, br: usize, bc: usize) -> impl Iterator<Item = Cell> + '_ {
-        let bs = self.box_size;
-        let (sr, sc) = (br * bs, bc * bs);
-        (sr..sr + bs).flat_map(move |r| (sc..sc + bs).map(move |c| self.grid[r][c]))
+    /// The `k`th cell of box `b`, in row-major order.
+    #[inline]
+    pub(crate) fn box_cell(&self, b: usize, k: usize) -> (usize, usize) {
+        match &self.regions {
+            Some(g) => g.cells[b][k],
+            None => self.shape().box_cell(b, k),
+        }
+    }
+
+    /// True if the boxes are irregular jigsaw regions.
+    #[inline]
+    pub(crate) fn is_jigsaw(&self) -> bool {
+        self.regions.is_some()
     }
 
     #[inline]
-    fn box_origin(&self, r: usize, c: usize) -> (usize, usize) {
-        let bs = self.box_size;
-        (r / bs * bs, c / bs * bs)
+    fn shape(&self) -> Shape {
+        Shape {
+            rows: self.box_rows,
+            cols: self.box_cols,
+        }
+    }
+
+    /// The character used for `value` in compact strings and [`Display`]:
+    /// `1`-`9`, then `A` for 10 up to `G` for 16 and on through `Z`, then
+    /// `a`-`z` and `@#$` for grids that big. Zero is `.`; `None` past the
+    /// largest grid.
+    ///
+    /// ```
+    /// use sodo::Sudoku;
+    ///
+    /// assert_eq!(Sudoku::symbol(9), Some('9'));
+    /// assert_eq!(Sudoku::symbol(16), Some('G'));
+    /// assert_eq!(Sudoku::symbol(65), None);
+    /// ```
+    ///
+    /// [`Display`]: fmt::Display
+    pub fn symbol(value: u8) -> Option<char> {
+        (usize::from(value) <= MAX_SIZE).then(|| value_char(value))
     }
 
     /// Returns a compact string representation (81 chars for 9x9).
     pub fn to_string_compact(&self) -> String {
-        self.grid
-            .iter()
-            .flatten()
-            .map(|c| match c.value() {
-                None => '.',
-                Some(v) if v <= 9 => (b'0' + v) as char,
-                Some(v) => (b'A' + v - 10) as char,
-            })
-            .collect()
+        let mut out = String::with_capacity(self.cells.len());
+        self.write_compact(&mut out);
+        out
+    }
+
+    /// Appends the compact string representation to `out`.
+    pub fn write_compact(&self, out: &mut String) {
+        out.extend(self.cells().map(|c| value_char(c.value().unwrap_or(0))));
     }
 }
 
 impl fmt::Display for Sudoku {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let bs = self.box_size;
+        // Jigsaw regions don't follow lines, so they get no separators.
+        let (br, bc) = match self.regions {
+            Some(_) => (self.size, self.size),
+            None => (self.box_rows, self.box_cols),
+        };
         for r in 0..self.size {
-            if r > 0 && r % bs == 0 {
-                writeln!(f, "{}", "-".repeat(self.size * 2 + bs - 1))?;
+            if r > 0 && r % br == 0 {
+                writeln!(f, "{}", "-".repeat(self.size * 2 + self.size / bc - 1))?;
             }
             for c in 0..self.size {
-                if c > 0 && c % bs == 0 {
+                if c > 0 && c % bc == 0 {
                     write!(f, "|")?;
                 }
-                match self.grid[r][c].value() {
-                    None => write!(f, ". ")?,
-                    Some(v) if v <= 9 => write!(f, "{v} ")?,
-                    Some(v) => write!(f, "{} ", (b'A' + v - 10) as char)?,
-                }
+                write!(f, "{} ", value_char(self.cell(r, c).value().unwrap_or(0)))?;
             }
             writeln!(f)?;
         }
+        // Outside clues have no room in the grid, so they follow it.
+        for clue in self.clues() {
+            writeln!(f, "{clue}")?;
+        }
         Ok(())
     }
 }
 
-/// Parses a character to a cell value.
-fn parse_char(ch: char, size: usize) -> Option<u8> {
-    if let Some(d) = ch.to_digit(10) {
-        let v = d as u8;
-        (1..=size as u8).contains(&v).then_some(v)
-    } else if ch.is_ascii_uppercase() {
-        let v = ch as u8 - b'A' + 10;
-        (10..=size as u8).contains(&v).then_some(v)
-    } else {
-        None
+/// Serialized form of a [`Sudoku`]; the house masks are rebuilt on load.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SudokuRepr {
+    grid: Vec<Vec<Cell>>,
+    size: usize,
+    /// Box width; the height is `size / box_size`.
+    box_size: usize,
+    /// Rules beyond the classic ones, as fields of their own.
+    #[serde(flatten)]
+    rules: VariantConfig,
+    /// Digits each cell may hold, one bit each, from
+    /// [`Sudoku::with_pencilmarks`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pencilmarks: Option<Vec<Mask>>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Sudoku> for SudokuRepr {
+    fn from(s: Sudoku) -> Self {
+        Self {
+            grid: (0..s.size).map(|r| s.row_cells(r).collect()).collect(),
+            size: s.size,
+            box_size: s.box_cols,
+            rules: s.config(),
+            pencilmarks: s.pencilmarks.as_deref().cloned(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SudokuRepr> for Sudoku {
+    type Error = String;
+
+    fn try_from(repr: SudokuRepr) -> Result<Self, String> {
+        let (size, cols) = (repr.size, repr.box_size);
+        if size == 0 || size > MAX_SIZE || cols == 0 || !size.is_multiple_of(cols) {
+            return Err(format!("Invalid dimensions {size}/{cols}"));
+        }
+        if repr.grid.len() != size || repr.grid.iter().any(|row| row.len() != size) {
+            return Err(format!("Expected {size}x{size} grid"));
+        }
+
+        let mut sudoku = Self::with_boxes(size / cols, cols).with_config(&repr.rules)?;
+        if let Some(bits) = repr.pencilmarks {
+            let sets = bits.into_iter().map(CandidateSet::from_bits).collect();
+            sudoku = sudoku.with_pencilmarks(sets)?;
+        }
+        for (r, row) in repr.grid.into_iter().enumerate() {
+            for (c, cell) in row.into_iter().enumerate() {
+                if cell.value().is_some_and(|v| v == 0 || v as usize > size) {
+                    return Err(format!("Invalid value at ({r},{c})"));
+                }
+                sudoku.put(r, c, cell);
+            }
+        }
+        Ok(sudoku)
+    }
+}
+
+/// Jigsaw regions, indexed both ways.
+#[derive(Debug)]
+struct Regions {
+    /// Region of each cell, row-major.
+    of: Vec<u8>,
+    /// Cells of each region, row-major.
+    cells: Vec<Vec<(usize, usize)>>,
+}
+
+/// Killer cages, indexed both ways.
+#[derive(Debug)]
+struct Cages {
+    list: Vec<Cage>,
+    /// Cage of each cell, row-major.
+    of: Vec<Option<usize>>,
+}
+
+/// Line constraints, with where each cell sits on them.
+#[derive(Debug)]
+struct Lines {
+    list: Vec<Line>,
+    /// (line, position along it) for each cell, row-major.
+    on: Vec<Vec<(usize, usize)>>,
+}
+
+/// Outside clues, with the cells each looks along.
+#[derive(Debug)]
+struct Clues {
+    list: Vec<Clue>,
+    /// Cells of each clue, from its start.
+    cells: Vec<Vec<(usize, usize)>>,
+    /// Clues looking along each cell, row-major.
+    on: Vec<Vec<usize>>,
+}
+
+/// Dimensions of regular boxes.
+#[derive(Clone, Copy)]
+struct Shape {
+    rows: usize,
+    cols: usize,
+}
+
+impl Shape {
+    #[inline]
+    fn box_index(self, r: usize, c: usize) -> usize {
+        // Boxes per band equals the box height.
+        r / self.rows * self.rows + c / self.cols
+    }
+
+    #[inline]
+    fn box_cell(self, b: usize, k: usize) -> (usize, usize) {
+        let (top, left) = (b / self.rows * self.rows, b % self.rows * self.cols);
+        (top + k / self.cols, left + k % self.cols)
+    }
+}
+
+/// Returns the mask bit for a value.
+#[inline]
+pub(crate) fn bit(v: u8) -> Mask {
+    1 << (v - 1)
+}
+
+/// Iterates over the values set in a mask, in ascending order.
+pub(crate) fn digits(mut mask: Mask) -> impl Iterator<Item = u8> {
+    std::iter::from_fn(move || {
+        (mask != 0).then(|| {
+            let v = mask.trailing_zeros() as u8 + 1;
+            mask &= mask - 1;
+            v
+        })
+    })
+}
+
+/// Mask of the digits from `lo` to `hi`, inclusive.
+pub(crate) fn span(lo: usize, hi: usize) -> Mask {
+    if lo == 0 || lo > hi || hi > MAX_SIZE {
+        return 0;
+    }
+    (Mask::MAX >> (MAX_SIZE - hi)) & !(bit(lo as u8) - 1)
+}
+
+/// Digits used by some set of `k` distinct digits from `free` adding up to
+/// `sum`; `None` if there is no such set.
+pub(crate) fn combos(free: Mask, k: usize, sum: u32) -> Option<Mask> {
+    if k == 0 {
+        return (sum == 0).then_some(0);
+    }
+    let mut out = None;
+    for v in digits(free) {
+        if u32::from(v) * k as u32 > sum {
+            break;
+        }
+        // Only larger digits follow, so each set is tried once.
+        let above = free & !(bit(v) | (bit(v) - 1));
+        if let Some(m) = combos(above, k - 1, sum - u32::from(v)) {
+            out = Some(out.unwrap_or(0) | m | bit(v));
+        }
+    }
+    out
+}
+
+fn valid_unit(cells: impl Iterator<Item = Cell>) -> bool {
+    let mut seen: Mask = 0;
+    cells.filter_map(|c| c.value()).all(|v| {
+        let fresh = seen & bit(v) == 0;
+        seen |= bit(v);
+        fresh
+    })
+}
+
+/// Value symbols in order, see [`Sudoku::symbol`].
+const SYMBOLS: &[u8; MAX_SIZE] =
+    b"123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz@#$";
+
+/// [`Sudoku::symbol`] for a cell value, `0` through [`MAX_SIZE`].
+pub(crate) fn value_char(value: u8) -> char {
+    match value {
+        0 => '.',
+        _ => SYMBOLS[value as usize - 1] as char,
     }
 }
+
+/// Parses a character to a cell value. Letters are case-insensitive until
+/// the grid needs lowercase ones of its own.
+pub(crate) fn parse_char(ch: char, size: usize) -> Option<u8> {
+    let ch = if size <= 35 {
+        ch.to_ascii_uppercase()
+    } else {
+        ch
+    };
+    let i = SYMBOLS[..size].iter().position(|&s| s as char == ch)?;
+    Some(i as u8 + 1)
+}
@@ -1,31 +1,304 @@
-use crate::sodo::{Cell, Sudoku};
-use crate::strategy::{Strategy, all as all_strategies};
-use rand::{Rng, rng, seq::SliceRandom};
+use crate::clock::Stopwatch;
+use crate::config::VariantConfig;
+use crate::options::{ABORTED, Budget, OnBudget, Progress, REPORT_EVERY, SolveError, SolveOptions};
+use crate::sodo::{
+    Cage, CandidateMode, Cell, House, Mark, Mask, Sudoku, SymmetryKind, Variant, bit, digits,
+    value_char,
+};
+use crate::strategy::{Step, Strategy, all as all_strategies};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng, rng, seq::IndexedRandom, seq::IteratorRandom, seq::SliceRandom};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::{AddAssign, RangeInclusive};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 
 /// Statistics collected during solving.
 #[derive(Debug, Clone, Default)]
 pub struct Stats {
     pub strategies_used: HashMap<String, usize>,
     pub cells_filled: usize,
+    /// Candidates ruled out by strategies, without filling a cell.
+    pub eliminations: usize,
     pub iterations: usize,
     pub backtracks: usize,
+    /// Strategy applications attempted, whether or not they made progress.
+    pub strategy_calls: usize,
+    /// Strategy applications that changed the grid.
+    pub propagations: usize,
+    /// Wall-clock time spent solving (zero on targets without a clock).
+    pub elapsed: Duration,
+    /// Time spent in each strategy, by name, whether or not it made progress.
+    pub strategy_time: HashMap<String, Duration>,
+    /// Time spent on strategies, and on searching once they stalled.
+    pub logic_time: Duration,
+    pub search_time: Duration,
+    /// Branch points where backtracking had more than one value to try.
+    pub guesses: usize,
+    /// Most placements backtracking held at once. Like `guesses`, left
+    /// at zero by the other [`Backend`]s and parallel search.
+    pub max_depth: usize,
 }
 
-/// Puzzle difficulty level.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Stats {
+    /// Zeroes every count, keeping the strategy names already seen so a
+    /// reused `Stats` doesn't allocate them again.
+    fn reset(&mut self) {
+        self.strategies_used.values_mut().for_each(|n| *n = 0);
+        self.strategy_time
+            .values_mut()
+            .for_each(|t| *t = Duration::ZERO);
+        *self = Self {
+            strategies_used: std::mem::take(&mut self.strategies_used),
+            strategy_time: std::mem::take(&mut self.strategy_time),
+            ..Self::default()
+        };
+    }
+
+    /// Backtracking nodes visited per second.
+    pub fn nodes_per_sec(&self) -> f64 {
+        per_sec(self.backtracks, self.elapsed)
+    }
+
+    /// Successful strategy applications per second.
+    pub fn propagations_per_sec(&self) -> f64 {
+        per_sec(self.propagations, self.elapsed)
+    }
+
+    /// Cells filled by logical strategies per second.
+    pub fn cells_per_sec(&self) -> f64 {
+        per_sec(self.cells_filled, self.elapsed)
+    }
+}
+
+fn per_sec(count: usize, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 { count as f64 / secs } else { 0.0 }
+}
+
+/// Adds `by` to the entry for `name`, allocating the key only when new.
+fn tally<T: AddAssign + Default>(map: &mut HashMap<String, T>, name: &str, by: T) {
+    match map.get_mut(name) {
+        Some(total) => *total += by,
+        None => {
+            map.insert(name.into(), by);
+        }
+    }
+}
+
+/// Puzzle difficulty level, set by the hardest technique a logical solve
+/// needs (see [`Solver::grade`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Difficulty {
+    /// Singles only.
     Easy,
+    /// Locked candidates and subsets.
     Medium,
+    /// Fish, wings and other short patterns.
     Hard,
+    /// Chains, or beyond the strategies altogether.
     Expert,
 }
 
+/// How backtracking picks the cell to branch on, see [`Solver::cell_order`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CellOrder {
+    /// The cell with the fewest candidates, the first of them in row-major
+    /// order, unless a digit has one place left in a house.
+    #[default]
+    Mrv,
+    /// [`Mrv`](Self::Mrv), breaking ties by the most empty peers.
+    MrvDegree,
+    /// [`Mrv`](Self::Mrv), breaking ties at random.
+    Random,
+    /// The first empty cell in row-major order.
+    RowMajor,
+}
+
+/// The order backtracking tries a cell's values in, see
+/// [`Solver::value_order`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ValueOrder {
+    #[default]
+    Ascending,
+    /// Values that rule out the fewest candidates among empty peers first.
+    LeastConstraining,
+    Random,
+}
+
+/// How [`Solver`] searches once its strategies stall.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Backend {
+    /// Depth-first search over the most constrained cell. Works under every
+    /// rule.
+    #[default]
+    Backtracking,
+    /// Dancing Links over the exact cover problem of the grid: far fewer
+    /// nodes on hard and many-solution grids. Falls back to backtracking
+    /// under cages, lines, clues, move rules or constraints.
+    DancingLinks,
+    /// A SAT encoding of every rule, cages and lines included, handed to a
+    /// built-in CDCL solver. Complete where the other backends have to
+    /// crawl, such as Killer cages under move rules. Counts match
+    /// backtracking's, including under arrows and [`Constraint`]s, which
+    /// are checked on each model rather than encoded:
+    ///
+    /// ```
+    /// use sodo::{Backend, Cage, Constraint, Line, Solver, Sudoku};
+    ///
+    /// /// The top-left cell is bigger than the one right of it.
+    /// struct Greater;
+    ///
+    /// impl Constraint for Greater {
+    ///     fn name(&self) -> &'static str {
+    ///         "Greater"
+    ///     }
+    ///
+    ///     fn allows(&self, sudoku: &Sudoku, row: usize, col: usize, value: u8) -> bool {
+    ///         let other = |r, c| sudoku.get(r, c).and_then(|cell| cell.value());
+    ///         match (row, col) {
+    ///             (0, 0) => other(0, 1).is_none_or(|v| value > v),
+    ///             (0, 1) => other(0, 0).is_none_or(|v| value < v),
+    ///             _ => true,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let cage = Cage { sum: 3, cells: vec![(0, 0), (0, 1)] };
+    /// let killer = Sudoku::new(4).with_cages(vec![cage]).unwrap();
+    /// let arrow = Line::arrow(vec![(0, 0)], vec![(1, 1), (2, 2)]);
+    /// let arrow = Sudoku::new(4).with_lines(vec![arrow]).unwrap();
+    /// let greater = Sudoku::new(4).with_constraint(Greater);
+    ///
+    /// let sat = Solver::new().backend(Backend::Sat);
+    /// for (grid, count) in [(Sudoku::new(4), 288), (killer, 48), (greater, 144), (arrow, 36)] {
+    ///     assert_eq!(sat.count_solutions(&grid, None), count);
+    ///     assert_eq!(Solver::new().count_solutions(&grid, None), count);
+    /// }
+    /// ```
+    ///
+    /// [`Constraint`]: crate::Constraint
+    #[cfg(feature = "sat")]
+    Sat,
+}
+
+/// Why [`Solver::solve_logical`] stopped short of a solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StuckReason {
+    /// The grid broke the rules to begin with.
+    Invalid,
+    /// The strategies ran into a broken rule or a cell with no candidates:
+    /// there is no solution.
+    Contradiction,
+    /// No strategy allowed could make progress.
+    NoProgress,
+    /// The solver's [`max_iterations`](Solver::max_iterations) ran out.
+    IterationLimit,
+}
+
+impl fmt::Display for StuckReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Invalid => "Invalid initial state",
+            Self::Contradiction => "No solution found",
+            Self::NoProgress => "No strategy applies",
+            Self::IterationLimit => "Too many iterations",
+        })
+    }
+}
+
+/// The furthest a logic-only solve got, from [`Solver::solve_logical`].
+#[derive(Debug, Clone)]
+pub struct Stuck {
+    pub grid: Sudoku,
+    pub reason: StuckReason,
+    pub stats: Stats,
+}
+
+/// A puzzle with exactly one solution, from
+/// [`Solver::generate_unique`], holding that solution and its grade too.
+#[derive(Debug, Clone)]
+pub struct UniquePuzzle {
+    puzzle: Sudoku,
+    solution: Sudoku,
+    grade: Difficulty,
+}
+
+impl UniquePuzzle {
+    pub fn puzzle(&self) -> &Sudoku {
+        &self.puzzle
+    }
+
+    pub fn solution(&self) -> &Sudoku {
+        &self.solution
+    }
+
+    /// The puzzle's [`Solver::grade`].
+    pub fn grade(&self) -> Difficulty {
+        self.grade
+    }
+
+    /// The puzzle and its solution.
+    pub fn into_parts(self) -> (Sudoku, Sudoku) {
+        (self.puzzle, self.solution)
+    }
+}
+
+/// Placements as (row, col, value), one of which must hold.
+type Branch = Vec<(usize, usize, u8)>;
+
+/// Buffers kept from one solve to the next, so a solver run over many
+/// puzzles doesn't allocate them again each time.
+#[derive(Default)]
+struct Scratch {
+    /// Branch being tried at each search depth.
+    branches: Vec<Branch>,
+    /// Per-house masks for [`Solver::hidden_single`].
+    houses: Vec<Mask>,
+    /// Stats of solves that don't hand them back.
+    stats: Stats,
+}
+
 /// A Sudoku solver using logical strategies and optional backtracking.
 pub struct Solver {
-    strategies: Vec<Box<dyn Strategy>>,
+    pub(crate) strategies: Vec<Box<dyn Strategy>>,
     max_iters: usize,
-    backtrack: bool,
+    pub(crate) backtrack: bool,
+    unique: bool,
+    config: VariantConfig,
+    jigsaw: bool,
+    killer: bool,
+    marks: bool,
+    /// Symmetry of the givens left by generation; `None` for the default
+    /// lean towards half-turn pairs.
+    symmetry: Option<SymmetryKind>,
+    /// Fewest and most givens generation may leave.
+    clues: Option<(usize, usize)>,
+    /// Strategy generated puzzles must need, by name.
+    technique: Option<String>,
+    /// Cells generation may leave givens in, row-major.
+    clue_mask: Option<Vec<bool>>,
+    /// Whether generation clears every given it can.
+    minimal: bool,
+    candidates: Option<CandidateMode>,
+    backend: Backend,
+    cell_order: CellOrder,
+    value_order: ValueOrder,
+    /// Bounds of the call in progress, from [`SolveOptions`].
+    budget: Option<Arc<Budget>>,
+    scratch: Mutex<Scratch>,
+    /// Source of every random choice, see [`Solver::seed`].
+    rng: Mutex<StdRng>,
+    #[cfg(feature = "parallel")]
+    parallel: bool,
 }
 
 impl Default for Solver {
@@ -37,11 +310,7 @@ impl Default for Solver {
 impl Solver {
     /// Creates a new solver with default strategies.
     pub fn new() -> Self {
-        Self {
-            strategies: all_strategies(),
-            max_iters: 1000,
-            backtrack: true,
-        }
+        Self::with_strategies(all_strategies())
     }
 
     /// Creates a solver with custom strategies.
@@ -50,9 +319,103 @@ impl Solver {
             strategies,
             max_iters: 1000,
             backtrack: true,
+            unique: false,
+            config: VariantConfig::new(),
+            jigsaw: false,
+            killer: false,
+            marks: false,
+            symmetry: None,
+            clues: None,
+            technique: None,
+            clue_mask: None,
+            minimal: false,
+            candidates: None,
+            backend: Backend::Backtracking,
+            cell_order: CellOrder::Mrv,
+            value_order: ValueOrder::Ascending,
+            budget: None,
+            scratch: Mutex::default(),
+            rng: Mutex::new(StdRng::from_rng(&mut rng())),
+            #[cfg(feature = "parallel")]
+            parallel: false,
         }
     }
 
+    /// Appends a strategy after the existing ones.
+    pub fn add_strategy(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.strategies.push(Box::new(strategy));
+        self
+    }
+
+    /// Inserts a strategy right after the one named `after`, or at the end
+    /// if there is none by that name.
+    pub fn insert_strategy_after(mut self, after: &str, strategy: impl Strategy + 'static) -> Self {
+        let at = self
+            .strategies
+            .iter()
+            .position(|s| s.name() == after)
+            .map_or(self.strategies.len(), |i| i + 1);
+        self.strategies.insert(at, Box::new(strategy));
+        self
+    }
+
+    /// Inserts a strategy right before the one named `before`, or at the end
+    /// if there is none by that name.
+    pub fn insert_strategy_before(
+        mut self,
+        before: &str,
+        strategy: impl Strategy + 'static,
+    ) -> Self {
+        let at = self
+            .strategies
+            .iter()
+            .position(|s| s.name() == before)
+            .unwrap_or(self.strategies.len());
+        self.strategies.insert(at, Box::new(strategy));
+        self
+    }
+
+    /// Drops every strategy named `name`.
+    pub fn remove_strategy(mut self, name: &str) -> Self {
+        self.strategies.retain(|s| s.name() != name);
+        self
+    }
+
+    /// Keeps only the strategies in `names`, tried in that order. Fails on a
+    /// name the solver doesn't have, or one given twice.
+    ///
+    /// ```
+    /// use sodo::Solver;
+    ///
+    /// let solver = Solver::new()
+    ///     .keep_strategies(&["Hidden Singles", "Naked Singles", "X-Wing"])
+    ///     .unwrap()
+    ///     .use_backtracking(false);
+    /// assert_eq!(
+    ///     solver.strategy_names(),
+    ///     ["Hidden Singles", "Naked Singles", "X-Wing"]
+    /// );
+    /// assert!(Solver::new().keep_strategies(&["Guessing"]).is_err());
+    /// ```
+    pub fn keep_strategies(mut self, names: &[&str]) -> Result<Self, String> {
+        let mut kept = Vec::with_capacity(names.len());
+        for name in names {
+            let i = self
+                .strategies
+                .iter()
+                .position(|s| s.name() == *name)
+                .ok_or(format!("Unknown strategy '{name}'"))?;
+            kept.push(self.strategies.remove(i));
+        }
+        self.strategies = kept;
+        Ok(self)
+    }
+
+    /// Names of the configured strategies, in the order they are tried.
+    pub fn strategy_names(&self) -> Vec<&'static str> {
+        self.strategies.iter().map(|s| s.name()).collect()
+    }
+
     /// Sets maximum iterations for logical solving.
     pub fn max_iterations(mut self, n: usize) -> Self {
         self.max_iters = n;
@@ -65,55 +428,678 @@ impl Solver {
         self
     }
 
+    /// Asserts that puzzles have exactly one solution, enabling strategies
+    /// that rely on it (see [`Strategy::assumes_unique`]). On a puzzle with
+    /// several solutions they may rule out all of them.
+    pub fn assume_unique(mut self, enabled: bool) -> Self {
+        self.unique = enabled;
+        self
+    }
+
+    /// Adds a variant rule to the grids this solver builds itself: those from
+    /// [`generate`](Self::generate) and [`solve_many`](Self::solve_many).
+    /// Other puzzles are solved under their own [`Sudoku::variants`].
+    pub fn variant(mut self, variant: Variant) -> Self {
+        self.config = self.config.variant(variant);
+        self
+    }
+
+    /// Sets every rule of the grids this solver builds itself, as with
+    /// [`variant`](Self::variant). The options for jigsaw, Killer and marked
+    /// puzzles replace the layout, cages and marks it gives.
+    pub fn config(mut self, config: VariantConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Generates jigsaw puzzles, each on a fresh random layout of connected
+    /// regions; see [`Sudoku::with_regions`].
+    pub fn jigsaw(mut self, enabled: bool) -> Self {
+        self.jigsaw = enabled;
+        self
+    }
+
+    /// Generates Killer puzzles: cages laid over the solved grid, with no
+    /// givens left. Cages grow larger with difficulty, and are split until
+    /// the solution is unique. Cages from the [`config`](Self::config) are
+    /// kept, among the new ones.
+    pub fn killer(mut self, enabled: bool) -> Self {
+        self.killer = enabled;
+        self
+    }
+
+    /// Generates Odd/Even puzzles: half the cells, picked at random, are
+    /// marked with the parity of their solution digit; see [`Sudoku::with_marks`].
+    pub fn marks(mut self, enabled: bool) -> Self {
+        self.marks = enabled;
+        self
+    }
+
+    /// Keeps the givens of generated puzzles in a pattern with `symmetry`,
+    /// by clearing cells a whole orbit at a time. A mirror or diagonal axis
+    /// is picked at random per puzzle. Killer puzzles have no givens, so
+    /// this leaves them alone.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver, SymmetryKind};
+    ///
+    /// let puzzle = Solver::new()
+    ///     .symmetry(SymmetryKind::Rotational90)
+    ///     .generate(9, Difficulty::Medium)
+    ///     .unwrap();
+    /// assert!(matches!(
+    ///     puzzle.symmetry(),
+    ///     SymmetryKind::Rotational90 | SymmetryKind::Dihedral
+    /// ));
+    /// ```
+    pub fn symmetry(mut self, symmetry: SymmetryKind) -> Self {
+        self.symmetry = Some(symmetry);
+        self
+    }
+
+    /// Leaves a number of givens in `range` when generating, instead of the
+    /// share set by the difficulty. Best effort: removal is retried on the
+    /// same solution grid a few times, and generation fails if no attempt
+    /// lands in range. Ignored for Killer puzzles, which have no givens.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let puzzle = Solver::new().clues(28..=32).generate(9, Difficulty::Medium).unwrap();
+    /// assert!((28..=32).contains(&(81 - puzzle.empty_count())));
+    /// ```
+    pub fn clues(mut self, range: RangeInclusive<usize>) -> Self {
+        self.clues = Some((*range.start(), *range.end()));
+        self
+    }
+
+    /// Generates puzzles that need the strategy called `name`: the easier
+    /// strategies stall, and with it they finish. The puzzle takes the
+    /// strategy's difficulty in place of the one asked for. Each attempt
+    /// takes a fresh solution grid, and generation fails after a few.
+    ///
+    /// Fails on a name the solver doesn't have, as listed by
+    /// [`strategy_names`](Self::strategy_names). Generation fails at once
+    /// if the strategy is not active by then, such as after
+    /// [`remove_strategy`](Self::remove_strategy).
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let mut solver = Solver::new().requires_technique("X-Wing").unwrap();
+    /// let puzzle = solver.generate(9, Difficulty::Easy).unwrap();
+    /// assert_eq!(solver.grade(&puzzle), Difficulty::Hard);
+    /// assert!(solver.solve_logical(puzzle, Difficulty::Medium).is_err());
+    /// assert!(Solver::new().requires_technique("X-Wnig").is_err());
+    /// ```
+    pub fn requires_technique(mut self, name: impl Into<String>) -> Result<Self, String> {
+        let name = name.into();
+        if !self.strategies.iter().any(|s| s.name() == name) {
+            return Err(format!("Unknown strategy '{name}'"));
+        }
+        self.technique = Some(name);
+        Ok(self)
+    }
+
+    /// Only leaves givens where `mask` is true when generating, so the
+    /// clues can form a shape. `mask` holds one entry per cell in
+    /// row-major order. Every cell outside it is cleared first, and each
+    /// attempt that leaves more than one solution, or ends at another
+    /// grade, starts over on a fresh solution grid; generation fails after
+    /// a few. Ignored for Killer puzzles, which have no givens.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// // Everything but a frame one cell in from the edge.
+    /// let mask: Vec<bool> = (0..81)
+    ///     .map(|i| !matches!((i / 9, i % 9), (1, 1..=7) | (7, 1..=7) | (1..=7, 1) | (1..=7, 7)))
+    ///     .collect();
+    /// let puzzle = Solver::new().clue_mask(mask.clone()).generate(9, Difficulty::Easy).unwrap();
+    /// assert!((0..81).all(|i| mask[i] || puzzle.get(i / 9, i % 9).unwrap().is_empty()));
+    /// ```
+    pub fn clue_mask(mut self, mask: Vec<bool>) -> Self {
+        self.clue_mask = Some(mask);
+        self
+    }
+
+    /// Generates minimal puzzles: givens are cleared, in random order, until
+    /// none can go without allowing a second solution. One pass settles
+    /// it, as a given that is needed stays needed once others go. The
+    /// difficulty no longer sets how many givens go, only which grade is
+    /// kept; with [`symmetry`](Self::symmetry) the minimum is over whole
+    /// orbits. Past 16 wide a given only goes if
+    /// singles still solve the puzzle, so it is minimal for those.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let mut solver = Solver::new().minimal(true).seed(3);
+    /// let puzzle = solver.generate(9, Difficulty::Hard).unwrap();
+    /// assert!(solver.has_unique_solution(&puzzle));
+    /// for i in 0..81 {
+    ///     let mut fewer = puzzle.clone();
+    ///     if !fewer.get(i / 9, i % 9).unwrap().is_empty() {
+    ///         fewer.set(i / 9, i % 9, 0).unwrap();
+    ///         assert!(!solver.has_unique_solution(&fewer));
+    ///     }
+    /// }
+    /// ```
+    pub fn minimal(mut self, enabled: bool) -> Self {
+        self.minimal = enabled;
+        self
+    }
+
+    /// An empty grid with this solver's rules.
+    fn blank(&self, box_rows: usize, box_cols: usize) -> Result<Sudoku, String> {
+        Sudoku::with_boxes(box_rows, box_cols).with_config(&self.config)
+    }
+
+    /// Strategies allowed to run, in priority order.
+    fn active(&self) -> impl Iterator<Item = &dyn Strategy> {
+        self.strategies
+            .iter()
+            .map(|s| s.as_ref())
+            .filter(|s| self.unique || !s.assumes_unique())
+    }
+
+    /// Tracks candidates in `mode` while solving, instead of the puzzle's own
+    /// mode. The input's mode is restored on the returned grid.
+    pub fn candidate_mode(mut self, mode: CandidateMode) -> Self {
+        self.candidates = Some(mode);
+        self
+    }
+
+    /// Sets how the solver searches, for solving past its strategies and
+    /// for [`count_solutions`](Self::count_solutions) and
+    /// [`has_unique_solution`](Self::has_unique_solution).
+    ///
+    /// ```
+    /// use sodo::{Backend, Solver, Sudoku};
+    ///
+    /// let solver = Solver::new().backend(Backend::DancingLinks);
+    /// assert_eq!(solver.count_solutions(&Sudoku::new(4), None), 288);
+    /// ```
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Seeds the solver's random choices: the value order of uniqueness
+    /// checks, the random [`CellOrder`] and [`ValueOrder`], sampled
+    /// solutions and generation. From the same seed, the same calls give
+    /// the same results and the same [`Stats`] counts on any machine;
+    /// timings still vary, and so does [`parallel`] search. Unseeded, the
+    /// choices differ from run to run.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let a = Solver::new().seed(7).generate(9, Difficulty::Medium).unwrap();
+    /// let b = Solver::new().seed(7).generate(9, Difficulty::Medium).unwrap();
+    /// assert_eq!(a.to_string_compact(), b.to_string_compact());
+    /// ```
+    ///
+    /// [`parallel`]: Self::parallel
+    pub fn seed(self, seed: u64) -> Self {
+        *self.rng.lock().unwrap_or_else(|e| e.into_inner()) = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// A generator for one call, drawn from the solver's own.
+    pub(crate) fn rng(&self) -> StdRng {
+        StdRng::from_rng(&mut *self.shared_rng())
+    }
+
+    fn shared_rng(&self) -> MutexGuard<'_, StdRng> {
+        self.rng.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Sets how backtracking picks the next cell to branch on. Solving and
+    /// generation follow it; counting and the other [`Backend`]s keep
+    /// their own order.
+    ///
+    /// ```
+    /// use sodo::{CellOrder, Solver, Sudoku, ValueOrder};
+    ///
+    /// let mut solver = Solver::new()
+    ///     .cell_order(CellOrder::MrvDegree)
+    ///     .value_order(ValueOrder::LeastConstraining);
+    /// assert!(solver.solve(Sudoku::new(9)).unwrap().is_solved());
+    /// ```
+    pub fn cell_order(mut self, order: CellOrder) -> Self {
+        self.cell_order = order;
+        self
+    }
+
+    /// Sets the order backtracking tries a cell's values in, like
+    /// [`cell_order`](Self::cell_order).
+    pub fn value_order(mut self, order: ValueOrder) -> Self {
+        self.value_order = order;
+        self
+    }
+
+    /// Searches `sudoku` for up to `max` solutions with the chosen backend,
+    /// as (found, first, nodes), or `None` to backtrack instead.
+    fn backend_search(
+        &self,
+        sudoku: &Sudoku,
+        max: usize,
+    ) -> Option<(usize, Option<Sudoku>, usize)> {
+        match self.backend {
+            Backend::Backtracking => None,
+            Backend::DancingLinks => sudoku
+                .is_exact_cover()
+                .then(|| crate::dlx::solve(sudoku, max)),
+            #[cfg(feature = "sat")]
+            Backend::Sat => Some(crate::sat::solve(sudoku, max)),
+        }
+    }
+
+    /// Splits the backtracking search across threads, for single hard puzzles
+    /// such as large grids or near-empty inputs. Also splits
+    /// [`count_solutions`](Self::count_solutions) when backtracking. Every
+    /// thread keeps to the bounds of
+    /// [`solve_with_options`](Self::solve_with_options):
+    ///
+    /// ```
+    /// use sodo::{SolveError, SolveOptions, Solver, Sudoku};
+    /// use std::time::Duration;
+    ///
+    /// let options = SolveOptions::new().deadline(Duration::from_millis(1));
+    /// let err = Solver::new()
+    ///     .parallel(true)
+    ///     .solve_with_options(Sudoku::new(25), &options)
+    ///     .unwrap_err();
+    /// assert_eq!(err, SolveError::Aborted);
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn parallel(mut self, enabled: bool) -> Self {
+        self.parallel = enabled;
+        self
+    }
+
     /// Solves the puzzle, returning the solution.
-    pub fn solve(&mut self, sudoku: Sudoku) -> Result<Sudoku, String> {
-        self.solve_with_stats(sudoku).map(|(s, _)| s)
+    ///
+    /// Search buffers are kept between calls, so solving puzzle after
+    /// puzzle with one solver allocates little beyond what the strategies
+    /// themselves need.
+    pub fn solve(&mut self, mut sudoku: Sudoku) -> Result<Sudoku, String> {
+        self.solve_in_place(&mut sudoku)?;
+        Ok(sudoku)
+    }
+
+    /// Solves `sudoku` where it is, without a copy. On error the grid holds
+    /// whatever the strategies had filled in.
+    ///
+    /// ```
+    /// use sodo::{Solver, Sudoku};
+    ///
+    /// let mut grid = Sudoku::new(9);
+    /// Solver::new().solve_in_place(&mut grid).unwrap();
+    /// assert!(grid.is_solved());
+    /// ```
+    pub fn solve_in_place(&mut self, sudoku: &mut Sudoku) -> Result<(), String> {
+        self.run_reusing(sudoku)
+    }
+
+    /// Solves a copy of `sudoku`, leaving it as it is.
+    pub fn solve_ref(&mut self, sudoku: &Sudoku) -> Result<Sudoku, String> {
+        self.solve(sudoku.clone())
+    }
+
+    /// [`run`](Self::run) into the kept stats, for callers that drop them.
+    fn run_reusing(&mut self, sudoku: &mut Sudoku) -> Result<(), String> {
+        let scratch = self.scratch.get_mut().unwrap_or_else(|e| e.into_inner());
+        let mut stats = std::mem::take(&mut scratch.stats);
+        stats.reset();
+        let result = self.run(sudoku, &mut stats);
+        self.scratch
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .stats = stats;
+        result
     }
 
     /// Solves the puzzle, returning solution and statistics.
     pub fn solve_with_stats(&mut self, mut sudoku: Sudoku) -> Result<(Sudoku, Stats), String> {
+        let mut stats = Stats::default();
+        self.run(&mut sudoku, &mut stats)?;
+        Ok((sudoku, stats))
+    }
+
+    /// [`solve_with_stats`](Self::solve_with_stats) within `options`,
+    /// failing with [`SolveError::Aborted`] once a bound is hit. The bounds
+    /// are checked before each strategy application and at each
+    /// backtracking node, in a parallel search too; a [`Backend`] other
+    /// than backtracking checks them only before it starts.
+    ///
+    /// ```
+    /// use sodo::{SolveOptions, Solver, Sudoku};
+    /// use std::time::Duration;
+    ///
+    /// let options = SolveOptions::new().deadline(Duration::from_secs(5));
+    /// let (solution, _) = Solver::new()
+    ///     .solve_with_options(Sudoku::new(9), &options)
+    ///     .unwrap();
+    /// assert!(solution.is_solved());
+    /// ```
+    pub fn solve_with_options(
+        &mut self,
+        sudoku: Sudoku,
+        options: &SolveOptions,
+    ) -> Result<(Sudoku, Stats), SolveError> {
+        let budget = Arc::new(Budget::start(options));
+        self.budget = Some(budget.clone());
+        let result = self.solve_with_stats(sudoku);
+        self.budget = None;
+        result.map_err(|e| {
+            if budget.spent() {
+                SolveError::Aborted
+            } else {
+                SolveError::Failed(e)
+            }
+        })
+    }
+
+    /// True once a bound of the call in progress is hit. `nodes` paces the
+    /// clock readings.
+    fn exhausted(&self, nodes: usize) -> bool {
+        self.budget.as_ref().is_some_and(|b| b.exhausted(nodes))
+    }
+
+    /// Passes the progress of the call in progress to its hook.
+    fn report(&self, update: impl FnOnce(&mut Progress)) {
+        if let Some(budget) = &self.budget {
+            budget.report(update);
+        }
+    }
+
+    fn iteration_limit(&self) -> usize {
+        self.budget
+            .as_ref()
+            .and_then(|b| b.max_iterations)
+            .unwrap_or(self.max_iters)
+    }
+
+    /// Solves many puzzles given as compact strings, returning compact solutions.
+    ///
+    /// A single grid buffer is reused across the whole batch, along with the
+    /// solver's search buffers, so the per-puzzle cost is parsing plus the
+    /// search itself. Results are in input order.
+    pub fn solve_many<I, S>(&mut self, puzzles: I, size: usize) -> Vec<Result<String, String>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let blank = match Sudoku::box_shape(size) {
+            Some((rows, cols)) => self.blank(rows, cols),
+            None => Ok(Sudoku::new(size)),
+        };
+        let mut sudoku = match blank {
+            Ok(sudoku) => sudoku,
+            Err(e) => return puzzles.into_iter().map(|_| Err(e.clone())).collect(),
+        };
+
+        puzzles
+            .into_iter()
+            .map(|p| {
+                sudoku.load(p.as_ref())?;
+                self.run_reusing(&mut sudoku)?;
+                let mut out = String::with_capacity(size * size);
+                sudoku.write_compact(&mut out);
+                Ok(out)
+            })
+            .collect()
+    }
+
+    /// Solves `sudoku` in place, accumulating into `stats`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "solve",
+            skip_all,
+            fields(size = sudoku.size(), empty = sudoku.empty_count())
+        )
+    )]
+    pub(crate) fn run(&self, sudoku: &mut Sudoku, stats: &mut Stats) -> Result<(), String> {
+        let clock = Stopwatch::start();
+        let result = match self.candidates {
+            Some(mode) => {
+                let original = sudoku.candidate_mode();
+                sudoku.set_candidate_mode(mode);
+                let result = self.search(sudoku, stats);
+                sudoku.set_candidate_mode(original);
+                result
+            }
+            None => self.search(sudoku, stats),
+        };
+        stats.elapsed += clock.elapsed();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            ok = result.is_ok(),
+            iterations = stats.iterations,
+            backtracks = stats.backtracks,
+            elapsed = ?stats.elapsed,
+            "solve finished"
+        );
+        result
+    }
+
+    fn search(&self, sudoku: &mut Sudoku, stats: &mut Stats) -> Result<(), String> {
         if !sudoku.is_valid() {
             return Err("Invalid initial state".into());
         }
 
-        let mut stats = Stats::default();
+        let clock = Stopwatch::start();
+        let solved = self.apply_strategies(sudoku, stats);
+        stats.logic_time += clock.elapsed();
+        if solved {
+            return Ok(());
+        }
+        if self.exhausted(0) {
+            return Err(ABORTED.into());
+        }
+        let clock = Stopwatch::start();
+        let result = self.search_stalled(sudoku, stats);
+        stats.search_time += clock.elapsed();
+        result
+    }
+
+    /// Searches a grid the strategies left unsolved.
+    fn search_stalled(&self, sudoku: &mut Sudoku, stats: &mut Stats) -> Result<(), String> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            empty = sudoku.empty_count(),
+            "strategies stalled, backtracking"
+        );
 
-        if self.apply_strategies(&mut sudoku, &mut stats) {
-            return Ok((sudoku, stats));
+        #[cfg(feature = "parallel")]
+        if self.backtrack && self.parallel {
+            let budget = self.budget.as_deref();
+            return match crate::parallel::solve(sudoku.clone(), stats, budget) {
+                Some(solution) => {
+                    *sudoku = solution;
+                    Ok(())
+                }
+                None if budget.is_some_and(Budget::spent) => Err(ABORTED.into()),
+                None => Err("No solution found".into()),
+            };
         }
 
-        if self.backtrack && self.backtrack_solve(&mut sudoku, &mut stats) {
-            return Ok((sudoku, stats));
+        if self.backtrack
+            && let Some((found, solution, nodes)) = self.backend_search(sudoku, 1)
+        {
+            stats.backtracks += nodes;
+            return match (found, solution) {
+                (1, Some(solution)) => {
+                    *sudoku = solution;
+                    Ok(())
+                }
+                _ => Err("No solution found".into()),
+            };
+        }
+
+        // Search only ever places candidates, so one check up front stands
+        // in for checking every node.
+        if self.backtrack && sudoku.is_valid() {
+            // Another call on this solver may hold the buffers, e.g. from
+            // a second thread; it then searches with its own.
+            let mut spare = Scratch::default();
+            let mut kept = self.scratch.try_lock().ok();
+            let scratch = kept.as_deref_mut().unwrap_or(&mut spare);
+            if self.backtrack_solve(sudoku, stats, scratch, 0) {
+                return Ok(());
+            }
         }
 
         if sudoku.is_solved() {
-            Ok((sudoku, stats))
+            Ok(())
+        } else if self.budget.as_ref().is_some_and(|b| b.spent()) {
+            Err(ABORTED.into())
         } else {
             Err("No solution found".into())
         }
     }
 
-    fn apply_strategies(&self, sudoku: &mut Sudoku, stats: &mut Stats) -> bool {
+    /// Solves by strategies alone, with none harder than `tier`, and never
+    /// guesses. Short of a solution it hands back how far it got and why it
+    /// stopped.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver, StuckReason, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// assert!(Solver::new().solve_logical(puzzle, Difficulty::Easy).is_ok());
+    ///
+    /// let stuck = Solver::new()
+    ///     .solve_logical(Sudoku::new(9), Difficulty::Expert)
+    ///     .unwrap_err();
+    /// assert_eq!(stuck.reason, StuckReason::NoProgress);
+    /// ```
+    pub fn solve_logical(&self, sudoku: Sudoku, tier: Difficulty) -> Result<Sudoku, Box<Stuck>> {
+        self.logical_where(sudoku, &|s| s.difficulty() <= tier)
+    }
+
+    /// [`solve_logical`](Self::solve_logical) with only the strategies in
+    /// `names`, whatever their difficulty, such as to check a puzzle needs
+    /// nothing beyond singles. Names the solver doesn't have match nothing;
+    /// [`strategy_names`](Self::strategy_names) lists those it does.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver, StuckReason};
+    ///
+    /// let mut solver = Solver::new();
+    /// let singles = ["Naked Singles", "Hidden Singles"];
+    /// let easy = solver.generate_seeded(9, Difficulty::Easy, 1).unwrap();
+    /// assert!(solver.solve_logical_with(easy, &singles).is_ok());
+    ///
+    /// let hard = solver.generate_seeded(9, Difficulty::Hard, 1).unwrap();
+    /// let stuck = solver.solve_logical_with(hard, &singles).unwrap_err();
+    /// assert_eq!(stuck.reason, StuckReason::NoProgress);
+    /// assert!(stuck.grid.empty_count() > 0);
+    /// ```
+    pub fn solve_logical_with(&self, sudoku: Sudoku, names: &[&str]) -> Result<Sudoku, Box<Stuck>> {
+        self.logical_where(sudoku, &|s| names.contains(&s.name()))
+    }
+
+    /// Solves by the strategies `allowed` picks, for
+    /// [`solve_logical`](Self::solve_logical).
+    fn logical_where(
+        &self,
+        mut sudoku: Sudoku,
+        allowed: &dyn Fn(&dyn Strategy) -> bool,
+    ) -> Result<Sudoku, Box<Stuck>> {
+        let mut stats = Stats::default();
+        let reason = if !sudoku.is_valid() {
+            StuckReason::Invalid
+        } else if self.apply_where(&mut sudoku, &mut stats, allowed) {
+            return Ok(sudoku);
+        } else if !sudoku.is_valid() || sudoku.first_blocked().is_some() {
+            StuckReason::Contradiction
+        } else if stats.iterations >= self.iteration_limit() {
+            StuckReason::IterationLimit
+        } else {
+            StuckReason::NoProgress
+        };
+        Err(Box::new(Stuck {
+            grid: sudoku,
+            reason,
+            stats,
+        }))
+    }
+
+    pub(crate) fn apply_strategies(&self, sudoku: &mut Sudoku, stats: &mut Stats) -> bool {
+        self.apply_up_to(sudoku, stats, Difficulty::Expert)
+    }
+
+    /// [`apply_strategies`](Self::apply_strategies) with only the
+    /// strategies up to `tier`.
+    fn apply_up_to(&self, sudoku: &mut Sudoku, stats: &mut Stats, tier: Difficulty) -> bool {
+        self.apply_where(sudoku, stats, &|s| s.difficulty() <= tier)
+    }
+
+    /// [`apply_strategies`](Self::apply_strategies) with only the
+    /// strategies `allowed` picks.
+    fn apply_where(
+        &self,
+        sudoku: &mut Sudoku,
+        stats: &mut Stats,
+        allowed: &dyn Fn(&dyn Strategy) -> bool,
+    ) -> bool {
         let mut progress = true;
 
-        while progress && !sudoku.is_complete() && stats.iterations < self.max_iters {
+        let limit = self.iteration_limit();
+        while progress && !sudoku.is_complete() && stats.iterations < limit {
             progress = false;
             stats.iterations += 1;
+            self.report(|p| (p.iterations, p.backtracks) = (stats.iterations, stats.backtracks));
 
-            for strategy in &self.strategies {
+            if self.propagate(sudoku, stats) {
+                if !sudoku.is_valid() {
+                    return false;
+                }
+                progress = true;
+                continue;
+            }
+            for strategy in self.active().filter(|&s| allowed(s)) {
+                if self.exhausted(0) {
+                    return false;
+                }
                 let before = sudoku.empty_count();
+                let ruled_out = sudoku.eliminated();
+                stats.strategy_calls += 1;
 
-                if strategy.apply(sudoku) {
+                let clock = Stopwatch::start();
+                let applied = strategy.apply(sudoku);
+                tally(&mut stats.strategy_time, strategy.name(), clock.elapsed());
+                if applied {
                     stats.cells_filled += before - sudoku.empty_count();
-                    *stats
-                        .strategies_used
-                        .entry(strategy.name().into())
-                        .or_default() += 1;
+                    stats.eliminations += sudoku.eliminated().saturating_sub(ruled_out);
+                    stats.propagations += 1;
+                    tally(&mut stats.strategies_used, strategy.name(), 1);
                     progress = true;
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(
+                        strategy = strategy.name(),
+                        filled = before - sudoku.empty_count(),
+                        eliminated = sudoku.eliminated().saturating_sub(ruled_out),
+                        "strategy applied"
+                    );
 
                     if !sudoku.is_valid() {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(strategy = strategy.name(), "contradiction");
                         return false;
                     }
+                    // Back to the simplest strategy, so harder ones only
+                    // run (and count) when nothing easier applies.
+                    break;
                 }
             }
         }
@@ -121,39 +1107,217 @@ impl Solver {
         sudoku.is_solved()
     }
 
-    fn backtrack_solve(&self, sudoku: &mut Sudoku, stats: &mut Stats) -> bool {
+    /// Runs the first [`Constraint::propagate`] hook of the grid's own
+    /// rules that makes progress, counted like a strategy.
+    ///
+    /// [`Constraint::propagate`]: crate::Constraint::propagate
+    fn propagate(&self, sudoku: &mut Sudoku, stats: &mut Stats) -> bool {
+        for constraint in sudoku.constraints().to_vec() {
+            let before = sudoku.empty_count();
+            let ruled_out = sudoku.eliminated();
+            if constraint.propagate(sudoku) {
+                stats.cells_filled += before.saturating_sub(sudoku.empty_count());
+                stats.eliminations += sudoku.eliminated().saturating_sub(ruled_out);
+                stats.propagations += 1;
+                tally(&mut stats.strategies_used, constraint.name(), 1);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Depth-first search below `depth` placements.
+    fn backtrack_solve(
+        &self,
+        sudoku: &mut Sudoku,
+        stats: &mut Stats,
+        scratch: &mut Scratch,
+        depth: usize,
+    ) -> bool {
+        if self.exhausted(stats.backtracks) {
+            return false;
+        }
+        if stats.backtracks.is_multiple_of(REPORT_EVERY) {
+            self.report(|p| (p.iterations, p.backtracks) = (stats.iterations, stats.backtracks));
+        }
         if sudoku.is_complete() {
             return sudoku.is_valid();
         }
 
-        let Some((r, c)) = self.find_mrv_cell(sudoku) else {
-            return sudoku.is_valid();
+        if scratch.branches.len() <= depth {
+            scratch.branches.push(Vec::new());
+        }
+        let mut branch = std::mem::take(&mut scratch.branches[depth]);
+        let solved = if !self.choose_branch(sudoku, &mut branch, &mut scratch.houses) {
+            sudoku.is_valid()
+        } else {
+            if branch.len() > 1 {
+                stats.guesses += 1;
+            }
+            branch.iter().any(|&(r, c, val)| {
+                if sudoku.set(r, c, val).is_err() {
+                    return false;
+                }
+                stats.backtracks += 1;
+                stats.max_depth = stats.max_depth.max(depth + 1);
+                if self.backtrack_solve(sudoku, stats, scratch, depth + 1) {
+                    return true;
+                }
+                let _ = sudoku.set(r, c, 0);
+                false
+            })
         };
+        scratch.branches[depth] = branch;
+        solved
+    }
 
-        for val in sudoku.candidates(r, c) {
-            if sudoku.set(r, c, val).is_ok() {
-                stats.backtracks += 1;
+    /// Placements to try next, one of which must hold: the values of the
+    /// most constrained cell, or the last place for a digit in a house.
+    /// `None` when the grid is full; an empty branch is a dead end.
+    pub(crate) fn find_branch(sudoku: &Sudoku) -> Option<Branch> {
+        let mut branch = Vec::new();
+        Self::fill_branch(sudoku, &mut branch, &mut Vec::new()).then_some(branch)
+    }
+
+    /// [`find_branch`](Self::find_branch) into `branch`, with `houses` to
+    /// work in. False when the grid is full.
+    fn fill_branch(sudoku: &Sudoku, branch: &mut Branch, houses: &mut Vec<Mask>) -> bool {
+        branch.clear();
+        let Some((r, c)) = Self::find_mrv_cell(sudoku) else {
+            return false;
+        };
+        let mask = sudoku.candidate_mask(r, c);
+        if mask.count_ones() <= 1 || !Self::hidden_single(sudoku, houses, branch) {
+            branch.extend(digits(mask).map(|v| (r, c, v)));
+        }
+        true
+    }
 
-                if sudoku.is_valid() && self.backtrack_solve(sudoku, stats) {
+    /// [`find_branch`](Self::find_branch) under the solver's
+    /// [`CellOrder`] and [`ValueOrder`].
+    fn choose_branch(&self, sudoku: &Sudoku, branch: &mut Branch, houses: &mut Vec<Mask>) -> bool {
+        let n = sudoku.size();
+        let empty = |i: &usize| sudoku.cell(i / n, i % n).is_empty();
+        let cell = match self.cell_order {
+            CellOrder::Mrv => None,
+            CellOrder::RowMajor => {
+                let Some(i) = (0..n * n).find(empty) else {
+                    return false;
+                };
+                Some(i)
+            }
+            CellOrder::MrvDegree | CellOrder::Random => {
+                let count = |i: usize| sudoku.candidate_mask(i / n, i % n).count_ones();
+                let Some(fewest) = (0..n * n).filter(empty).map(count).min() else {
+                    return false;
+                };
+                branch.clear();
+                if fewest == 0 || fewest > 1 && Self::hidden_single(sudoku, houses, branch) {
                     return true;
                 }
+                let ties = (0..n * n).filter(|i| empty(i) && count(*i) == fewest);
+                match self.cell_order {
+                    CellOrder::Random => ties.choose(&mut *self.shared_rng()),
+                    _ => ties.max_by_key(|&i| {
+                        let open = sudoku
+                            .peers(i / n, i % n)
+                            .filter(|&(r, c)| sudoku.cell(r, c).is_empty());
+                        (open.count(), usize::MAX - i)
+                    }),
+                }
+            }
+        };
+        match cell {
+            None => {
+                if !Self::fill_branch(sudoku, branch, houses) {
+                    return false;
+                }
+            }
+            Some(i) => {
+                let mask = sudoku.candidate_mask(i / n, i % n);
+                branch.clear();
+                branch.extend(digits(mask).map(|v| (i / n, i % n, v)));
+            }
+        }
+        match self.value_order {
+            ValueOrder::Ascending => {}
+            ValueOrder::Random => branch.shuffle(&mut *self.shared_rng()),
+            ValueOrder::LeastConstraining => {
+                branch.sort_by_cached_key(|&(r, c, v)| {
+                    sudoku
+                        .peers(r, c)
+                        .filter(|&(pr, pc)| sudoku.candidate_mask(pr, pc) & bit(v) != 0)
+                        .count()
+                });
+            }
+        }
+        true
+    }
 
-                let _ = sudoku.set(r, c, 0);
+    /// Writes a digit left with one place in some house as a one-placement
+    /// `branch`, or leaves it empty if a house has a digit with no place at
+    /// all. False if neither, with `branch` untouched. `houses` is room to
+    /// work in.
+    fn hidden_single(sudoku: &Sudoku, houses: &mut Vec<Mask>, branch: &mut Branch) -> bool {
+        let n = sudoku.size();
+        // Rows, then columns, then boxes: digits placed, and candidates seen
+        // at least once and at least twice.
+        houses.clear();
+        houses.resize(9 * n, 0);
+        let (placed, rest) = houses.split_at_mut(3 * n);
+        let (once, twice) = rest.split_at_mut(3 * n);
+        for r in 0..n {
+            for c in 0..n {
+                let houses = [r, n + c, 2 * n + sudoku.box_index(r, c)];
+                match sudoku.cell(r, c).value() {
+                    Some(v) => houses.iter().for_each(|&h| placed[h] |= bit(v)),
+                    None => {
+                        let m = sudoku.candidate_mask(r, c);
+                        for h in houses {
+                            twice[h] |= once[h] & m;
+                            once[h] |= m;
+                        }
+                    }
+                }
             }
         }
 
+        let full = sudoku.full_mask();
+        for h in 0..3 * n {
+            let missing = full & !placed[h];
+            if missing & !once[h] != 0 {
+                branch.clear();
+                return true;
+            }
+            let Some(v) = digits(missing & !twice[h]).next() else {
+                continue;
+            };
+            let cell = |k: usize| match h / n {
+                0 => (h, k),
+                1 => (k, h - n),
+                _ => sudoku.box_cell(h - 2 * n, k),
+            };
+            if let Some((r, c)) = (0..n)
+                .map(cell)
+                .find(|&(r, c)| sudoku.candidate_mask(r, c) & bit(v) != 0)
+            {
+                branch.clear();
+                branch.push((r, c, v));
+                return true;
+            }
+        }
         false
     }
 
     /// Finds empty cell with minimum remaining values (MRV heuristic).
-    fn find_mrv_cell(&self, sudoku: &Sudoku) -> Option<(usize, usize)> {
+    pub(crate) fn find_mrv_cell(sudoku: &Sudoku) -> Option<(usize, usize)> {
         let mut best = None;
-        let mut min_cands = usize::MAX;
+        let mut min_cands = u32::MAX;
 
-        for r in 0..sudoku.size {
-            for c in 0..sudoku.size {
-                if sudoku.grid[r][c].is_empty() {
-                    let n = sudoku.candidates(r, c).len();
+        for r in 0..sudoku.size() {
+            for c in 0..sudoku.size() {
+                if sudoku.cell(r, c).is_empty() {
+                    let n = sudoku.candidate_mask(r, c).count_ones();
                     if n < min_cands {
                         min_cands = n;
                         best = Some((r, c));
@@ -171,12 +1335,12 @@ impl Solver {
     /// Returns a hint: (row, col, value) for the next logical move.
     pub fn hint(&self, sudoku: &Sudoku) -> Option<(usize, usize, u8)> {
         // Try naked singles first
-        for r in 0..sudoku.size {
-            for c in 0..sudoku.size {
-                if sudoku.grid[r][c].is_empty() {
-                    let cands = sudoku.candidates(r, c);
-                    if cands.len() == 1 {
-                        return Some((r, c, *cands.iter().next().unwrap()));
+        for r in 0..sudoku.size() {
+            for c in 0..sudoku.size() {
+                if sudoku.cell(r, c).is_empty() {
+                    let mask = sudoku.candidate_mask(r, c);
+                    if mask.count_ones() == 1 {
+                        return digits(mask).next().map(|v| (r, c, v));
                     }
                 }
             }
@@ -187,10 +1351,10 @@ impl Solver {
             if strategy.name() == "Hidden Singles" {
                 let mut temp = sudoku.clone();
                 if strategy.apply(&mut temp) {
-                    for r in 0..sudoku.size {
-                        for c in 0..sudoku.size {
-                            if sudoku.grid[r][c] != temp.grid[r][c]
-                                && let Some(v) = temp.grid[r][c].value()
+                    for r in 0..sudoku.size() {
+                        for c in 0..sudoku.size() {
+                            if sudoku.cell(r, c) != temp.cell(r, c)
+                                && let Some(v) = temp.cell(r, c).value()
                             {
                                 return Some((r, c, v));
                             }
@@ -203,56 +1367,823 @@ impl Solver {
         None
     }
 
-    /// Counts solutions up to a maximum.
-    pub fn count_solutions(&self, mut sudoku: Sudoku, max: usize) -> usize {
+    /// Finds the next deduction without applying it: the first strategy that
+    /// makes progress, what it would change, and the cells it rests on.
+    pub fn next_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        self.take_step(sudoku).map(|(step, _)| step)
+    }
+
+    /// The deductions that solve `sudoku` as far as the strategies reach,
+    /// in order, each applied before the next is found. Ends when the grid
+    /// is solved or no strategy makes progress.
+    ///
+    /// ```
+    /// use sodo::{Solver, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// let solver = Solver::new();
+    /// let placed: usize = solver.solve_steps(&puzzle).map(|s| s.placements.len()).sum();
+    /// assert_eq!(placed, puzzle.empty_count());
+    /// ```
+    pub fn solve_steps(&self, sudoku: &Sudoku) -> impl Iterator<Item = Step> + '_ {
+        let mut grid = sudoku.clone();
+        std::iter::from_fn(move || {
+            let (step, after) = self.take_step(&grid)?;
+            grid = after;
+            Some(step)
+        })
+    }
+
+    /// The next deduction and the grid after it.
+    fn take_step(&self, sudoku: &Sudoku) -> Option<(Step, Sudoku)> {
+        self.active().find_map(|strategy| {
+            let mut after = sudoku.clone();
+            let finding = strategy.find(&mut after)?;
+            let mut step = Step {
+                strategy: strategy.name().into(),
+                highlights: finding.highlights,
+                chain: finding.chain,
+                houses: finding.houses,
+                ..Step::default()
+            };
+            for r in 0..sudoku.size() {
+                for c in 0..sudoku.size() {
+                    if !sudoku.cell(r, c).is_empty() {
+                        continue;
+                    }
+                    if let Some(v) = after.cell(r, c).value() {
+                        step.placements.push((r, c, v));
+                    }
+                    let gone = after.ruled_out(r, c) & !sudoku.ruled_out(r, c);
+                    let gone = gone & sudoku.candidate_mask(r, c);
+                    step.eliminations.extend(digits(gone).map(|v| (r, c, v)));
+                }
+            }
+            Some((step, after))
+        })
+    }
+
+    /// Counts the solutions of `sudoku`, stopping once `limit` are found;
+    /// `None` counts them all. A search only, with no strategies, so
+    /// `Some(2)` settles uniqueness quickly.
+    ///
+    /// ```
+    /// use sodo::{Solver, Sudoku};
+    ///
+    /// let empty = Sudoku::new(4);
+    /// assert_eq!(Solver::new().count_solutions(&empty, None), 288);
+    /// assert_eq!(Solver::new().count_solutions(&empty, Some(2)), 2);
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(?limit), ret)
+    )]
+    pub fn count_solutions(&self, sudoku: &Sudoku, limit: Option<usize>) -> usize {
         let mut count = 0;
-        Self::count_recursive(&mut sudoku, &mut count, max);
+        if sudoku.is_valid() {
+            let max = limit.unwrap_or(usize::MAX);
+            if let Some((found, ..)) = self.backend_search(sudoku, max) {
+                return found;
+            }
+            #[cfg(feature = "parallel")]
+            if self.parallel {
+                return crate::parallel::count(sudoku, max, self.budget.as_deref());
+            }
+            Self::count_recursive(&mut sudoku.clone(), &mut count, max);
+        }
         count
     }
 
+    /// Only ever places candidates, so a valid grid stays valid throughout.
     fn count_recursive(sudoku: &mut Sudoku, count: &mut usize, max: usize) {
         if *count >= max {
             return;
         }
 
-        if sudoku.is_complete() {
-            if sudoku.is_valid() {
-                *count += 1;
-            }
+        let Some(branch) = Self::find_branch(sudoku) else {
+            *count += 1;
             return;
+        };
+
+        for (r, c, val) in branch {
+            sudoku.put(r, c, Cell::Filled(val));
+            Self::count_recursive(sudoku, count, max);
+            sudoku.put(r, c, Cell::Empty);
+            if *count >= max {
+                break;
+            }
+        }
+    }
+
+    /// True if `sudoku` has exactly one solution, stopping at a second,
+    /// like [`count_solutions`](Self::count_solutions) with `Some(2)`.
+    /// Values are tried in random order, so a run of puzzles that differ
+    /// in a few cells, as in a generator, doesn't keep hitting the same
+    /// worst case. Other [`Backend`]s search in their own order.
+    ///
+    /// ```
+    /// use sodo::{Solver, Sudoku};
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// assert!(Solver::new().has_unique_solution(&puzzle));
+    /// assert!(!Solver::new().has_unique_solution(&Sudoku::new(9)));
+    /// ```
+    pub fn has_unique_solution(&self, sudoku: &Sudoku) -> bool {
+        let mut count = 0;
+        if sudoku.is_valid() {
+            if let Some((found, ..)) = self.backend_search(sudoku, 2) {
+                return found == 1;
+            }
+            Self::find_two(&mut sudoku.clone(), &mut count, &mut self.rng());
         }
+        count == 1
+    }
 
-        let Some((r, c)) = sudoku.first_empty() else {
+    /// [`count_recursive`](Self::count_recursive) up to two, trying each
+    /// branch in random order.
+    fn find_two(sudoku: &mut Sudoku, count: &mut usize, rng: &mut impl Rng) {
+        let Some(mut branch) = Self::find_branch(sudoku) else {
+            *count += 1;
             return;
         };
+        branch.shuffle(rng);
+        for (r, c, val) in branch {
+            sudoku.put(r, c, Cell::Filled(val));
+            Self::find_two(sudoku, count, rng);
+            sudoku.put(r, c, Cell::Empty);
+            if *count >= 2 {
+                break;
+            }
+        }
+    }
+
+    /// Every solution of `sudoku`, found lazily as the iterator is pulled,
+    /// so taking a few costs only the search up to them. Like
+    /// [`count_solutions`](Self::count_solutions) it searches without
+    /// strategies.
+    ///
+    /// ```
+    /// use sodo::{Solver, Sudoku};
+    ///
+    /// let empty = Sudoku::new(4);
+    /// let mut all = Solver::new().solve_all(&empty);
+    /// let first = all.next().unwrap();
+    /// assert!(first.is_solved());
+    /// assert_eq!(all.count(), 287);
+    /// ```
+    pub fn solve_all(&self, sudoku: &Sudoku) -> impl Iterator<Item = Sudoku> + use<> {
+        let mut grid = sudoku.clone();
+        // Branches being searched, each with how many of its placements
+        // have been tried; the last one tried is still on the grid.
+        let mut stack: Vec<(Branch, usize)> = Vec::new();
+        let mut start = sudoku.is_valid();
+        std::iter::from_fn(move || {
+            if std::mem::take(&mut start) {
+                match Self::find_branch(&grid) {
+                    Some(branch) => stack.push((branch, 0)),
+                    None => return Some(grid.clone()),
+                }
+            }
+            loop {
+                let (branch, tried) = stack.last_mut()?;
+                if *tried > 0 {
+                    let (r, c, _) = branch[*tried - 1];
+                    grid.put(r, c, Cell::Empty);
+                }
+                let Some(&(r, c, val)) = branch.get(*tried) else {
+                    stack.pop();
+                    continue;
+                };
+                *tried += 1;
+                grid.put(r, c, Cell::Filled(val));
+                match Self::find_branch(&grid) {
+                    Some(next) => stack.push((next, 0)),
+                    None => return Some(grid.clone()),
+                }
+            }
+        })
+    }
+
+    /// A solution of `sudoku` found by search with values tried in random
+    /// order, or `None` if it has none. Quick, but solutions reached
+    /// through fewer branches come up more often; see
+    /// [`sample_solution`](Self::sample_solution) for an even spread.
+    pub fn random_solution(&self, sudoku: &Sudoku) -> Option<Sudoku> {
+        if !sudoku.is_valid() {
+            return None;
+        }
+        let mut grid = sudoku.clone();
+        Self::random_fill(&mut grid, &mut self.rng()).then_some(grid)
+    }
+
+    fn random_fill(sudoku: &mut Sudoku, rng: &mut impl Rng) -> bool {
+        let Some(mut branch) = Self::find_branch(sudoku) else {
+            return true;
+        };
+        branch.shuffle(rng);
+        for (r, c, val) in branch {
+            sudoku.put(r, c, Cell::Filled(val));
+            if Self::random_fill(sudoku, rng) {
+                return true;
+            }
+            sudoku.put(r, c, Cell::Empty);
+        }
+        false
+    }
 
-        for val in sudoku.candidates(r, c) {
-            if sudoku.set(r, c, val).is_ok() && sudoku.is_valid() {
-                Self::count_recursive(sudoku, count, max);
+    /// A solution of `sudoku` drawn near uniformly from all of them, or
+    /// `None` if it has none. Each branch of the search is taken with
+    /// odds in proportion to how many solutions it holds, counted up to
+    /// `effort`; the draw is exactly uniform while no branch reaches it.
+    /// Costs about `effort` solutions counted per branch, so raise it for
+    /// accuracy and lower it for speed.
+    ///
+    /// ```
+    /// use sodo::{Solver, Sudoku};
+    ///
+    /// let mut grid = Sudoku::new(4);
+    /// grid.set(0, 0, 1).unwrap();
+    /// let sample = Solver::new().sample_solution(&grid, 1000).unwrap();
+    /// assert!(sample.is_solved());
+    /// assert_eq!(sample.get(0, 0).unwrap().value(), Some(1));
+    /// ```
+    pub fn sample_solution(&self, sudoku: &Sudoku, effort: usize) -> Option<Sudoku> {
+        if !sudoku.is_valid() {
+            return None;
+        }
+        let mut grid = sudoku.clone();
+        let mut rng = self.rng();
+        let mut counts = Vec::new();
+        while let Some(branch) = Self::find_branch(&grid) {
+            counts.clear();
+            for &(r, c, val) in &branch {
+                grid.put(r, c, Cell::Filled(val));
+                counts.push(self.count_solutions(&grid, Some(effort.max(1))));
+                grid.put(r, c, Cell::Empty);
+            }
+            let total: usize = counts.iter().sum();
+            if total == 0 {
+                return None;
             }
-            let _ = sudoku.set(r, c, 0);
+            let mut pick = rng.random_range(0..total);
+            let k = counts
+                .iter()
+                .position(|&n| {
+                    let hit = pick < n;
+                    pick = pick.saturating_sub(n);
+                    hit
+                })
+                .expect("pick below total");
+            let (r, c, val) = branch[k];
+            grid.put(r, c, Cell::Filled(val));
         }
+        Some(grid)
     }
 
-    /// Generates a puzzle of the given size and difficulty.
+    /// Generates a puzzle of the given size and difficulty. It has exactly
+    /// one solution: a given is only cleared once a search for a second
+    /// solution without it comes up empty.
+    ///
+    /// Givens go for as long as they can: below Expert, while the
+    /// strategies up to `difficulty` still finish the puzzle. Removal is
+    /// retried on the same solution grid until the puzzle
+    /// [grades](Self::grade) exactly so. After a few attempts the closest
+    /// grade is kept, as small grids never need more than singles. Past 16
+    /// wide puzzles are kept to singles and not graded.
+    ///
+    /// The solution grid is built under every rule of the solver's
+    /// [`config`](Self::config), so the puzzle works under any variant.
     pub fn generate(&mut self, size: usize, difficulty: Difficulty) -> Result<Sudoku, String> {
-        let mut sudoku = Sudoku::new(size);
-        let mut rng = rng();
+        self.generate_with(size, difficulty, &mut || {}, &mut |_, _| true)
+    }
 
-        // Fill diagonal boxes first (they don't affect each other)
-        let bs = sudoku.box_size;
-        let mut diag: Vec<usize> = (0..bs).collect();
-        diag.shuffle(&mut rng);
+    /// [`generate`](Self::generate), with the uniqueness in the type.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let unique = Solver::new().generate_unique(9, Difficulty::Medium).unwrap();
+    /// let solution = Solver::new().solve(unique.puzzle().clone()).unwrap();
+    /// assert_eq!(solution.to_string_compact(), unique.solution().to_string_compact());
+    /// ```
+    pub fn generate_unique(
+        &mut self,
+        size: usize,
+        difficulty: Difficulty,
+    ) -> Result<UniquePuzzle, String> {
+        self.unique_puzzle(size, difficulty)
+    }
 
-        for &i in &diag {
-            self.fill_box(&mut sudoku, i * bs)?;
+    /// [`generate_unique`](Self::generate_unique) `count` times, in order.
+    /// With the `parallel` feature the puzzles are generated across
+    /// rayon's pool, so a [`seed`](Self::seed) no longer fixes the batch.
+    /// Fails with the first error.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let batch = Solver::new().generate_batch(4, 9, Difficulty::Easy).unwrap();
+    /// assert_eq!(batch.len(), 4);
+    /// assert!(batch.iter().all(|p| p.grade() == Difficulty::Easy));
+    /// ```
+    pub fn generate_batch(
+        &self,
+        count: usize,
+        size: usize,
+        difficulty: Difficulty,
+    ) -> Result<Vec<UniquePuzzle>, String> {
+        let one = |_| self.unique_puzzle(size, difficulty);
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            (0..count).into_par_iter().map(one).collect()
         }
+        #[cfg(not(feature = "parallel"))]
+        (0..count).map(one).collect()
+    }
 
-        // Solve to get complete grid
-        let solution = self.solve(sudoku.clone())?;
+    /// An endless stream of [`generate_unique`](Self::generate_unique)
+    /// puzzles, sharing the solver's search buffers from one to the next.
+    /// Ends at the first error, such as an invalid size.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let solver = Solver::new();
+    /// let corner_free: Vec<_> = solver
+    ///     .generator(9, Difficulty::Easy)
+    ///     .filter(|p| p.puzzle().get(0, 0).unwrap().is_empty())
+    ///     .take(3)
+    ///     .collect();
+    /// assert_eq!(corner_free.len(), 3);
+    /// assert_eq!(solver.generator(7, Difficulty::Easy).count(), 0);
+    /// ```
+    pub fn generator(
+        &self,
+        size: usize,
+        difficulty: Difficulty,
+    ) -> impl Iterator<Item = UniquePuzzle> + '_ {
+        std::iter::from_fn(move || self.unique_puzzle(size, difficulty).ok())
+    }
 
-        // Calculate cells to remove based on difficulty
+    fn unique_puzzle(&self, size: usize, difficulty: Difficulty) -> Result<UniquePuzzle, String> {
+        let puzzle = self.generate_with(size, difficulty, &mut || {}, &mut |_, _| true)?;
+        let mut solution = puzzle.clone();
+        self.run(&mut solution, &mut Stats::default())?;
+        Ok(UniquePuzzle {
+            grade: self.grade(&puzzle),
+            puzzle,
+            solution,
+        })
+    }
+
+    /// [`generate`](Self::generate) with `solution` as the solution grid,
+    /// for puzzles whose answer holds some pattern of the caller's. The
+    /// puzzle keeps `solution`'s rules; the solver's own variant settings
+    /// are not applied, other than making a Killer puzzle.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver, Sudoku};
+    ///
+    /// let solution = Sudoku::from_string(
+    ///     "123456789456789123789123456214365897365897214897214365531642978642978531978531642",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// let mut solver = Solver::new();
+    /// let puzzle = solver.generate_from_solution(&solution, Difficulty::Easy).unwrap();
+    /// assert_eq!(solver.solve(puzzle).unwrap().to_string_compact(), solution.to_string_compact());
+    /// ```
+    pub fn generate_from_solution(
+        &mut self,
+        solution: &Sudoku,
+        difficulty: Difficulty,
+    ) -> Result<Sudoku, String> {
+        if !solution.is_solved() {
+            return Err("Solution grid is not complete and valid".into());
+        }
+        self.dig(solution.clone(), difficulty, &mut |_, _| true)
+    }
+
+    /// Clears every clue of a unique puzzle that isn't needed to keep it
+    /// unique, returning the minimal puzzle and the cells cleared, in
+    /// row-major order. With a `symmetry`, clues go an orbit at a time, so
+    /// a pattern that had it keeps it; a mirror or diagonal axis is one
+    /// the clues already have, if any. Errors unless `sudoku` has exactly
+    /// one solution.
+    ///
+    /// ```
+    /// use sodo::{Solver, Sudoku, SymmetryKind};
+    ///
+    /// let solver = Solver::new();
+    /// let solution = Sudoku::random_filled(9, 5).unwrap();
+    /// let (puzzle, removed) = solver.minimize(&solution, None).unwrap();
+    /// assert_eq!(removed.len(), puzzle.empty_count());
+    /// assert!(solver.analyze(&puzzle).minimal);
+    ///
+    /// let (puzzle, _) = solver
+    ///     .minimize(&solution, Some(SymmetryKind::Rotational180))
+    ///     .unwrap();
+    /// assert!(solver.has_unique_solution(&puzzle));
+    /// assert!(matches!(
+    ///     puzzle.symmetry(),
+    ///     SymmetryKind::Rotational180 | SymmetryKind::Rotational90 | SymmetryKind::Dihedral
+    /// ));
+    /// ```
+    pub fn minimize(
+        &self,
+        sudoku: &Sudoku,
+        symmetry: Option<SymmetryKind>,
+    ) -> Result<(Sudoku, Vec<(usize, usize)>), String> {
+        if !sudoku.is_valid() || !self.has_unique_solution(sudoku) {
+            return Err("Puzzle does not have a unique solution".into());
+        }
+        let size = sudoku.size();
+        let clue = |s: &Sudoku, (r, c): (usize, usize)| s.cell(r, c) != Cell::Empty;
+        let maps = symmetry.map(|kind| {
+            let first = symmetry_maps(kind, true);
+            // Keep an axis the clues already have.
+            let kept = (0..size * size)
+                .map(|i| (i / size, i % size))
+                .filter(|&cell| clue(sudoku, cell))
+                .all(|(r, c)| {
+                    orbit(&first, size, r, c)
+                        .into_iter()
+                        .all(|o| clue(sudoku, o))
+                });
+            if kept {
+                first
+            } else {
+                symmetry_maps(kind, false)
+            }
+        });
+
+        let mut puzzle = sudoku.clone();
+        let mut removed = Vec::new();
+        // A clue needed now stays needed once others go, so one pass leaves
+        // every remaining clue (or orbit) needed.
+        for (r, c) in (0..size * size).map(|i| (i / size, i % size)) {
+            if !clue(&puzzle, (r, c)) {
+                continue;
+            }
+            let cells: Vec<_> = match &maps {
+                Some(maps) => orbit(maps, size, r, c)
+                    .into_iter()
+                    .filter(|&cell| clue(&puzzle, cell))
+                    .collect(),
+                None => vec![(r, c)],
+            };
+            let mut fewer = puzzle.clone();
+            for &(r, c) in &cells {
+                fewer.put(r, c, Cell::Empty);
+            }
+            if self.has_unique_solution(&fewer) {
+                puzzle = fewer;
+                removed.extend(cells);
+            }
+        }
+        removed.sort_unstable();
+        Ok((puzzle, removed))
+    }
+
+    /// [`generate`](Self::generate) from `seed`: the same seed, size,
+    /// difficulty and solver settings give the same puzzle on every run and
+    /// platform. Reseeds the solver, as [`seed`](Self::seed) does.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, Solver};
+    ///
+    /// let mut solver = Solver::new();
+    /// let a = solver.generate_seeded(9, Difficulty::Hard, 2024).unwrap();
+    /// let b = solver.generate_seeded(9, Difficulty::Hard, 2024).unwrap();
+    /// assert_eq!(a.to_string_compact(), b.to_string_compact());
+    /// ```
+    pub fn generate_seeded(
+        &mut self,
+        size: usize,
+        difficulty: Difficulty,
+        seed: u64,
+    ) -> Result<Sudoku, String> {
+        *self.rng.get_mut().unwrap_or_else(|e| e.into_inner()) = StdRng::seed_from_u64(seed);
+        self.generate(size, difficulty)
+    }
+
+    /// [`generate`](Self::generate) within `options`, failing with
+    /// [`SolveError::Aborted`] once a bound is hit, unless they settle for
+    /// the best puzzle so far. The iteration bound applies to each solve
+    /// along the way. A puzzle settled for may be of another difficulty,
+    /// so [`grade`](Self::grade) it to know which.
+    ///
+    /// ```
+    /// use sodo::{Difficulty, OnBudget, SolveError, SolveOptions, Solver};
+    /// use std::time::Duration;
+    ///
+    /// let options = SolveOptions::new().deadline(Duration::ZERO);
+    /// let err = Solver::new()
+    ///     .generate_with_options(9, Difficulty::Easy, &options)
+    ///     .unwrap_err();
+    /// assert_eq!(err, SolveError::Aborted);
+    ///
+    /// // A 4x4 grid never needs more than singles.
+    /// let options = SolveOptions::new().max_attempts(2);
+    /// let mut solver = Solver::new();
+    /// assert!(solver.generate_with_options(4, Difficulty::Hard, &options).is_err());
+    /// let options = options.on_budget(OnBudget::BestSoFar);
+    /// let puzzle = solver.generate_with_options(4, Difficulty::Hard, &options).unwrap();
+    /// assert_eq!(solver.grade(&puzzle), Difficulty::Easy);
+    /// ```
+    pub fn generate_with_options(
+        &mut self,
+        size: usize,
+        difficulty: Difficulty,
+        options: &SolveOptions,
+    ) -> Result<Sudoku, SolveError> {
+        let budget = Arc::new(Budget::start(options));
+        self.budget = Some(budget.clone());
+        let result = self.generate_with(size, difficulty, &mut || {}, &mut |removed, target| {
+            budget.report(|p| (p.removed, p.target) = (removed, target));
+            !budget.exhausted(0)
+        });
+        self.budget = None;
+        match result {
+            Ok(puzzle) if !budget.spent() || options.on_budget == OnBudget::BestSoFar => Ok(puzzle),
+            Err(e) if !budget.spent() => Err(SolveError::Failed(e)),
+            _ => Err(SolveError::Aborted),
+        }
+    }
+
+    /// Stops the searches of later calls once `token` is cancelled, as
+    /// [`SolveOptions::cancel_token`] does for a single call.
+    #[cfg(feature = "async")]
+    pub(crate) fn cancel_with(&mut self, token: crate::CancelToken) {
+        let options = SolveOptions::new().cancel_token(token);
+        self.budget = Some(Arc::new(Budget::start(&options)));
+    }
+
+    /// True once a bound of the call is hit, if it settles for the best
+    /// puzzle so far.
+    fn settling(&self) -> bool {
+        self.budget
+            .as_ref()
+            .is_some_and(|b| b.on_budget == OnBudget::BestSoFar && b.spent())
+    }
+
+    /// Attempts at the asked difficulty, `default` unless the call's
+    /// options say otherwise.
+    fn attempts(&self, default: usize) -> usize {
+        self.budget
+            .as_ref()
+            .and_then(|b| b.max_attempts)
+            .unwrap_or(default)
+    }
+
+    /// Out of attempts at the asked difficulty, which is a bound if the
+    /// call's options set them.
+    fn out_of_attempts(&self) {
+        if let Some(budget) = &self.budget
+            && budget.max_attempts.is_some()
+        {
+            budget.spend();
+        }
+    }
+
+    /// [`Self::generate`], calling `on_filled` once each solution grid is
+    /// ready, and `on_step(removed, target)` as each removal pass starts
+    /// and after each removal. Returning false stops early.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "generate",
+            skip(self, on_filled, on_step),
+            err
+        )
+    )]
+    pub(crate) fn generate_with(
+        &self,
+        size: usize,
+        difficulty: Difficulty,
+        on_filled: &mut dyn FnMut(),
+        on_step: &mut dyn FnMut(usize, usize) -> bool,
+    ) -> Result<Sudoku, String> {
+        let mut rng = self.rng();
+        if let Some(name) = &self.technique
+            && self.technique().is_none()
+        {
+            return Err(format!("Unknown strategy '{name}'"));
+        }
+        if self.clue_mask.is_none() && self.technique.is_none() {
+            let solution = self.solution_grid(size, &mut rng)?;
+            on_filled();
+            return self.dig(solution, difficulty, on_step);
+        }
+        if let Some(mask) = &self.clue_mask
+            && mask.len() != size * size
+        {
+            return Err(format!(
+                "Clue mask has {} cells, not {}",
+                mask.len(),
+                size * size
+            ));
+        }
+        // Whether the cells outside the mask can go, or the technique comes
+        // up, depends on the grid, so each attempt takes a fresh one.
+        let difficulty = self.target(difficulty);
+        let attempts = self.attempts(MASK_ATTEMPTS);
+        let mut closest = None;
+        for _ in 0..attempts {
+            let solution = self.solution_grid(size, &mut rng)?;
+            on_filled();
+            match self.dig(solution, difficulty, on_step) {
+                Ok(puzzle)
+                    if self.killer
+                        || (size > JUMBO || self.grade(&puzzle) == difficulty)
+                            && self.needs_technique(&puzzle) =>
+                {
+                    return Ok(puzzle);
+                }
+                Ok(puzzle) => closest = Some(puzzle),
+                Err(e) if e == NO_FIT => {}
+                Err(e) => return closest.filter(|_| self.settling()).ok_or(e),
+            }
+            if self.settling() {
+                break;
+            }
+        }
+        self.out_of_attempts();
+        closest
+            .filter(|_| self.settling())
+            .ok_or_else(|| match &self.technique {
+                Some(name) => format!("No puzzle needing {name} in {attempts} attempts"),
+                None => {
+                    format!("No {difficulty:?} puzzle fits the clue mask in {attempts} attempts")
+                }
+            })
+    }
+
+    /// A random solution grid `size` wide, under this solver's rules.
+    fn solution_grid(&self, size: usize, rng: &mut StdRng) -> Result<Sudoku, String> {
+        let (rows, cols) = Sudoku::box_shape(size).ok_or(format!("Invalid grid size {size}"))?;
+
+        let plain = self.config.is_empty();
+        let solution = if size > JUMBO && plain {
+            shuffled_pattern(rows, cols, rng)?
+        } else {
+            // Fill diagonal boxes first (they share no row or column). With
+            // 2-wide or rectangular boxes, or variant houses across them,
+            // that can leave no completion, so seed only the first box there.
+            let seeded = if rows == cols && rows >= 3 && plain {
+                rows
+            } else {
+                1
+            };
+            let mut diag: Vec<usize> = (0..seeded).collect();
+            // Even one box can leave no completion under cages or lines, so
+            // those get a few fresh seeds.
+            let mut attempts = if plain { 1 } else { SEED_ATTEMPTS };
+            loop {
+                let mut sudoku = self.blank(rows, cols)?;
+                diag.shuffle(rng);
+                for &i in &diag {
+                    self.fill_box(&mut sudoku, i * rows + i)?;
+                }
+
+                // Solve to get complete grid
+                match self.run(&mut sudoku, &mut Stats::default()) {
+                    Ok(()) => break sudoku,
+                    Err(e) if attempts == 1 => return Err(e),
+                    Err(_) => attempts -= 1,
+                }
+            }
+        };
+        // Reshape the boxes around the finished grid, so the layout is
+        // solvable by construction.
+        let solution = if self.jigsaw {
+            let layout = random_layout(&solution, rng);
+            solution.with_regions(&layout)?
+        } else {
+            solution
+        };
+        let solution = if self.marks {
+            let n = size;
+            let mut cells: Vec<usize> = (0..n * n).collect();
+            cells.shuffle(rng);
+            let marks = cells[..n * n / 2]
+                .iter()
+                .map(|&i| {
+                    let odd = solution.cell(i / n, i % n).value().unwrap_or(0) % 2 == 1;
+                    ((i / n, i % n), if odd { Mark::Odd } else { Mark::Even })
+                })
+                .collect();
+            solution.with_marks(marks)?
+        } else {
+            solution
+        };
+        #[cfg(feature = "tracing")]
+        tracing::debug!("filled solution grid");
+        Ok(solution)
+    }
+
+    /// Turns the solved `solution` into a puzzle of `difficulty`, the
+    /// second half of [`generate_with`](Self::generate_with).
+    fn dig(
+        &self,
+        solution: Sudoku,
+        difficulty: Difficulty,
+        on_step: &mut dyn FnMut(usize, usize) -> bool,
+    ) -> Result<Sudoku, String> {
+        if self.killer {
+            return self.lay_cages(solution, difficulty, on_step);
+        }
+
+        let mut rng = self.rng();
+        let size = solution.size();
         let total = size * size;
+        let (fewest, most) = self.clues.unwrap_or((0, total));
+        if fewest > most || most > total {
+            return Err(format!("Invalid clue range {fewest}..={most}"));
+        }
+        // Below Expert a given only goes while the strategies of the tier
+        // still finish the puzzle, so removal can carry on until the
+        // puzzle needs them. Past JUMBO the search for a second solution
+        // can take minutes, so singles must finish it instead, and every
+        // puzzle grades Easy.
+        let graded = size <= JUMBO;
+        let difficulty = self.target(difficulty);
+        let tier = if !graded {
+            Some(Difficulty::Easy)
+        } else if self.technique.is_some() {
+            Some(difficulty)
+        } else if self.minimal || difficulty == Difficulty::Expert {
+            None
+        } else {
+            Some(difficulty)
+        };
+        let gap = |grade: Difficulty| (grade as i32 - difficulty as i32).abs();
+        let mut closest: Option<(Sudoku, Difficulty)> = None;
+        let attempts = self.attempts(GENERATE_ATTEMPTS);
+        for _ in 0..attempts {
+            if self.settling() {
+                break;
+            }
+            let to_remove = if self.clues.is_some() {
+                total - rng.random_range(fewest..=most)
+            } else if self.minimal || graded {
+                total
+            } else {
+                Self::removal_target(total, difficulty, &mut rng)
+            };
+            #[cfg(feature = "tracing")]
+            tracing::debug!(to_remove, "removing cells");
+            let puzzle = match self.remove_cells(solution.clone(), to_remove, tier, on_step) {
+                Ok(puzzle) => puzzle,
+                Err(_) if self.settling() && closest.is_some() => break,
+                Err(e) => return Err(e),
+            };
+            if self.settling() {
+                // Cut short, so only worth keeping if nothing came before;
+                // its grade would be cut short too.
+                closest.get_or_insert((puzzle, difficulty));
+                break;
+            }
+            if !(fewest..=most).contains(&(total - puzzle.empty_count())) {
+                continue;
+            }
+            let grade = if graded {
+                self.grade(&puzzle)
+            } else {
+                difficulty
+            };
+            if grade == difficulty && self.needs_technique(&puzzle) {
+                return Ok(puzzle);
+            }
+            if closest
+                .as_ref()
+                .is_none_or(|&(_, best)| gap(grade) < gap(best))
+            {
+                closest = Some((puzzle, grade));
+            }
+        }
+        self.out_of_attempts();
+        closest.map(|(puzzle, _)| puzzle).ok_or_else(|| {
+            format!("No puzzle with {fewest} to {most} givens in {attempts} attempts")
+        })
+    }
+
+    /// How many of `total` cells to empty for `difficulty`.
+    pub(crate) fn removal_target(
+        total: usize,
+        difficulty: Difficulty,
+        rng: &mut impl Rng,
+    ) -> usize {
         let base_remove = match difficulty {
             Difficulty::Easy => total * 40 / 100,
             Difficulty::Medium => total * 50 / 100,
@@ -262,68 +2193,489 @@ impl Solver {
 
         // Add ±5% variation
         let var = (base_remove as f32 * 0.05) as usize;
-        let to_remove = if var > 0 {
+        if var > 0 {
             let min = base_remove.saturating_sub(var);
-            let max = (base_remove + var).min(total - 17);
+            let max = (base_remove + var).min(total.saturating_sub(17)).max(min);
             rng.random_range(min..=max)
         } else {
             base_remove
-        };
-
-        self.remove_cells(solution, to_remove)
+        }
     }
 
-    fn fill_box(&self, sudoku: &mut Sudoku, start: usize) -> Result<(), String> {
-        let bs = sudoku.box_size;
-        let mut vals: Vec<u8> = (1..=sudoku.size as u8).collect();
-        vals.shuffle(&mut rng());
+    /// Fills an empty box with shuffled digits, each in the first cell that
+    /// can take it. Cells no digit left fits stay empty.
+    pub(crate) fn fill_box(&self, sudoku: &mut Sudoku, b: usize) -> Result<(), String> {
+        let mut vals: Vec<u8> = (1..=sudoku.size() as u8).collect();
+        vals.shuffle(&mut self.rng());
 
-        let mut i = 0;
-        for r in start..start + bs {
-            for c in start..start + bs {
-                sudoku.set(r, c, vals[i])?;
-                i += 1;
+        for (r, c) in sudoku.house_cells(House::Box(b)) {
+            if let Some(k) = vals.iter().position(|&v| sudoku.can_place(r, c, v)) {
+                sudoku.set(r, c, vals.remove(k))?;
             }
         }
 
         Ok(())
     }
 
-    fn remove_cells(&self, mut sudoku: Sudoku, to_remove: usize) -> Result<Sudoku, String> {
-        let size = sudoku.size;
-        let mut rng = rng();
+    /// Covers `solution` with random cages, up to a size set by
+    /// `difficulty`, then empties it. While another solution remains, a
+    /// cell where it differs gets a cage of its own. Cages the solution
+    /// already has are kept, and their cells left out.
+    fn lay_cages(
+        &self,
+        solution: Sudoku,
+        difficulty: Difficulty,
+        on_step: &mut dyn FnMut(usize, usize) -> bool,
+    ) -> Result<Sudoku, String> {
+        let n = solution.size();
+        let mut rng = self.rng();
+        let largest = match difficulty {
+            Difficulty::Easy => 2,
+            Difficulty::Medium => 3,
+            Difficulty::Hard => 4,
+            Difficulty::Expert => 5,
+        };
+        let value = |i: usize| solution.cell(i / n, i % n).value().unwrap_or(0);
+        if !on_step(0, n * n) {
+            return Err("Cancelled".into());
+        }
+
+        let fixed = solution.cages().to_vec();
+        let mut of: Vec<usize> = vec![usize::MAX; n * n];
+        for &(r, c) in fixed.iter().flat_map(|cage| &cage.cells) {
+            of[r * n + c] = FIXED;
+        }
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut order: Vec<usize> = (0..n * n).collect();
+        order.shuffle(&mut rng);
+        for start in order {
+            if of[start] != usize::MAX {
+                continue;
+            }
+            let target = rng.random_range(2..=largest);
+            let (id, mut used) = (groups.len(), bit(value(start)));
+            let mut group = vec![start];
+            of[start] = id;
+            while group.len() < target {
+                let open: Vec<usize> = group
+                    .iter()
+                    .flat_map(|&i| neighbours(i, n))
+                    .filter(|&j| of[j] == usize::MAX && used & bit(value(j)) == 0)
+                    .collect();
+                let Some(&j) = open.choose(&mut rng) else {
+                    break;
+                };
+                of[j] = id;
+                used |= bit(value(j));
+                group.push(j);
+            }
+            groups.push(group);
+        }
+
+        loop {
+            let cages = fixed
+                .iter()
+                .cloned()
+                .chain(groups.iter().map(|g| Cage {
+                    sum: g.iter().map(|&i| u32::from(value(i))).sum(),
+                    cells: g.iter().map(|&i| (i / n, i % n)).collect(),
+                }))
+                .collect();
+            let mut puzzle = solution.clone().with_cages(cages)?;
+            puzzle.clear();
+
+            let mut probe = puzzle.clone();
+            if !Self::find_other(&mut probe, &solution) {
+                if !on_step(n * n, n * n) {
+                    return Err("Cancelled".into());
+                }
+                return Ok(puzzle);
+            }
+            let Some(i) = (0..n * n)
+                .find(|&i| of[i] != FIXED && probe.cell(i / n, i % n).value() != Some(value(i)))
+            else {
+                return Err("Cage layout has no unique solution".into());
+            };
+
+            // Cut `i` out into a cage of its own, and the rest of its cage
+            // into connected pieces.
+            let id = of[i];
+            let rest: Vec<usize> = groups[id].iter().copied().filter(|&j| j != i).collect();
+            groups[id] = vec![i];
+            let mut left = rest.clone();
+            while let Some(&seed) = left.first() {
+                let mut piece = vec![seed];
+                let mut k = 0;
+                while k < piece.len() {
+                    for j in neighbours(piece[k], n) {
+                        if left.contains(&j) && !piece.contains(&j) {
+                            piece.push(j);
+                        }
+                    }
+                    k += 1;
+                }
+                left.retain(|j| !piece.contains(j));
+                for &j in &piece {
+                    of[j] = groups.len();
+                }
+                groups.push(piece);
+            }
+        }
+    }
+
+    /// Searches for a solution that differs from `known`, leaving it in
+    /// `sudoku`. Only ever places candidates, like
+    /// [`count_recursive`](Self::count_recursive).
+    fn find_other(sudoku: &mut Sudoku, known: &Sudoku) -> bool {
+        let Some(branch) = Self::find_branch(sudoku) else {
+            return sudoku
+                .cells()
+                .zip(known.cells())
+                .any(|(a, b)| a.value() != b.value());
+        };
+        for (r, c, val) in branch {
+            sudoku.put(r, c, Cell::Filled(val));
+            if Self::find_other(sudoku, known) {
+                return true;
+            }
+            sudoku.put(r, c, Cell::Empty);
+        }
+        false
+    }
+
+    /// Clears up to `to_remove` cells of the solved `sudoku`, each only if
+    /// the puzzle still [`keeps`](Self::keeps) to `tier`.
+    fn remove_cells(
+        &self,
+        mut sudoku: Sudoku,
+        to_remove: usize,
+        tier: Option<Difficulty>,
+        on_step: &mut dyn FnMut(usize, usize) -> bool,
+    ) -> Result<Sudoku, String> {
+        let size = sudoku.size();
+        let mut rng = self.rng();
         let mut removed = 0;
 
         let mut positions: Vec<_> = (0..size)
             .flat_map(|r| (0..size).map(move |c| (r, c)))
             .collect();
         positions.shuffle(&mut rng);
+        let maps = self.symmetry.map(|kind| {
+            let axes = matches!(kind, SymmetryKind::Diagonal | SymmetryKind::Mirror);
+            symmetry_maps(kind, axes && rng.random_bool(0.5))
+        });
+        let solution = sudoku.clone();
+
+        if !on_step(0, to_remove) {
+            return Err("Cancelled".into());
+        }
+
+        if let Some(mask) = &self.clue_mask {
+            for i in (0..size * size).filter(|&i| !mask[i]) {
+                sudoku.put(i / size, i % size, Cell::Empty);
+                removed += 1;
+            }
+            if !self.keeps(&sudoku, tier) {
+                return Err(NO_FIT.into());
+            }
+        }
 
         for (r, c) in positions {
             if removed >= to_remove {
                 break;
             }
+            if sudoku.cell(r, c) == Cell::Empty {
+                continue;
+            }
 
-            if sudoku.grid[r][c] != Cell::Empty {
-                sudoku.grid[r][c] = Cell::Empty;
-                removed += 1;
-
-                // Remove symmetric cell with 70% probability
-                if removed < to_remove && rng.random_bool(0.7) {
+            let cells = match &maps {
+                // Cells outside a clue mask are empty already.
+                Some(maps) => orbit(maps, size, r, c)
+                    .into_iter()
+                    .filter(|&(r, c)| sudoku.cell(r, c) != Cell::Empty)
+                    .collect(),
+                None => {
+                    let mut cells = vec![(r, c)];
+                    // Pair with the symmetric cell 70% of the time
                     let (sr, sc) = (size - 1 - r, size - 1 - c);
-                    if (sr != r || sc != c) && sudoku.grid[sr][sc] != Cell::Empty {
-                        sudoku.grid[sr][sc] = Cell::Empty;
-                        removed += 1;
+                    if !self.minimal
+                        && removed + 1 < to_remove
+                        && rng.random_bool(0.7)
+                        && (sr != r || sc != c)
+                        && sudoku.cell(sr, sc) != Cell::Empty
+                    {
+                        cells.push((sr, sc));
                     }
+                    cells
+                }
+            };
+            if removed + cells.len() > to_remove {
+                continue;
+            }
+            // Clear the cells only if no second solution turns up without
+            // them, else put them back for good.
+            for &(r, c) in &cells {
+                sudoku.put(r, c, Cell::Empty);
+            }
+            if self.keeps(&sudoku, tier) {
+                removed += cells.len();
+            } else {
+                for &(r, c) in &cells {
+                    sudoku.put(r, c, solution.cell(r, c));
                 }
             }
+
+            if !on_step(removed, to_remove) {
+                // Every grid along the way has one solution, so it will do
+                // if the call settles for what it has.
+                return if self.settling() {
+                    Ok(sudoku)
+                } else {
+                    Err("Cancelled".into())
+                };
+            }
         }
 
         Ok(sudoku)
     }
 
+    /// The [`requires_technique`](Self::requires_technique) strategy, if
+    /// set and among the active ones.
+    fn technique(&self) -> Option<&dyn Strategy> {
+        let name = self.technique.as_deref()?;
+        self.active().find(|s| s.name() == name)
+    }
+
+    /// The difficulty to generate for `difficulty`: the technique's, if
+    /// one is required.
+    fn target(&self, difficulty: Difficulty) -> Difficulty {
+        self.technique().map_or(difficulty, |t| t.difficulty())
+    }
+
+    /// False if a technique is required and the strategies easier than it
+    /// finish `puzzle` on their own.
+    fn needs_technique(&self, puzzle: &Sudoku) -> bool {
+        let Some(tier) = self.technique().map(|t| t.difficulty()) else {
+            return true;
+        };
+        let easier = |s: &dyn Strategy| s.difficulty() < tier;
+        !self.apply_where(&mut puzzle.clone(), &mut Stats::default(), &easier)
+    }
+
+    /// True if the strategies up to `tier` finish `puzzle`, or with a
+    /// technique required, the ones easier than it along with it.
+    fn solves(&self, puzzle: &Sudoku, tier: Difficulty) -> bool {
+        let mut grid = puzzle.clone();
+        let mut stats = Stats::default();
+        match self.technique() {
+            Some(t) => {
+                let (name, tier) = (t.name(), t.difficulty());
+                let allowed = |s: &dyn Strategy| s.difficulty() < tier || s.name() == name;
+                self.apply_where(&mut grid, &mut stats, &allowed)
+            }
+            None => grid.is_valid() && self.apply_up_to(&mut grid, &mut stats, tier),
+        }
+    }
+
+    /// True if `puzzle` has one solution and, given a `tier`, the
+    /// strategies up to it finish the puzzle. A logical finish settles
+    /// uniqueness as well, unless strategies may assume it.
+    fn keeps(&self, puzzle: &Sudoku, tier: Option<Difficulty>) -> bool {
+        match tier {
+            Some(tier) => {
+                self.solves(puzzle, tier) && (!self.unique || self.has_unique_solution(puzzle))
+            }
+            None => self.has_unique_solution(puzzle),
+        }
+    }
+
     /// Applies one strategy step. Returns true if progress was made.
     pub fn step(&self, sudoku: &mut Sudoku) -> bool {
-        self.strategies.iter().any(|s| s.apply(sudoku))
+        self.active().any(|s| s.apply(sudoku))
+    }
+}
+
+impl Sudoku {
+    /// A complete random solution grid, the one
+    /// [`Solver::generate`](Solver::generate) would dig holes into with the
+    /// same `seed`. Errors if `size` has no box shape.
+    ///
+    /// ```
+    /// use sodo::Sudoku;
+    ///
+    /// let grid = Sudoku::random_filled(9, 7).unwrap();
+    /// assert!(grid.is_solved());
+    /// let again = Sudoku::random_filled(9, 7).unwrap();
+    /// assert_eq!(grid.to_string_compact(), again.to_string_compact());
+    /// assert!(Sudoku::random_filled(7, 7).is_err());
+    /// ```
+    pub fn random_filled(size: usize, seed: u64) -> Result<Sudoku, String> {
+        let solver = Solver::new().seed(seed);
+        let mut rng = solver.rng();
+        solver.solution_grid(size, &mut rng)
+    }
+}
+
+/// A map of the square onto itself, given its last index and a cell.
+type CellMap = fn(usize, usize, usize) -> (usize, usize);
+
+/// The maps a pattern with `kind` symmetry is invariant under, the
+/// identity included. A single mirror or diagonal is across the rows or the
+/// main diagonal if `first_axis`, else down the columns or the anti-diagonal.
+fn symmetry_maps(kind: SymmetryKind, first_axis: bool) -> Vec<CellMap> {
+    let id: CellMap = |_, r, c| (r, c);
+    let half: CellMap = |m, r, c| (m - r, m - c);
+    let quarter: CellMap = |m, r, c| (c, m - r);
+    let three: CellMap = |m, r, c| (m - c, r);
+    let main: CellMap = |_, r, c| (c, r);
+    let anti: CellMap = |m, r, c| (m - c, m - r);
+    let across: CellMap = |m, r, c| (m - r, c);
+    let down: CellMap = |m, r, c| (r, m - c);
+    match kind {
+        SymmetryKind::Dihedral => vec![id, half, quarter, three, main, anti, across, down],
+        SymmetryKind::Rotational90 => vec![id, half, quarter, three],
+        SymmetryKind::Rotational180 => vec![id, half],
+        SymmetryKind::Diagonal => vec![id, if first_axis { main } else { anti }],
+        SymmetryKind::Mirror => vec![id, if first_axis { across } else { down }],
+        SymmetryKind::None => vec![id],
+    }
+}
+
+/// The distinct cells `maps` take (row, col) to, itself first.
+fn orbit(maps: &[CellMap], n: usize, row: usize, col: usize) -> Vec<(usize, usize)> {
+    let mut cells = Vec::with_capacity(maps.len());
+    for map in maps {
+        let cell = map(n - 1, row, col);
+        if !cells.contains(&cell) {
+            cells.push(cell);
+        }
+    }
+    cells
+}
+
+/// Removal passes tried for a puzzle of the asked difficulty, within
+/// [`Solver::clues`].
+const GENERATE_ATTEMPTS: usize = 20;
+
+/// Owner in [`Solver::lay_cages`] of cells in the solution's own cages.
+const FIXED: usize = usize::MAX - 1;
+
+/// Solution grids tried for a puzzle within [`Solver::clue_mask`].
+const MASK_ATTEMPTS: usize = 50;
+
+/// Why a solution grid can't make a puzzle within [`Solver::clue_mask`].
+const NO_FIT: &str = "No unique puzzle fits the clue mask";
+
+/// Seeds tried for a solution grid under rules beyond the houses.
+const SEED_ATTEMPTS: usize = 20;
+
+/// Largest size whose solution grid is found by search. Beyond it search
+/// times vary wildly, so [`shuffled_pattern`] fills the grid instead.
+const JUMBO: usize = 16;
+
+/// A random complete grid built without search: the shifted base pattern
+/// with digits relabelled and bands, stacks, and the lines within them
+/// shuffled, none of which breaks a house.
+fn shuffled_pattern(rows: usize, cols: usize, rng: &mut impl Rng) -> Result<Sudoku, String> {
+    let n = rows * cols;
+    let mut values: Vec<u8> = (1..=n as u8).collect();
+    values.shuffle(rng);
+
+    // `groups` blocks of `width` lines each, both in random order.
+    let mut lines = |groups: usize, width: usize| -> Vec<usize> {
+        let mut order: Vec<usize> = (0..groups).collect();
+        order.shuffle(rng);
+        order
+            .into_iter()
+            .flat_map(|g| {
+                let mut block: Vec<usize> = (g * width..(g + 1) * width).collect();
+                block.shuffle(rng);
+                block
+            })
+            .collect()
+    };
+    let row_order = lines(cols, rows);
+    let col_order = lines(rows, cols);
+
+    let mut sudoku = Sudoku::with_boxes(rows, cols);
+    for (r, &pr) in row_order.iter().enumerate() {
+        for (c, &pc) in col_order.iter().enumerate() {
+            sudoku.set(r, c, values[(cols * (pr % rows) + pr / rows + pc) % n])?;
+        }
+    }
+    Ok(sudoku)
+}
+
+/// A random jigsaw layout that `solution` still satisfies: its boxes
+/// reshaped by trading equal digits between neighbouring regions, keeping
+/// each trade only if both regions stay connected.
+fn random_layout(solution: &Sudoku, rng: &mut impl Rng) -> String {
+    let n = solution.size();
+    let value = |i: usize| solution.cell(i / n, i % n).value();
+    let mut of: Vec<usize> = (0..n * n)
+        .map(|i| solution.box_index(i / n, i % n))
+        .collect();
+
+    for _ in 0..n * n * 32 {
+        // `a` leaves region A for a neighbouring B; the cell of B holding
+        // the same digit moves the other way.
+        let a = rng.random_range(0..n * n);
+        let Some(&x) = neighbours(a, n).choose(rng) else {
+            continue;
+        };
+        let (ra, rb) = (of[a], of[x]);
+        if ra == rb {
+            continue;
+        }
+        let Some(b) = (0..n * n).find(|&b| of[b] == rb && value(b) == value(a)) else {
+            continue;
+        };
+        of[a] = rb;
+        of[b] = ra;
+        if !connected(&of, n, ra) || !connected(&of, n, rb) {
+            of[a] = ra;
+            of[b] = rb;
+        }
+    }
+    of.iter().map(|&g| value_char(g as u8 + 1)).collect()
+}
+
+/// Orthogonal neighbours of cell `i` in an `n`x`n` grid.
+fn neighbours(i: usize, n: usize) -> Vec<usize> {
+    let (r, c) = (i / n, i % n);
+    let mut out = Vec::with_capacity(4);
+    if r > 0 {
+        out.push(i - n);
+    }
+    if r + 1 < n {
+        out.push(i + n);
+    }
+    if c > 0 {
+        out.push(i - 1);
+    }
+    if c + 1 < n {
+        out.push(i + 1);
+    }
+    out
+}
+
+/// True if the cells of `region` form one orthogonally connected piece.
+fn connected(of: &[usize], n: usize, region: usize) -> bool {
+    let Some(start) = of.iter().position(|&g| g == region) else {
+        return true;
+    };
+    let mut seen = vec![false; of.len()];
+    let mut stack = vec![start];
+    seen[start] = true;
+    let mut reached = 0;
+    while let Some(i) = stack.pop() {
+        reached += 1;
+        for j in neighbours(i, n) {
+            if of[j] == region && !seen[j] {
+                seen[j] = true;
+                stack.push(j);
+            }
+        }
     }
+    reached == n
 }
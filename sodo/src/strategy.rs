@@ -1,17 +1,160 @@
 use crate::Sudoku;
+use crate::chain::{Aic, XChain, XyChain};
+use crate::clue::ClueKind;
+use crate::line::LineKind;
+use crate::sodo::{House, Mask, bit, digits, span};
+use crate::solver::Difficulty;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A cell as (row, col).
+pub(crate) type Pos = (usize, usize);
 
 /// A solving strategy that can make progress on a puzzle.
+///
+/// Implement it to plug a technique into a [`Solver`](crate::Solver):
+///
+/// ```
+/// use sodo::{House, Solver, Strategy, Sudoku};
+///
+/// /// Rules a digit out of a row's other cells once a cell holds it alone.
+/// struct LoneDigit;
+///
+/// impl Strategy for LoneDigit {
+///     fn name(&self) -> &'static str {
+///         "Lone Digit"
+///     }
+///
+///     fn apply(&self, sudoku: &mut Sudoku) -> bool {
+///         let mut progress = false;
+///         for house in sudoku.houses().filter(|h| matches!(h, House::Row(_))) {
+///             let cells = sudoku.house_cells(house);
+///             for &(r, c) in &cells {
+///                 if let Some(v) = sudoku.candidates(r, c).single() {
+///                     for &(r2, c2) in cells.iter().filter(|&&p| p != (r, c)) {
+///                         progress |= sudoku.remove_candidate(r2, c2, v);
+///                     }
+///                 }
+///             }
+///         }
+///         progress
+///     }
+/// }
+///
+/// let solver = Solver::new().insert_strategy_after("Hidden Singles", LoneDigit);
+/// assert_eq!(solver.strategy_names()[2], "Lone Digit");
+/// ```
 pub trait Strategy: Send + Sync {
     /// Attempts to apply the strategy. Returns true if progress was made.
     fn apply(&self, sudoku: &mut Sudoku) -> bool;
 
     /// Returns the strategy name.
     fn name(&self) -> &'static str;
+
+    /// Difficulty of a puzzle that needs this strategy. A puzzle is rated by
+    /// the hardest strategy its logical solve used.
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Medium
+    }
+
+    /// How hard a step with this strategy is, on a scale like Sudoku
+    /// Explainer's, for [`Solver::rate`](crate::Solver::rate). Built-in
+    /// ratings keep to a band per difficulty: below 2.5 for Easy, then up
+    /// to 3.5, 6 and 9. Defaults to 1.5, 3.0, 4.5 or 7.0 by difficulty.
+    fn rating(&self) -> f64 {
+        match self.difficulty() {
+            Difficulty::Easy => 1.5,
+            Difficulty::Medium => 3.0,
+            Difficulty::Hard => 4.5,
+            Difficulty::Expert => 7.0,
+        }
+    }
+
+    /// True for strategies that are only sound when the puzzle has a single
+    /// solution. A [`Solver`](crate::Solver) skips them unless told to
+    /// [`assume_unique`](crate::Solver::assume_unique).
+    fn assumes_unique(&self) -> bool {
+        false
+    }
+
+    /// Applies the strategy as [`apply`](Self::apply) does, and says what
+    /// the deduction rested on, found in the same pass. `None` when no
+    /// progress was made. Defaults to `apply` with nothing to show.
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        self.apply(sudoku).then(Finding::default)
+    }
+}
+
+/// What [`Strategy::find`] saw behind a deduction, for hint UIs. The
+/// placements and eliminations themselves are read off the grid.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Finding {
+    /// Cells the deduction rests on, to highlight.
+    pub highlights: Vec<(usize, usize)>,
+    /// The chain behind it, as linked candidates in order, to draw.
+    pub chain: Vec<(usize, usize, u8)>,
+    /// Houses it works in, for hint text such as "confined to window 2".
+    pub houses: Vec<House>,
 }
 
-/// Returns all available strategies in priority order.
+/// One strategy application, from [`Solver::next_step`](crate::Solver::next_step)
+/// or [`Solver::solve_steps`](crate::Solver::solve_steps).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Step {
+    pub strategy: String,
+    /// Values placed, as (row, col, value).
+    pub placements: Vec<(usize, usize, u8)>,
+    /// Candidates ruled out, as (row, col, value).
+    pub eliminations: Vec<(usize, usize, u8)>,
+    /// Cells the deduction rests on, from [`Finding::highlights`].
+    pub highlights: Vec<(usize, usize)>,
+    /// Candidates of the chain used, from [`Finding::chain`]. Links
+    /// alternate strong and weak, starting and ending strong.
+    pub chain: Vec<(usize, usize, u8)>,
+    /// Houses involved, from [`Finding::houses`].
+    pub houses: Vec<House>,
+}
+
+/// Returns all available strategies in priority order, from easiest to
+/// hardest by [`Strategy::difficulty`].
 pub fn all() -> Vec<Box<dyn Strategy>> {
-    vec![Box::new(NakedSingles), Box::new(HiddenSingles)]
+    vec![
+        Box::new(NakedSingles),
+        Box::new(HiddenSingles),
+        Box::new(LockedCandidates),
+        Box::new(CageCombinations),
+        Box::new(Thermometers),
+        Box::new(Arrows),
+        Box::new(LittleKillers),
+        Box::new(NakedSubsets),
+        Box::new(HiddenSubsets),
+        Box::new(XWing),
+        Box::new(Swordfish),
+        Box::new(Skyscraper),
+        Box::new(TwoStringKite),
+        Box::new(TurbotFish),
+        Box::new(EmptyRectangle),
+        Box::new(SimpleColoring),
+        Box::new(XyzWing),
+        Box::new(WWing),
+        Box::new(UniqueRectangle),
+        Box::new(XChain::default()),
+        Box::new(Jellyfish),
+        Box::new(Medusa),
+        Box::new(AlsXz),
+        Box::new(XyChain::default()),
+        Box::new(Aic::default()),
+    ]
+}
+
+/// [`all`] plus strategies too slow for everyday solving, such as
+/// [`PatternOverlay`].
+pub fn extreme() -> Vec<Box<dyn Strategy>> {
+    let mut strategies = all();
+    strategies.push(Box::new(PatternOverlay::default()));
+    strategies
 }
 
 /// Fills cells that have only one candidate.
@@ -22,18 +165,24 @@ impl Strategy for NakedSingles {
         "Naked Singles"
     }
 
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Easy
+    }
+
+    fn rating(&self) -> f64 {
+        2.3
+    }
+
     fn apply(&self, sudoku: &mut Sudoku) -> bool {
         let mut progress = false;
 
-        for r in 0..sudoku.size {
-            for c in 0..sudoku.size {
-                if sudoku.grid[r][c].is_empty() {
-                    let cands = sudoku.candidates(r, c);
-                    if cands.len() == 1 {
-                        let val = *cands.iter().next().unwrap();
-                        let _ = sudoku.set(r, c, val);
-                        progress = true;
-                    }
+        for r in 0..sudoku.size() {
+            for c in 0..sudoku.size() {
+                let mask = sudoku.candidate_mask(r, c);
+                if mask.count_ones() == 1 {
+                    let val = mask.trailing_zeros() as u8 + 1;
+                    let _ = sudoku.set(r, c, val);
+                    progress = true;
                 }
             }
         }
@@ -50,35 +199,36 @@ impl Strategy for HiddenSingles {
         "Hidden Singles"
     }
 
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Easy
+    }
+
+    fn rating(&self) -> f64 {
+        1.5
+    }
+
     fn apply(&self, sudoku: &mut Sudoku) -> bool {
         let mut progress = false;
 
-        for i in 0..sudoku.size {
-            progress |= apply_row(sudoku, i);
-            progress |= apply_col(sudoku, i);
-        }
-
-        let bs = sudoku.box_size;
-        for br in 0..bs {
-            for bc in 0..bs {
-                progress |= apply_box(sudoku, br, bc);
-            }
+        for house in sudoku.houses() {
+            progress |= place_hidden(sudoku, sudoku.house_cells(house));
         }
 
         progress
     }
 }
 
-fn apply_row(sudoku: &mut Sudoku, row: usize) -> bool {
+/// Places every value that has exactly one candidate position in the unit.
+fn place_hidden(sudoku: &mut Sudoku, cells: Vec<Pos>) -> bool {
     let mut progress = false;
 
-    for val in 1..=sudoku.size as u8 {
-        let cols: Vec<_> = (0..sudoku.size)
-            .filter(|&c| sudoku.grid[row][c].is_empty() && sudoku.candidates(row, c).contains(&val))
-            .collect();
+    for val in 1..=sudoku.size() as u8 {
+        let mut spots = cells
+            .iter()
+            .filter(|&&(r, c)| sudoku.candidate_mask(r, c) & bit(val) != 0);
 
-        if cols.len() == 1 {
-            let _ = sudoku.set(row, cols[0], val);
+        if let (Some(&(r, c)), None) = (spots.next(), spots.next()) {
+            let _ = sudoku.set(r, c, val);
             progress = true;
         }
     }
@@ -86,42 +236,1948 @@ fn apply_row(sudoku: &mut Sudoku, row: usize) -> bool {
     progress
 }
 
-fn apply_col(sudoku: &mut Sudoku, col: usize) -> bool {
+/// Pointing and claiming: when a digit's spots in one house all lie in a
+/// second house, it is eliminated from the rest of that second house. Boxes
+/// point into rows and columns; rows and columns claim within boxes. Variant
+/// houses do both.
+pub struct LockedCandidates;
+
+impl Strategy for LockedCandidates {
+    fn name(&self) -> &'static str {
+        "Locked Candidates"
+    }
+
+    fn rating(&self) -> f64 {
+        2.6
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        lock_candidates(sudoku, &mut Vec::new())
+    }
+
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        let mut houses = Vec::new();
+        lock_candidates(sudoku, &mut houses).then(|| Finding {
+            houses,
+            ..Finding::default()
+        })
+    }
+}
+
+/// Applies [`LockedCandidates`], noting each confining house and the house
+/// it clears in `used`.
+fn lock_candidates(sudoku: &mut Sudoku, used: &mut Vec<House>) -> bool {
     let mut progress = false;
 
-    for val in 1..=sudoku.size as u8 {
-        let rows: Vec<_> = (0..sudoku.size)
-            .filter(|&r| sudoku.grid[r][col].is_empty() && sudoku.candidates(r, col).contains(&val))
-            .collect();
+    for house in sudoku.houses() {
+        let cells = sudoku.house_cells(house);
+        for d in 1..=sudoku.size() as u8 {
+            let spots: Vec<Pos> = cells
+                .iter()
+                .copied()
+                .filter(|&(r, c)| sudoku.candidate_mask(r, c) & bit(d) != 0)
+                .collect();
+            let Some(&(r, c)) = spots.first() else {
+                continue;
+            };
+            // Two lines cross in one cell, which says nothing new.
+            let line = |h: House| matches!(h, House::Row(_) | House::Col(_) | House::Diagonal(_));
+            let targets = sudoku
+                .houses_at(r, c)
+                .into_iter()
+                .filter(|&t| t != house && !(line(t) && line(house)));
 
-        if rows.len() == 1 {
-            let _ = sudoku.set(rows[0], col, val);
-            progress = true;
+            for target in targets {
+                let line = sudoku.house_cells(target);
+                if !spots.iter().all(|p| line.contains(p)) {
+                    continue;
+                }
+                let mut hit = false;
+                for (r, c) in line {
+                    if !cells.contains(&(r, c)) {
+                        hit |= sudoku.eliminate(r, c, bit(d));
+                    }
+                }
+                if hit {
+                    for h in [house, target] {
+                        if !used.contains(&h) {
+                            used.push(h);
+                        }
+                    }
+                }
+                progress |= hit;
+            }
         }
     }
 
     progress
 }
 
-fn apply_box(sudoku: &mut Sudoku, br: usize, bc: usize) -> bool {
+/// Killer cages: rules out candidates that fit no way of filling a cage's
+/// empty cells with distinct digits making up the rest of its sum.
+pub struct CageCombinations;
+
+impl Strategy for CageCombinations {
+    fn name(&self) -> &'static str {
+        "Cage Combinations"
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let cages = sudoku.cages().len();
+        eliminate_each(sudoku, cages, cage_eliminations).is_some()
+    }
+
+    /// The first cage that rules something out.
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        let cages = sudoku.cages().len();
+        let cage = House::Cage(eliminate_each(sudoku, cages, cage_eliminations)?);
+        Some(Finding {
+            highlights: sudoku.house_cells(cage),
+            houses: vec![cage],
+            ..Finding::default()
+        })
+    }
+}
+
+/// Rules out `eliminations` of each of the first `count` cages, lines or
+/// clues in turn, returning the first one that made progress.
+fn eliminate_each(
+    sudoku: &mut Sudoku,
+    count: usize,
+    eliminations: fn(&Sudoku, usize) -> Vec<(Pos, Mask)>,
+) -> Option<usize> {
+    let mut first = None;
+    for i in 0..count {
+        for ((r, c), mask) in eliminations(sudoku, i) {
+            if sudoku.eliminate(r, c, mask) {
+                first.get_or_insert(i);
+            }
+        }
+    }
+    first
+}
+
+/// Rules out every candidate of `ruled_out`. Returns true if progress was
+/// made.
+pub(crate) fn eliminate_all(
+    sudoku: &mut Sudoku,
+    ruled_out: impl IntoIterator<Item = (Pos, u8)>,
+) -> bool {
+    ruled_out.into_iter().fold(false, |progress, ((r, c), d)| {
+        sudoku.eliminate(r, c, bit(d)) | progress
+    })
+}
+
+/// Candidates of cage `i` that no completion of it can use, per cell.
+fn cage_eliminations(sudoku: &Sudoku, i: usize) -> Vec<(Pos, Mask)> {
+    let cage = &sudoku.cages()[i];
+    let mut open = Vec::new();
+    let mut left = cage.sum;
+    for &(r, c) in &cage.cells {
+        match sudoku.cell(r, c).value() {
+            Some(v) => left = left.saturating_sub(u32::from(v)),
+            None => open.push((r, c)),
+        }
+    }
+    // A lone empty cell already gets exactly the digit that is left.
+    if open.len() < 2 {
+        return Vec::new();
+    }
+    let masks: Vec<Mask> = open
+        .iter()
+        .map(|&(r, c)| sudoku.candidate_mask(r, c))
+        .collect();
+    let mut fits = vec![0; open.len()];
+    fill_cage(&masks, 0, 0, left, &mut fits, &mut HashMap::new());
+    open.into_iter()
+        .zip(masks.iter().zip(fits))
+        .map(|(p, (&m, fit))| (p, m & !fit))
+        .filter(|&(_, m)| m != 0)
+        .collect()
+}
+
+/// Whether cells `k..` can take distinct candidates outside `used` adding up
+/// to `left`, recording in `fits` each digit some such choice uses. The
+/// outcome of a state depends only on `k` and `used`, so it is memoized.
+fn fill_cage(
+    masks: &[Mask],
+    k: usize,
+    used: Mask,
+    left: u32,
+    fits: &mut [Mask],
+    memo: &mut HashMap<(usize, Mask), bool>,
+) -> bool {
+    if k == masks.len() {
+        return left == 0;
+    }
+    if let Some(&ok) = memo.get(&(k, used)) {
+        return ok;
+    }
+    let mut ok = false;
+    for v in digits(masks[k] & !used) {
+        if u32::from(v) > left {
+            break;
+        }
+        if fill_cage(masks, k + 1, used | bit(v), left - u32::from(v), fits, memo) {
+            fits[k] |= bit(v);
+            ok = true;
+        }
+    }
+    memo.insert((k, used), ok);
+    ok
+}
+
+/// Thermometers: each cell must beat the smallest digit still possible in
+/// the cell before it, and stay under the largest one possible after it.
+pub struct Thermometers;
+
+impl Strategy for Thermometers {
+    fn name(&self) -> &'static str {
+        "Thermometers"
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let lines = sudoku.lines().len();
+        eliminate_each(sudoku, lines, thermo_eliminations).is_some()
+    }
+
+    /// The first thermometer that rules something out.
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        let lines = sudoku.lines().len();
+        let i = eliminate_each(sudoku, lines, thermo_eliminations)?;
+        Some(Finding {
+            highlights: sudoku.lines()[i].cells.clone(),
+            ..Finding::default()
+        })
+    }
+}
+
+/// Candidates of line `i` outside the bounds its neighbours leave, per
+/// cell; none unless it is a thermometer.
+fn thermo_eliminations(sudoku: &Sudoku, i: usize) -> Vec<(Pos, Mask)> {
+    let line = &sudoku.lines()[i];
+    if line.kind != LineKind::Thermo {
+        return Vec::new();
+    }
+    let masks = line_masks(sudoku, &line.cells);
+    let mut kept = masks.clone();
+    for k in 1..kept.len() {
+        // Above the lowest digit before.
+        let low = kept[k - 1].trailing_zeros() as usize + 1;
+        kept[k] &= !span(1, low);
+    }
+    for k in (0..kept.len() - 1).rev() {
+        // Below the highest digit after.
+        let high = Mask::BITS as usize - kept[k + 1].leading_zeros() as usize;
+        kept[k] &= span(1, high.saturating_sub(1));
+    }
+    changed(sudoku, &line.cells, &masks, &kept)
+}
+
+/// Arrows: the pill can only show a number the shaft's digits can add up
+/// to, and each shaft cell only a digit that leaves the rest of the shaft
+/// able to make up such a number.
+pub struct Arrows;
+
+impl Strategy for Arrows {
+    fn name(&self) -> &'static str {
+        "Arrows"
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let lines = sudoku.lines().len();
+        eliminate_each(sudoku, lines, arrow_eliminations).is_some()
+    }
+
+    /// The first arrow that rules something out.
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        let lines = sudoku.lines().len();
+        let i = eliminate_each(sudoku, lines, arrow_eliminations)?;
+        Some(Finding {
+            highlights: sudoku.lines()[i].cells.clone(),
+            ..Finding::default()
+        })
+    }
+}
+
+/// Candidates of line `i` that fit no pill number within the shaft's
+/// range, per cell; none unless it is an arrow.
+fn arrow_eliminations(sudoku: &Sudoku, i: usize) -> Vec<(Pos, Mask)> {
+    let line = &sudoku.lines()[i];
+    let LineKind::Arrow { pill } = line.kind else {
+        return Vec::new();
+    };
+    let masks = line_masks(sudoku, &line.cells);
+    let lowest = |m: Mask| m.trailing_zeros() as usize + 1;
+    let highest = |m: Mask| Mask::BITS as usize - m.leading_zeros() as usize;
+    let shaft = &masks[pill..];
+    if shaft.contains(&0) {
+        return Vec::new();
+    }
+    let low: usize = shaft.iter().map(|&m| lowest(m)).sum();
+    let high: usize = shaft.iter().map(|&m| highest(m)).sum();
+
+    // Pill readings the shaft can reach, as digits per pill cell.
+    let mut kept = masks.clone();
+    kept[..pill].fill(0);
+    let (mut least, mut most) = (usize::MAX, 0);
+    let mut reach = |number: usize, ds: &[u8]| {
+        if (low..=high).contains(&number) {
+            for (k, &d) in ds.iter().enumerate() {
+                kept[k] |= bit(d);
+            }
+            least = least.min(number);
+            most = most.max(number);
+        }
+    };
+    match pill {
+        1 => digits(masks[0]).for_each(|d| reach(usize::from(d), &[d])),
+        _ => {
+            for t in digits(masks[0]) {
+                digits(masks[1]).for_each(|u| reach(usize::from(t) * 10 + usize::from(u), &[t, u]));
+            }
+        }
+    }
+    if most == 0 {
+        return Vec::new();
+    }
+    for (k, &m) in masks.iter().enumerate().skip(pill) {
+        let floor = least.saturating_sub(high - highest(m)).max(1);
+        let ceil = (most - (low - lowest(m))).min(sudoku.size());
+        kept[k] &= span(floor, ceil);
+    }
+    changed(sudoku, &line.cells, &masks, &kept)
+}
+
+/// Little Killers: each cell on a clued diagonal takes only digits that
+/// leave the others able to make up the rest of the sum.
+pub struct LittleKillers;
+
+impl Strategy for LittleKillers {
+    fn name(&self) -> &'static str {
+        "Little Killers"
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let clues = sudoku.clues().len();
+        eliminate_each(sudoku, clues, little_killer_eliminations).is_some()
+    }
+
+    /// The first clue that rules something out.
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        let clues = sudoku.clues().len();
+        let i = eliminate_each(sudoku, clues, little_killer_eliminations)?;
+        Some(Finding {
+            highlights: sudoku.clue_cells(i).to_vec(),
+            ..Finding::default()
+        })
+    }
+}
+
+/// Candidates along clue `i` that its sum can't reach with the others'
+/// smallest or largest candidates, per cell; none unless it is a Little
+/// Killer.
+fn little_killer_eliminations(sudoku: &Sudoku, i: usize) -> Vec<(Pos, Mask)> {
+    let clue = &sudoku.clues()[i];
+    if clue.kind != ClueKind::LittleKiller {
+        return Vec::new();
+    }
+    let cells = sudoku.clue_cells(i);
+    let masks = line_masks(sudoku, cells);
+    if masks.contains(&0) {
+        return Vec::new();
+    }
+    let lowest = |m: Mask| m.trailing_zeros() as usize + 1;
+    let highest = |m: Mask| Mask::BITS as usize - m.leading_zeros() as usize;
+    let low: usize = masks.iter().map(|&m| lowest(m)).sum();
+    let high: usize = masks.iter().map(|&m| highest(m)).sum();
+    let value = clue.value as usize;
+    let kept: Vec<Mask> = masks
+        .iter()
+        .map(|&m| {
+            let floor = value.saturating_sub(high - highest(m)).max(1);
+            let ceil = value.saturating_sub(low - lowest(m)).min(sudoku.size());
+            m & span(floor, ceil)
+        })
+        .collect();
+    changed(sudoku, cells, &masks, &kept)
+}
+
+/// Candidates of `cells`, with a placed digit standing in as the cell's
+/// only candidate.
+fn line_masks(sudoku: &Sudoku, cells: &[Pos]) -> Vec<Mask> {
+    cells
+        .iter()
+        .map(|&(r, c)| match sudoku.cell(r, c).value() {
+            Some(v) => bit(v),
+            None => sudoku.candidate_mask(r, c),
+        })
+        .collect()
+}
+
+/// What narrowing `masks` to `kept` rules out in the empty cells.
+fn changed(sudoku: &Sudoku, cells: &[Pos], masks: &[Mask], kept: &[Mask]) -> Vec<(Pos, Mask)> {
+    cells
+        .iter()
+        .zip(masks.iter().zip(kept))
+        .filter(|&(&(r, c), _)| sudoku.cell(r, c).is_empty())
+        .map(|(&p, (&m, &k))| (p, m & !k))
+        .filter(|&(_, m)| m != 0)
+        .collect()
+}
+
+/// Naked pairs, triples and quads: N cells in a house whose candidates
+/// together number N. Those digits are eliminated from the rest of the house.
+pub struct NakedSubsets;
+
+impl Strategy for NakedSubsets {
+    fn name(&self) -> &'static str {
+        "Naked Subsets"
+    }
+
+    fn rating(&self) -> f64 {
+        3.0
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let mut progress = false;
+
+        for house in sudoku.houses() {
+            let empty: Vec<Pos> = sudoku
+                .house_cells(house)
+                .into_iter()
+                .filter(|&(r, c)| sudoku.candidate_mask(r, c) != 0)
+                .collect();
+
+            for n in 2..=4.min(empty.len().saturating_sub(1)) {
+                let small: Vec<Pos> = empty
+                    .iter()
+                    .copied()
+                    .filter(|&(r, c)| sudoku.candidate_mask(r, c).count_ones() as usize <= n)
+                    .collect();
+
+                for_each_subset(small.len(), n, &mut |picked| {
+                    let union: Mask = picked
+                        .iter()
+                        .fold(0, |m, &i| m | sudoku.candidate_mask(small[i].0, small[i].1));
+                    if union.count_ones() as usize != n {
+                        return;
+                    }
+                    for &(r, c) in &empty {
+                        if !picked.iter().any(|&i| small[i] == (r, c)) {
+                            progress |= sudoku.eliminate(r, c, union);
+                        }
+                    }
+                });
+            }
+        }
+
+        progress
+    }
+}
+
+/// Hidden pairs, triples and quads: N digits that, within a house, only fit
+/// in the same N cells. Every other candidate is eliminated from those cells.
+pub struct HiddenSubsets;
+
+impl Strategy for HiddenSubsets {
+    fn name(&self) -> &'static str {
+        "Hidden Subsets"
+    }
+
+    fn rating(&self) -> f64 {
+        3.4
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let mut progress = false;
+        let full = sudoku.full_mask();
+
+        for house in sudoku.houses() {
+            let cells = sudoku.house_cells(house);
+            let free = cells
+                .iter()
+                .fold(0, |m, &(r, c)| m | sudoku.candidate_mask(r, c));
+            // For each open digit, the house positions it can still take.
+            let spots: Vec<(u8, Mask)> = digits(free)
+                .map(|d| {
+                    let at = cells
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_, &(r, c))| sudoku.candidate_mask(r, c) & bit(d) != 0)
+                        .fold(0, |m: Mask, (i, _)| m | 1 << i);
+                    (d, at)
+                })
+                .collect();
+
+            for n in 2..=4.min(spots.len().saturating_sub(1)) {
+                let few: Vec<(u8, Mask)> = spots
+                    .iter()
+                    .copied()
+                    .filter(|&(_, at)| at.count_ones() as usize <= n)
+                    .collect();
+
+                for_each_subset(few.len(), n, &mut |picked| {
+                    let at = picked.iter().fold(0, |m, &i| m | few[i].1);
+                    if at.count_ones() as usize != n {
+                        return;
+                    }
+                    let keep = picked.iter().fold(0, |m, &i| m | bit(few[i].0));
+                    for (i, &(r, c)) in cells.iter().enumerate() {
+                        if at & 1 << i != 0 {
+                            progress |= sudoku.eliminate(r, c, full & !keep);
+                        }
+                    }
+                });
+            }
+        }
+
+        progress
+    }
+}
+
+/// A digit confined to the same two columns in two rows (or the same two
+/// rows in two columns) is eliminated from the rest of those columns (rows).
+pub struct XWing;
+
+impl Strategy for XWing {
+    fn name(&self) -> &'static str {
+        "X-Wing"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        3.6
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        fish(sudoku, 2)
+    }
+}
+
+/// X-Wing over three rows and three columns.
+pub struct Swordfish;
+
+impl Strategy for Swordfish {
+    fn name(&self) -> &'static str {
+        "Swordfish"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        3.8
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        fish(sudoku, 3)
+    }
+}
+
+/// X-Wing over four rows and four columns.
+pub struct Jellyfish;
+
+impl Strategy for Jellyfish {
+    fn name(&self) -> &'static str {
+        "Jellyfish"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        5.2
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        fish(sudoku, 4)
+    }
+}
+
+/// Finds basic fish of `n` lines: a digit whose spots in `n` base rows all
+/// fall within `n` columns is eliminated from the rest of those columns.
+/// Columns-as-base is checked the same way.
+fn fish(sudoku: &mut Sudoku, n: usize) -> bool {
     let mut progress = false;
-    let bs = sudoku.box_size;
-    let (sr, sc) = (br * bs, bc * bs);
-
-    for val in 1..=sudoku.size as u8 {
-        let cells: Vec<_> = (sr..sr + bs)
-            .flat_map(|r| (sc..sc + bs).map(move |c| (r, c)))
-            .filter(|&(r, c)| {
-                sudoku.grid[r][c].is_empty() && sudoku.candidates(r, c).contains(&val)
-            })
-            .collect();
+    let size = sudoku.size();
 
-        if cells.len() == 1 {
-            let (r, c) = cells[0];
-            let _ = sudoku.set(r, c, val);
-            progress = true;
+    for d in 1..=size as u8 {
+        for by_row in [true, false] {
+            let at = |line: usize, k: usize| if by_row { (line, k) } else { (k, line) };
+            // Positions of `d` along each line, as a bitset.
+            let spots: Vec<Mask> = (0..size)
+                .map(|line| {
+                    (0..size)
+                        .filter(|&k| {
+                            let (r, c) = at(line, k);
+                            sudoku.candidate_mask(r, c) & bit(d) != 0
+                        })
+                        .fold(0, |m, k| m | 1 << k)
+                })
+                .collect();
+            let base: Vec<usize> = (0..size)
+                .filter(|&l| (1..=n).contains(&(spots[l].count_ones() as usize)))
+                .collect();
+
+            for_each_subset(base.len(), n, &mut |picked| {
+                let cover = picked.iter().fold(0, |m: Mask, &i| m | spots[base[i]]);
+                if cover.count_ones() as usize != n {
+                    return;
+                }
+                for k in (0..size).filter(|&k| cover & 1 << k != 0) {
+                    for line in (0..size).filter(|&l| !picked.iter().any(|&i| base[i] == l)) {
+                        let (r, c) = at(line, k);
+                        progress |= sudoku.eliminate(r, c, bit(d));
+                    }
+                }
+            });
         }
     }
 
     progress
 }
+
+/// XYZ-Wing: a pivot with candidates {x, y, z} seeing wings {x, z} and
+/// {y, z}. Whichever holds z, cells seeing all three can't.
+pub struct XyzWing;
+
+impl Strategy for XyzWing {
+    fn name(&self) -> &'static str {
+        "XYZ-Wing"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        4.4
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let mut progress = false;
+        let cells = empty_cells(sudoku);
+
+        for &pivot in &cells {
+            let pm = sudoku.candidate_mask(pivot.0, pivot.1);
+            if pm.count_ones() != 3 {
+                continue;
+            }
+            let wings: Vec<(Pos, Mask)> = cells
+                .iter()
+                .map(|&(r, c)| ((r, c), sudoku.candidate_mask(r, c)))
+                .filter(|&(w, m)| m.count_ones() == 2 && m & !pm == 0 && sees(sudoku, pivot, w))
+                .collect();
+
+            for (i, &(a, am)) in wings.iter().enumerate() {
+                for &(b, bm) in &wings[i + 1..] {
+                    let z = am & bm;
+                    if am | bm != pm || z.count_ones() != 1 {
+                        continue;
+                    }
+                    for &t in &cells {
+                        if t != pivot
+                            && t != a
+                            && t != b
+                            && sees(sudoku, t, pivot)
+                            && sees(sudoku, t, a)
+                            && sees(sudoku, t, b)
+                        {
+                            progress |= sudoku.eliminate(t.0, t.1, z);
+                        }
+                    }
+                }
+            }
+        }
+
+        progress
+    }
+}
+
+/// W-Wing: two unconnected cells with the same two candidates {x, y}, each
+/// seeing one end of a strong link on x. One of them must be y, so cells
+/// seeing both can't.
+pub struct WWing;
+
+impl Strategy for WWing {
+    fn name(&self) -> &'static str {
+        "W-Wing"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        4.4
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let mut progress = false;
+        let cells = empty_cells(sudoku);
+        let pairs: Vec<(Pos, Mask)> = cells
+            .iter()
+            .map(|&(r, c)| ((r, c), sudoku.candidate_mask(r, c)))
+            .filter(|&(_, m)| m.count_ones() == 2)
+            .collect();
+
+        for (i, &(a, m)) in pairs.iter().enumerate() {
+            for &(b, bm) in &pairs[i + 1..] {
+                if bm != m || sees(sudoku, a, b) {
+                    continue;
+                }
+                for x in digits(m) {
+                    let linked = strong_links(sudoku, x).into_iter().any(|(_, p, q)| {
+                        let ends = [a, b];
+                        !ends.contains(&p)
+                            && !ends.contains(&q)
+                            && (sees(sudoku, p, a) && sees(sudoku, q, b)
+                                || sees(sudoku, p, b) && sees(sudoku, q, a))
+                    });
+                    if !linked {
+                        continue;
+                    }
+                    let y = m & !bit(x);
+                    for &t in &cells {
+                        if sees(sudoku, t, a) && sees(sudoku, t, b) {
+                            progress |= sudoku.eliminate(t.0, t.1, y);
+                        }
+                    }
+                }
+            }
+        }
+
+        progress
+    }
+}
+
+/// Skyscraper: two strong links on a digit in parallel rows (or columns),
+/// with one end of each sharing a house.
+pub struct Skyscraper;
+
+impl Strategy for Skyscraper {
+    fn name(&self) -> &'static str {
+        "Skyscraper"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        4.0
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        linked_pairs(sudoku, |a, b| {
+            matches!(
+                (a, b),
+                (House::Row(_), House::Row(_)) | (House::Col(_), House::Col(_))
+            )
+        })
+    }
+}
+
+/// 2-String Kite: a strong link in a row and one in a column, with one end
+/// of each sharing a box.
+pub struct TwoStringKite;
+
+impl Strategy for TwoStringKite {
+    fn name(&self) -> &'static str {
+        "2-String Kite"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        4.1
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        linked_pairs(sudoku, |a, b| {
+            matches!(
+                (a, b),
+                (House::Row(_), House::Col(_)) | (House::Col(_), House::Row(_))
+            )
+        })
+    }
+}
+
+/// Turbot Fish: any two strong links on a digit joined end to end by a shared
+/// house; covers the cases with a link inside a box.
+pub struct TurbotFish;
+
+impl Strategy for TurbotFish {
+    fn name(&self) -> &'static str {
+        "Turbot Fish"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        4.2
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        linked_pairs(sudoku, |_, _| true)
+    }
+}
+
+/// Empty Rectangle: a box whose spots for a digit all lie on one row and
+/// one column of it, plus a strong link on a line that crosses one arm outside
+/// the box. The far end of the link and the other arm can't both hold the digit.
+pub struct EmptyRectangle;
+
+impl Strategy for EmptyRectangle {
+    fn name(&self) -> &'static str {
+        "Empty Rectangle"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        4.3
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let mut progress = false;
+        // The box's rows and columns are read off its corner below.
+        if sudoku.is_jigsaw() {
+            return false;
+        }
+        let (bh, bw) = (sudoku.box_rows(), sudoku.box_cols());
+
+        for d in 1..=sudoku.size() as u8 {
+            let links = strong_links(sudoku, d);
+            for house in sudoku.houses() {
+                if !matches!(house, House::Box(_)) {
+                    continue;
+                }
+                let cells = sudoku.house_cells(house);
+                let spots: Vec<Pos> = cells
+                    .iter()
+                    .copied()
+                    .filter(|&(r, c)| sudoku.candidate_mask(r, c) & bit(d) != 0)
+                    .collect();
+                if spots.len() < 2 {
+                    continue;
+                }
+                let (top, left) = cells[0];
+                let rows = top..top + bh;
+                let cols = left..left + bw;
+
+                for (row, col) in cells.iter().copied() {
+                    if !spots.iter().all(|&(r, c)| r == row || c == col) {
+                        continue;
+                    }
+                    for &(line, a, b) in &links {
+                        for (near, far) in [(a, b), (b, a)] {
+                            // If `far` held d, `near` wouldn't, leaving the
+                            // box's d on `col` (or `row`), where the target is.
+                            let target = match line {
+                                House::Col(c) if !cols.contains(&c) && near.0 == row => {
+                                    (far.0, col)
+                                }
+                                House::Row(r) if !rows.contains(&r) && near.1 == col => {
+                                    (row, far.1)
+                                }
+                                _ => continue,
+                            };
+                            if rows.contains(&target.0) && cols.contains(&target.1) {
+                                continue;
+                            }
+                            progress |= sudoku.eliminate(target.0, target.1, bit(d));
+                        }
+                    }
+                }
+            }
+        }
+
+        progress
+    }
+}
+
+/// Simple coloring: the strong links on a digit form chains whose cells
+/// alternate between two colors, one of which holds the digit throughout.
+/// A color seen twice in a house is false; a cell seeing both colors can't
+/// hold the digit. Applies one chain at a time.
+pub struct SimpleColoring;
+
+impl Strategy for SimpleColoring {
+    fn name(&self) -> &'static str {
+        "Simple Coloring"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        4.5
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        self.find(sudoku).is_some()
+    }
+
+    /// Highlights the colored chain cells.
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        let chain = find_coloring(sudoku)?;
+        let ruled_out = chain.ruled_out.into_iter().map(|p| (p, chain.digit));
+        eliminate_all(sudoku, ruled_out).then(|| Finding {
+            highlights: chain.cells,
+            ..Finding::default()
+        })
+    }
+}
+
+/// A productive chain from [`find_coloring`].
+struct Coloring {
+    digit: u8,
+    cells: Vec<Pos>,
+    ruled_out: Vec<Pos>,
+}
+
+/// The first two-colored chain, by digit, that rules out a candidate.
+fn find_coloring(sudoku: &Sudoku) -> Option<Coloring> {
+    let n = sudoku.size();
+    let cells = empty_cells(sudoku);
+
+    for d in 1..=n as u8 {
+        let links = strong_links(sudoku, d);
+        let mut seen = vec![false; n * n];
+
+        for &(_, start, _) in &links {
+            if seen[start.0 * n + start.1] {
+                continue;
+            }
+            seen[start.0 * n + start.1] = true;
+            // Breadth-first, coloring each cell opposite to its parent.
+            let mut chain = vec![(start, false)];
+            let mut i = 0;
+            while i < chain.len() {
+                let (at, color) = chain[i];
+                for &(_, p, q) in &links {
+                    let next = match at {
+                        _ if at == p => q,
+                        _ if at == q => p,
+                        _ => continue,
+                    };
+                    if !seen[next.0 * n + next.1] {
+                        seen[next.0 * n + next.1] = true;
+                        chain.push((next, !color));
+                    }
+                }
+                i += 1;
+            }
+
+            let wrap = [false, true].into_iter().find(|&color| {
+                let side: Vec<Pos> = chain.iter().filter(|e| e.1 == color).map(|e| e.0).collect();
+                side.iter()
+                    .enumerate()
+                    .any(|(k, &a)| side[k + 1..].iter().any(|&b| sees(sudoku, a, b)))
+            });
+            let ruled_out: Vec<Pos> = match wrap {
+                Some(color) => chain.iter().filter(|e| e.1 == color).map(|e| e.0).collect(),
+                None => cells
+                    .iter()
+                    .copied()
+                    .filter(|&t| {
+                        sudoku.candidate_mask(t.0, t.1) & bit(d) != 0
+                            && !chain.iter().any(|e| e.0 == t)
+                            && [false, true].iter().all(|&color| {
+                                chain.iter().any(|e| e.1 == color && sees(sudoku, t, e.0))
+                            })
+                    })
+                    .collect(),
+            };
+
+            if !ruled_out.is_empty() {
+                return Some(Coloring {
+                    digit: d,
+                    cells: chain.into_iter().map(|e| e.0).collect(),
+                    ruled_out,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// 3D Medusa: simple coloring across digits, where the two candidates of a
+/// bivalue cell also link. A color is false if it lands twice in a cell, twice
+/// on a digit in one house, or would leave some cell with nothing; otherwise
+/// candidates that one of the colors must knock out are eliminated. Applies one
+/// cluster at a time.
+pub struct Medusa;
+
+impl Strategy for Medusa {
+    fn name(&self) -> &'static str {
+        "3D Medusa"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Expert
+    }
+
+    fn rating(&self) -> f64 {
+        6.8
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        self.find(sudoku).is_some()
+    }
+
+    /// Highlights the cells holding colored candidates.
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        let cluster = find_medusa(sudoku)?;
+        eliminate_all(sudoku, cluster.ruled_out).then(|| Finding {
+            highlights: cluster.cells,
+            ..Finding::default()
+        })
+    }
+}
+
+/// A productive cluster from [`find_medusa`].
+struct MedusaCluster {
+    cells: Vec<Pos>,
+    ruled_out: Vec<(Pos, u8)>,
+}
+
+/// The first two-colored cluster of candidates that rules something out.
+fn find_medusa(sudoku: &Sudoku) -> Option<MedusaCluster> {
+    let n = sudoku.size();
+    let cells = empty_cells(sudoku);
+    let links: Vec<_> = (1..=n as u8).map(|d| strong_links(sudoku, d)).collect();
+    let node = |(r, c): Pos, d: u8| (r * n + c) * n + d as usize - 1;
+    let mut seen = vec![false; n * n * n];
+
+    for &start in &cells {
+        for d in digits(sudoku.candidate_mask(start.0, start.1)) {
+            if seen[node(start, d)] {
+                continue;
+            }
+            seen[node(start, d)] = true;
+            // Breadth-first over candidates, coloring each opposite to its parent.
+            let mut cluster = vec![(start, d, false)];
+            let mut i = 0;
+            while i < cluster.len() {
+                let (at, d, color) = cluster[i];
+                let mask = sudoku.candidate_mask(at.0, at.1);
+                let mut next: Vec<(Pos, u8)> = Vec::new();
+                if mask.count_ones() == 2 {
+                    next.extend(digits(mask & !bit(d)).map(|e| (at, e)));
+                }
+                for &(_, p, q) in &links[d as usize - 1] {
+                    match at {
+                        _ if at == p => next.push((q, d)),
+                        _ if at == q => next.push((p, d)),
+                        _ => {}
+                    }
+                }
+                for (pos, e) in next {
+                    if !seen[node(pos, e)] {
+                        seen[node(pos, e)] = true;
+                        cluster.push((pos, e, !color));
+                    }
+                }
+                i += 1;
+            }
+            if cluster.len() < 2 {
+                continue;
+            }
+
+            let ruled_out = medusa_eliminations(sudoku, &cells, &cluster);
+            if !ruled_out.is_empty() {
+                let mut marked: Vec<Pos> = cluster.iter().map(|e| e.0).collect();
+                marked.sort_unstable();
+                marked.dedup();
+                return Some(MedusaCluster {
+                    cells: marked,
+                    ruled_out,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// What a colored cluster rules out, by the standard Medusa rules.
+fn medusa_eliminations(
+    sudoku: &Sudoku,
+    cells: &[Pos],
+    cluster: &[(Pos, u8, bool)],
+) -> Vec<(Pos, u8)> {
+    let colored = |pos: Pos, d: u8| cluster.iter().find(|e| e.0 == pos && e.1 == d).map(|e| e.2);
+
+    // A color that contradicts itself is false, and all of it goes.
+    let broken = [false, true].into_iter().find(|&color| {
+        let side: Vec<(Pos, u8)> = cluster
+            .iter()
+            .filter(|e| e.2 == color)
+            .map(|e| (e.0, e.1))
+            .collect();
+        let clash = side.iter().enumerate().any(|(k, &(a, x))| {
+            side[k + 1..]
+                .iter()
+                .any(|&(b, y)| a == b || x == y && sees(sudoku, a, b))
+        });
+        // Some cell would have every candidate seen by this color.
+        let empties = cells.iter().any(|&t| {
+            let mask = sudoku.candidate_mask(t.0, t.1);
+            digits(mask).all(|d| colored(t, d).is_none())
+                && digits(mask).all(|d| side.iter().any(|&(p, x)| x == d && sees(sudoku, t, p)))
+        });
+        clash || empties
+    });
+    if let Some(color) = broken {
+        return cluster
+            .iter()
+            .filter(|e| e.2 == color)
+            .map(|e| (e.0, e.1))
+            .collect();
+    }
+
+    let mut out = Vec::new();
+    for &t in cells {
+        let here: Vec<(u8, bool)> = cluster
+            .iter()
+            .filter(|e| e.0 == t)
+            .map(|e| (e.1, e.2))
+            .collect();
+        for d in digits(sudoku.candidate_mask(t.0, t.1)) {
+            if colored(t, d).is_some() {
+                continue;
+            }
+            let seen_by = |color: bool| {
+                cluster
+                    .iter()
+                    .any(|e| e.1 == d && e.2 == color && sees(sudoku, t, e.0))
+            };
+            // Both colors in the cell: one of them is the value.
+            let both_here = here.iter().any(|e| e.1) && here.iter().any(|e| !e.1);
+            // Sees d in both colors.
+            let both_seen = seen_by(false) && seen_by(true);
+            // One color in the cell, d of the other color in view.
+            let split = here.iter().any(|&(_, color)| seen_by(!color));
+            if both_here || both_seen || split {
+                out.push((t, d));
+            }
+        }
+    }
+    out
+}
+
+/// Unique rectangles, types 1 to 6: four cells on two rows, two columns and
+/// two boxes that could all end up as {a, b} would let the two digits swap,
+/// giving a second solution. Whatever avoids that pattern must hold. Only
+/// sound for puzzles with one solution. Applies one rectangle at a time.
+pub struct UniqueRectangle;
+
+impl Strategy for UniqueRectangle {
+    fn name(&self) -> &'static str {
+        "Unique Rectangle"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Hard
+    }
+
+    fn rating(&self) -> f64 {
+        4.5
+    }
+
+    fn assumes_unique(&self) -> bool {
+        true
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        self.find(sudoku).is_some()
+    }
+
+    /// Highlights the rectangle's corners.
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        let rect = find_rectangle(sudoku)?;
+        eliminate_all(sudoku, rect.ruled_out).then(|| Finding {
+            highlights: rect.corners.to_vec(),
+            ..Finding::default()
+        })
+    }
+}
+
+/// A productive rectangle from [`find_rectangle`].
+struct Rectangle {
+    corners: [Pos; 4],
+    ruled_out: Vec<(Pos, u8)>,
+}
+
+/// The first rectangle that rules something out.
+fn find_rectangle(sudoku: &Sudoku) -> Option<Rectangle> {
+    let n = sudoku.size();
+
+    for r1 in 0..n {
+        for r2 in r1 + 1..n {
+            for c1 in 0..n {
+                for c2 in c1 + 1..n {
+                    let corners = [(r1, c1), (r1, c2), (r2, c2), (r2, c1)];
+                    // Exactly two boxes, each holding one side.
+                    let b = corners.map(|(r, c)| sudoku.box_index(r, c));
+                    if !(b[0] == b[1] && b[2] == b[3] || b[0] == b[3] && b[1] == b[2])
+                        || b[0] == b[2]
+                    {
+                        continue;
+                    }
+                    // Swapping the digits could break a variant house, a
+                    // cage sum, a move rule, a line, a clue or a registered
+                    // constraint, so the pattern is only deadly clear of them.
+                    if !sudoku.constraints().is_empty()
+                        || corners.iter().any(|&(r, c)| {
+                            !sudoku.extra_houses(r, c).is_empty()
+                                || sudoku.cage_of(r, c).is_some()
+                                || sudoku.move_peers(r, c).next().is_some()
+                                || sudoku.on_line(r, c)
+                                || sudoku.on_clue(r, c)
+                        })
+                    {
+                        continue;
+                    }
+                    let masks = corners.map(|(r, c)| sudoku.candidate_mask(r, c));
+                    let common = masks.iter().fold(sudoku.full_mask(), |m, &x| m & x);
+                    let pair: Vec<u8> = digits(common).collect();
+                    for (i, &a) in pair.iter().enumerate() {
+                        for &b in &pair[i + 1..] {
+                            let ruled_out = rectangle_eliminations(sudoku, corners, masks, a, b);
+                            if !ruled_out.is_empty() {
+                                return Some(Rectangle { corners, ruled_out });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// What the rectangle on `a` and `b` rules out. `corners` go around the
+/// rectangle, so neighbours in the array share a row or a column.
+fn rectangle_eliminations(
+    sudoku: &Sudoku,
+    corners: [Pos; 4],
+    masks: [Mask; 4],
+    a: u8,
+    b: u8,
+) -> Vec<(Pos, u8)> {
+    let ab = bit(a) | bit(b);
+    let floors: Vec<usize> = (0..4).filter(|&i| masks[i] == ab).collect();
+    let roofs: Vec<usize> = (0..4).filter(|&i| masks[i] != ab).collect();
+    let cells = empty_cells(sudoku);
+    let mut out = Vec::new();
+
+    // Type 1: only one corner has extras; it can't be a or b.
+    if let [roof] = roofs[..] {
+        return digits(ab).map(|d| (corners[roof], d)).collect();
+    }
+    // With no extras the pattern is already deadly: the puzzle has more
+    // than one solution, and nothing follows.
+    if floors.is_empty() || roofs.is_empty() {
+        return out;
+    }
+
+    // Types 2 and 5: every extra is the same digit x, so one roof holds x.
+    let extra = masks[roofs[0]] & !ab;
+    if extra.count_ones() == 1 && roofs.iter().all(|&i| masks[i] & !ab == extra) {
+        for &t in &cells {
+            if !corners.contains(&t) && roofs.iter().all(|&i| sees(sudoku, t, corners[i])) {
+                out.extend(digits(sudoku.candidate_mask(t.0, t.1) & extra).map(|d| (t, d)));
+            }
+        }
+        if !out.is_empty() {
+            return out;
+        }
+    }
+    if floors.len() != 2 {
+        return out;
+    }
+    let (p, q) = (corners[roofs[0]], corners[roofs[1]]);
+
+    if roofs[1] - roofs[0] == 2 {
+        // Type 6: diagonal roofs, and u locked to the rectangle on both rows
+        // (or both columns). A roof holding u would force the other to, with
+        // both floors left as the other digit.
+        let (rows, cols) = ([corners[0].0, corners[2].0], [corners[0].1, corners[2].1]);
+        for u in [a, b] {
+            let only = |line: House, ends: [usize; 2], row: bool| {
+                sudoku.house_cells(line).into_iter().all(|(r, c)| {
+                    let k = if row { c } else { r };
+                    sudoku.candidate_mask(r, c) & bit(u) == 0 || ends.contains(&k)
+                })
+            };
+            let by_rows = rows.iter().all(|&r| only(House::Row(r), cols, true));
+            let by_cols = cols.iter().all(|&c| only(House::Col(c), rows, false));
+            if by_rows || by_cols {
+                out.extend([(p, u), (q, u)]);
+            }
+        }
+        return out;
+    }
+
+    // Adjacent roofs share a line, and maybe a box.
+    let mut shared = vec![if p.0 == q.0 {
+        House::Row(p.0)
+    } else {
+        House::Col(p.1)
+    }];
+    if sudoku.box_index(p.0, p.1) == sudoku.box_index(q.0, q.1) {
+        shared.push(House::Box(sudoku.box_index(p.0, p.1)));
+    }
+
+    for &house in &shared {
+        let line = sudoku.house_cells(house);
+        // Type 4: u only fits the two roofs here, so one of them is u and
+        // neither can be the other digit.
+        for (u, v) in [(a, b), (b, a)] {
+            let locked = line.iter().all(|&(r, c)| {
+                (r, c) == p || (r, c) == q || sudoku.candidate_mask(r, c) & bit(u) == 0
+            });
+            if locked {
+                out.extend([(p, v), (q, v)]);
+            }
+        }
+        if !out.is_empty() {
+            return out;
+        }
+
+        // Type 3: the roofs' extras act as one cell in a naked subset.
+        let extras = (masks[roofs[0]] | masks[roofs[1]]) & !ab;
+        let others: Vec<Pos> = line
+            .iter()
+            .copied()
+            .filter(|&t| t != p && t != q && sudoku.candidate_mask(t.0, t.1) != 0)
+            .collect();
+        for k in 1..=3.min(others.len().saturating_sub(1)) {
+            for_each_subset(others.len(), k, &mut |picked| {
+                let union = picked.iter().fold(extras, |m, &i| {
+                    m | sudoku.candidate_mask(others[i].0, others[i].1)
+                });
+                if union.count_ones() as usize != k + 1 {
+                    return;
+                }
+                for (j, &t) in others.iter().enumerate() {
+                    if !picked.contains(&j) {
+                        out.extend(digits(sudoku.candidate_mask(t.0, t.1) & union).map(|d| (t, d)));
+                    }
+                }
+            });
+            if !out.is_empty() {
+                out.sort_unstable();
+                out.dedup();
+                return out;
+            }
+        }
+    }
+
+    out
+}
+
+/// ALS-XZ: two almost locked sets (N cells in a house with N + 1
+/// candidates) joined by a restricted common digit x, one whose copies all
+/// see each other across the sets. At most one set holds x, so the other is
+/// locked, and a second common digit z lands in one of them: cells seeing
+/// every z in both can't be z. Applies one pair at a time.
+pub struct AlsXz;
+
+/// Largest almost locked set searched for.
+const MAX_ALS: usize = 4;
+
+impl Strategy for AlsXz {
+    fn name(&self) -> &'static str {
+        "ALS-XZ"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Expert
+    }
+
+    fn rating(&self) -> f64 {
+        7.5
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        self.find(sudoku).is_some()
+    }
+
+    /// Highlights the cells of both sets.
+    fn find(&self, sudoku: &mut Sudoku) -> Option<Finding> {
+        let pair = find_als_xz(sudoku)?;
+        eliminate_all(sudoku, pair.ruled_out).then(|| Finding {
+            highlights: pair.cells,
+            ..Finding::default()
+        })
+    }
+}
+
+/// A productive pair of sets from [`find_als_xz`].
+struct AlsPair {
+    cells: Vec<Pos>,
+    ruled_out: Vec<(Pos, u8)>,
+}
+
+/// Every almost locked set up to [`MAX_ALS`] cells, as sorted cells and
+/// their candidates.
+fn almost_locked_sets(sudoku: &Sudoku) -> Vec<(Vec<Pos>, Mask)> {
+    let mut sets = Vec::new();
+    for house in sudoku.houses() {
+        let empty: Vec<Pos> = sudoku
+            .house_cells(house)
+            .into_iter()
+            .filter(|&(r, c)| sudoku.candidate_mask(r, c) != 0)
+            .collect();
+        for k in 1..=MAX_ALS.min(empty.len()) {
+            for_each_subset(empty.len(), k, &mut |picked| {
+                let cells: Vec<Pos> = picked.iter().map(|&i| empty[i]).collect();
+                let mask = cells
+                    .iter()
+                    .fold(0, |m, &(r, c)| m | sudoku.candidate_mask(r, c));
+                if mask.count_ones() as usize == k + 1 {
+                    sets.push((cells, mask));
+                }
+            });
+        }
+    }
+    // Cells in a row-major house order come out sorted; a set in a line
+    // within one box is found twice.
+    sets.sort_unstable();
+    sets.dedup();
+    sets
+}
+
+/// The first pair of sets that rules something out.
+fn find_als_xz(sudoku: &Sudoku) -> Option<AlsPair> {
+    let sets = almost_locked_sets(sudoku);
+    let cells = empty_cells(sudoku);
+    let holding = |set: &[Pos], d: u8| -> Vec<Pos> {
+        set.iter()
+            .copied()
+            .filter(|&(r, c)| sudoku.candidate_mask(r, c) & bit(d) != 0)
+            .collect()
+    };
+
+    for (i, (a, am)) in sets.iter().enumerate() {
+        for (b, bm) in &sets[i + 1..] {
+            let common = am & bm;
+            if common.count_ones() < 2 || a.iter().any(|p| b.contains(p)) {
+                continue;
+            }
+            for x in digits(common) {
+                let (ax, bx) = (holding(a, x), holding(b, x));
+                if !ax.iter().all(|&p| bx.iter().all(|&q| sees(sudoku, p, q))) {
+                    continue;
+                }
+                let mut ruled_out = Vec::new();
+                for z in digits(common & !bit(x)) {
+                    let zs: Vec<Pos> = holding(a, z).into_iter().chain(holding(b, z)).collect();
+                    for &t in &cells {
+                        if sudoku.candidate_mask(t.0, t.1) & bit(z) != 0
+                            && !a.contains(&t)
+                            && !b.contains(&t)
+                            && zs.iter().all(|&p| sees(sudoku, t, p))
+                        {
+                            ruled_out.push((t, z));
+                        }
+                    }
+                }
+                if !ruled_out.is_empty() {
+                    return Some(AlsPair {
+                        cells: a.iter().chain(b).copied().collect(),
+                        ruled_out,
+                    });
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Pattern overlay: enumerates every way a digit could still fill the grid,
+/// one per row, column and box, and removes candidates no such template
+/// uses. Digits with more than `max_templates` templates are skipped.
+pub struct PatternOverlay {
+    pub max_templates: usize,
+}
+
+impl Default for PatternOverlay {
+    fn default() -> Self {
+        Self {
+            max_templates: 100_000,
+        }
+    }
+}
+
+impl Strategy for PatternOverlay {
+    fn name(&self) -> &'static str {
+        "Pattern Overlay"
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Expert
+    }
+
+    fn rating(&self) -> f64 {
+        8.0
+    }
+
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let mut progress = false;
+        let n = sudoku.size();
+
+        for d in 1..=n as u8 {
+            // Columns `d` may take in each row: its placement, or its spots.
+            let rows: Vec<Mask> = (0..n)
+                .map(
+                    |r| match (0..n).find(|&c| sudoku.cell(r, c).value() == Some(d)) {
+                        Some(c) => 1 << c,
+                        None => (0..n)
+                            .filter(|&c| sudoku.candidate_mask(r, c) & bit(d) != 0)
+                            .fold(0, |m, c| m | 1 << c),
+                    },
+                )
+                .collect();
+            let Some(used) = overlay(sudoku, &rows, self.max_templates) else {
+                continue;
+            };
+            for r in 0..n {
+                for c in (0..n).filter(|&c| rows[r] & !used[r] & 1 << c != 0) {
+                    progress |= sudoku.eliminate(r, c, bit(d));
+                }
+            }
+        }
+
+        progress
+    }
+}
+
+/// Union over all templates through `rows`, per row; `None` past `limit`.
+fn overlay(sudoku: &Sudoku, rows: &[Mask], limit: usize) -> Option<Vec<Mask>> {
+    struct Search<'a> {
+        sudoku: &'a Sudoku,
+        rows: &'a [Mask],
+        picked: Vec<usize>,
+        used: Vec<Mask>,
+        found: usize,
+        limit: usize,
+    }
+
+    fn go(s: &mut Search, r: usize, cols: Mask, boxes: Mask) -> bool {
+        if r == s.rows.len() {
+            s.found += 1;
+            for (row, &c) in s.picked.iter().enumerate() {
+                s.used[row] |= 1 << c;
+            }
+            return s.found <= s.limit;
+        }
+        for c in (0..s.rows.len()).filter(|&c| s.rows[r] & !cols & 1 << c != 0) {
+            let b = s.sudoku.box_index(r, c);
+            if boxes & 1 << b != 0 {
+                continue;
+            }
+            s.picked.push(c);
+            let ok = go(s, r + 1, cols | 1 << c, boxes | 1 << b);
+            s.picked.pop();
+            if !ok {
+                return false;
+            }
+        }
+        true
+    }
+
+    let mut search = Search {
+        sudoku,
+        rows,
+        picked: Vec::with_capacity(rows.len()),
+        used: vec![0; rows.len()],
+        found: 0,
+        limit,
+    };
+    go(&mut search, 0, 0, 0).then_some(search.used)
+}
+
+/// Two strong links on one digit, (p1, q1) and (p2, q2), where q1 sees q2:
+/// at most one of the q ends holds the digit, so p1 or p2 does and cells
+/// seeing both lose it. `accept` picks which pairs of link houses count.
+fn linked_pairs(sudoku: &mut Sudoku, accept: impl Fn(House, House) -> bool) -> bool {
+    let mut progress = false;
+    let cells = empty_cells(sudoku);
+
+    for d in 1..=sudoku.size() as u8 {
+        let links = strong_links(sudoku, d);
+        for (i, &(h1, a1, b1)) in links.iter().enumerate() {
+            for &(h2, a2, b2) in &links[i + 1..] {
+                if !accept(h1, h2) {
+                    continue;
+                }
+                for (p1, q1) in [(a1, b1), (b1, a1)] {
+                    for (p2, q2) in [(a2, b2), (b2, a2)] {
+                        let ends = [p1, q1, p2, q2];
+                        let distinct = (1..4).all(|k| !ends[..k].contains(&ends[k]));
+                        if !distinct || !sees(sudoku, q1, q2) {
+                            continue;
+                        }
+                        for &t in &cells {
+                            if sees(sudoku, t, p1) && sees(sudoku, t, p2) {
+                                progress |= sudoku.eliminate(t.0, t.1, bit(d));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    progress
+}
+
+/// Houses where `d` has exactly two spots, with the pair of cells. Exactly
+/// one of the two holds `d`.
+pub(crate) fn strong_links(sudoku: &Sudoku, d: u8) -> Vec<(House, Pos, Pos)> {
+    sudoku
+        .houses()
+        .filter_map(|house| {
+            let mut spots = sudoku
+                .house_cells(house)
+                .into_iter()
+                .filter(|&(r, c)| sudoku.candidate_mask(r, c) & bit(d) != 0);
+            match (spots.next(), spots.next(), spots.next()) {
+                (Some(p), Some(q), None) => Some((house, p, q)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Empty cells, in row-major order.
+pub(crate) fn empty_cells(sudoku: &Sudoku) -> Vec<Pos> {
+    let n = sudoku.size();
+    (0..n * n)
+        .map(|i| (i / n, i % n))
+        .filter(|&(r, c)| sudoku.cell(r, c).is_empty())
+        .collect()
+}
+
+/// True if two distinct cells share a house.
+pub(crate) fn sees(sudoku: &Sudoku, a: Pos, b: Pos) -> bool {
+    a != b
+        && (a.0 == b.0
+            || a.1 == b.1
+            || sudoku.box_index(a.0, a.1) == sudoku.box_index(b.0, b.1)
+            || sudoku
+                .extra_houses(a.0, a.1)
+                .into_iter()
+                .any(|h| sudoku.in_house(h, b.0, b.1))
+            || sudoku
+                .cage_of(a.0, a.1)
+                .is_some_and(|i| sudoku.cage_of(b.0, b.1) == Some(i))
+            || sudoku.move_peers(a.0, a.1).any(|p| p == b)
+            || sudoku.line_peers(a.0, a.1).contains(&b))
+}
+
+/// Calls `f` with every ascending choice of `k` indices below `n`.
+fn for_each_subset(n: usize, k: usize, f: &mut dyn FnMut(&[usize])) {
+    fn go(start: usize, n: usize, k: usize, picked: &mut Vec<usize>, f: &mut dyn FnMut(&[usize])) {
+        if picked.len() == k {
+            return f(picked);
+        }
+        for i in start..n {
+            picked.push(i);
+            go(i + 1, n, k, picked, f);
+            picked.pop();
+        }
+    }
+    go(0, n, k, &mut Vec::with_capacity(k), f);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{CandidateSet, Solver};
+
+    /// Runs `strategy` on `placed`, a grid partway through solving `puzzle`,
+    /// with the candidates of its empty cells in `cands`, one group each in
+    /// row-major order. Checks that the grid agrees with the puzzle's
+    /// solution, and that the strategy rules out exactly `expected`, none of
+    /// it the solution's digit. Returns what it found.
+    pub(crate) fn check(
+        strategy: &dyn Strategy,
+        puzzle: &str,
+        placed: &str,
+        cands: &str,
+        expected: &[(usize, usize, u8)],
+    ) -> Finding {
+        let solution = Solver::new()
+            .solve(Sudoku::from_string(puzzle, 9).unwrap())
+            .unwrap();
+        let sudoku = Sudoku::from_string(placed, 9).unwrap();
+        let mut groups = cands.split_whitespace();
+        let sets = (0..81)
+            .map(|i| match sudoku.cell(i / 9, i % 9).value() {
+                Some(v) => CandidateSet::from_iter([v]),
+                None => groups.next().unwrap().bytes().map(|b| b - b'0').collect(),
+            })
+            .collect();
+        assert_eq!(groups.next(), None);
+        let sudoku = sudoku.with_pencilmarks(sets).unwrap();
+        for (r, c) in empty_cells(&sudoku) {
+            let v = solution.cell(r, c).value().unwrap();
+            assert!(sudoku.candidates(r, c).contains(v), "r{}c{}", r + 1, c + 1);
+        }
+
+        let mut after = sudoku.clone();
+        let finding = strategy.find(&mut after).expect("no deduction");
+        let mut ruled_out = Vec::new();
+        for (r, c) in empty_cells(&sudoku) {
+            let gone = sudoku.candidate_mask(r, c) & !after.candidate_mask(r, c);
+            for d in digits(gone) {
+                assert_ne!(solution.cell(r, c).value(), Some(d), "r{}c{}", r + 1, c + 1);
+                ruled_out.push((r, c, d));
+            }
+        }
+        assert_eq!(ruled_out, expected);
+        finding
+    }
+
+    /// A cluster colored across cells and digits clears 7 from r1c4.
+    #[test]
+    fn medusa() {
+        let finding = check(
+            &Medusa,
+            "...............4.1.4.....69.5..7.9.62..4.8..7..73.5...8.............7.3..915...7.",
+            ".8.....23.3....481.4.83..69358.7.946219468357467395812873...195524917638691583274",
+            "
+        179 56 167 45 469 57
+        79 256 267 25 69
+        17 25 12 57
+        12 12
+
+
+        26 24 46
+
+
+    ",
+            &[(0, 3, 7)],
+        );
+        assert_eq!(
+            finding.highlights,
+            [
+                (0, 0),
+                (0, 3),
+                (0, 6),
+                (1, 2),
+                (2, 0),
+                (2, 2),
+                (2, 5),
+                (2, 6),
+                (3, 3),
+                (3, 5)
+            ]
+        );
+    }
+
+    /// Type 1: r9c4 is the only corner with extras, so it is neither 1 nor 5.
+    #[test]
+    fn unique_rectangle_type_1() {
+        let finding = check(
+            &UniqueRectangle,
+            "....3....1....5.....9...4.......17.5.7.3...9...49.8..3.28...1..7.....2.86.....93.",
+            "4...3.5.91..4953..359...4..89362174527635489151497862392876315473..4926864...2937",
+            "
+        68 27 128 67 178
+        68 27 78 26
+        128 18 67 178 26
+
+
+
+
+        15 15
+        15 158 18
+    ",
+            &[(8, 3, 1), (8, 3, 5)],
+        );
+        assert_eq!(finding.highlights, [(7, 2), (7, 3), (8, 3), (8, 2)]);
+    }
+
+    /// Type 2: both roofs add only 4, so r3c4, seeing both, loses it.
+    #[test]
+    fn unique_rectangle_type_2() {
+        let finding = check(
+            &UniqueRectangle,
+            ".....7..........3763...........1.429..1...8..964.8........2..4.4.639..8...25..9.1",
+            "....376......6..37637.5....873615429521943876964782...719826.4.456391782382574961",
+            "
+        12 49 58 124 159 48
+        12 49 58 124 89 125
+        124 89 12 19 48
+
+
+        135 15 35
+        35 35
+
+
+    ",
+            &[(2, 3, 4)],
+        );
+        assert_eq!(finding.highlights, [(0, 0), (0, 3), (1, 3), (1, 0)]);
+    }
+
+    /// Type 3: the roofs' extras 3 and 4 make a naked triple with r3c1 and r8c1.
+    #[test]
+    fn unique_rectangle_type_3() {
+        let finding = check(
+            &UniqueRectangle,
+            "........3.....178...7.6...5...........1...594.8.2......1..4..3...597..216...1...7",
+            "1..7.5.63...4.178..27.69145...1.4.7.2716..594.8.2.731671.54..3...597..216...1..57",
+            "
+        49 489 28 29
+        359 56 36 23 29
+        38 38
+        359 56 36 59 28 28
+        38 38
+        459 49 59
+        289 268 689 89
+        48 34 36 468
+        349 249 38 238 489
+    ",
+            &[(1, 0, 3)],
+        );
+        assert_eq!(finding.highlights, [(3, 0), (3, 4), (5, 4), (5, 0)]);
+    }
+
+    /// Type 4: 6 only fits the roofs in row 2, so neither can be 3.
+    #[test]
+    fn unique_rectangle_type_4() {
+        let finding = check(
+            &UniqueRectangle,
+            "........6..2....8......43......7...57..6.2..12.1.8...4...5......9.....7.576.18.9.",
+            "......246..2...18.....2435.....7.825785642931231985764.2.5976181984..572576218493",
+            "
+        89 15 379 178 35 19
+        34 45 37 356 369 79
+        68 16 79 18 79
+        69 46 49 13 13
+
+
+        34 34
+        36 36
+
+    ",
+            &[(1, 4, 3), (1, 5, 3)],
+        );
+        assert_eq!(finding.highlights, [(1, 4), (1, 5), (7, 5), (7, 4)]);
+    }
+
+    /// Type 5: three roofs add only 5, so r2c4, seeing them all, loses it.
+    #[test]
+    fn unique_rectangle_type_5() {
+        let finding = check(
+            &UniqueRectangle,
+            "...............6..7.14....99...6...83.5..8....4..7...11....695...4..52.......3.14",
+            "...6.714......167.7614.2389912.64738375.184966483795211278469538.4.95267596723814",
+            "
+        2 38 39 358 25
+        24 358 39 59 358 25
+        5
+        5
+        2
+
+
+        3 1
+
+    ",
+            &[(1, 3, 5)],
+        );
+        assert_eq!(finding.highlights, [(0, 1), (0, 4), (1, 4), (1, 1)]);
+    }
+
+    /// Type 6: 8 fits rows 1 and 5 only in the rectangle, so the diagonal roofs can't be 8.
+    #[test]
+    fn unique_rectangle_type_6() {
+        let finding = check(
+            &UniqueRectangle,
+            "......6.........54...5.9.....2.4..155.......296.7.....74.89......3...2...86..3...",
+            "357...6..6.9...8544.85693..8.2.46.155.4....6296172548374589213619365.2.8286..35..",
+            "
+        124 18 148 29 19
+        12 123 137 17
+        12 27 17
+        37 39 79
+        37 139 138 18 79
+
+
+        47 47
+        14 17 479 79
+    ",
+            &[(0, 5, 8), (4, 4, 8)],
+        );
+        assert_eq!(finding.highlights, [(0, 4), (0, 5), (4, 5), (4, 4)]);
+    }
+
+    /// Two almost locked sets joined by a restricted common digit clear 5 from r1c5.
+    #[test]
+    fn als_xz() {
+        let finding = check(
+            &AlsXz,
+            ".....1..........9.....2...42...........76.8...91....621....3...58.9...37..354.2..",
+            "..9..1.2..2....19..1..29.74276198.4....762819891...762147283956582916437963547281",
+            "
+        3467 35 3468 357 56 358
+        3467 458 3468 357 45 358
+        36 58 368 356
+        35 35
+        34 35 45
+        34 35 45
+
+
+
+    ",
+            &[(0, 4, 5)],
+        );
+        assert_eq!(finding.highlights, [(0, 1), (0, 3), (0, 6), (0, 8), (1, 5)]);
+    }
+
+    /// No template for 3 passes through r3c1.
+    #[test]
+    fn pattern_overlay() {
+        check(
+            &PatternOverlay::default(),
+            "............57.41......45.2......1...984..76.7.........453.......7..62...6...2943",
+            "...2........57.41......45.2.....71...9842.7657....5...2453.....937.4625.861752943",
+            "
+        45 1578 349 13689 1389 368 3789 6789
+        36 28 239 389 689
+        136 178 39 1689 13689 3789
+        45 25 2346 689 3689 2389 489
+        13 13
+        12 246 1689 13689 38 2389 489
+        189 189 68 78 1678
+        18 18
+
+    ",
+            &[(2, 0, 3)],
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Moving a grid around without breaking it: the rotations, reflections,
+//! relabellings and line swaps that map valid grids to valid grids.
+
+use crate::sodo::{Cell, Sudoku};
+
+/// Largest grid [`Sudoku::canonicalize`] searches; past it the row and
+/// column orders run into the billions.
+const MAX_CANONICAL: usize = 9;
+
+/// Error for moving a grid whose rules the move wouldn't carry.
+const NOT_CLASSIC: &str = "Grid has rules beyond rows, columns and boxes";
+
+impl Sudoku {
+    /// The grid turned a quarter clockwise. Rectangular boxes turn with
+    /// it, so a 6x6 grid of 2x3 boxes comes out with 3x2 ones.
+    ///
+    /// Like every move here, it keeps givens as givens and filled cells as
+    /// filled, drops candidates ruled out, and errors for grids with rules
+    /// beyond rows, columns and boxes.
+    ///
+    /// ```
+    /// use sodo::Sudoku;
+    ///
+    /// let grid = Sudoku::from_string("1...............", 4).unwrap();
+    /// let turned = grid.rotate().unwrap();
+    /// assert_eq!(turned.get(0, 3).unwrap().value(), Some(1));
+    /// let back = turned.rotate().unwrap().rotate().unwrap().rotate().unwrap();
+    /// assert_eq!(back.to_string_compact(), grid.to_string_compact());
+    /// ```
+    pub fn rotate(&self) -> Result<Sudoku, String> {
+        let m = self.size() - 1;
+        self.remap(self.box_cols(), self.box_rows(), |r, c| (m - c, r), |v| v)
+    }
+
+    /// The grid mirrored across its main diagonal.
+    pub fn transpose(&self) -> Result<Sudoku, String> {
+        self.remap(self.box_cols(), self.box_rows(), |r, c| (c, r), |v| v)
+    }
+
+    /// The grid mirrored top to bottom.
+    pub fn flip_rows(&self) -> Result<Sudoku, String> {
+        let m = self.size() - 1;
+        self.remap(self.box_rows(), self.box_cols(), |r, c| (m - r, c), |v| v)
+    }
+
+    /// The grid mirrored left to right.
+    pub fn flip_cols(&self) -> Result<Sudoku, String> {
+        let m = self.size() - 1;
+        self.remap(self.box_rows(), self.box_cols(), |r, c| (r, m - c), |v| v)
+    }
+
+    /// The grid with every digit `v` replaced by `labels[v - 1]`. The
+    /// labels must be each digit once.
+    ///
+    /// ```
+    /// use sodo::Sudoku;
+    ///
+    /// let grid = Sudoku::from_string("12..............", 4).unwrap();
+    /// let swapped = grid.relabel(&[2, 1, 3, 4]).unwrap();
+    /// assert_eq!(swapped.to_string_compact(), "21..............");
+    /// assert!(grid.relabel(&[1, 1, 3, 4]).is_err());
+    /// ```
+    pub fn relabel(&self, labels: &[u8]) -> Result<Sudoku, String> {
+        let n = self.size();
+        let mut sorted = labels.to_vec();
+        sorted.sort_unstable();
+        if !sorted.iter().copied().eq(1..=n as u8) {
+            return Err(format!("Labels must be the digits 1 to {n}, each once"));
+        }
+        self.remap(
+            self.box_rows(),
+            self.box_cols(),
+            |r, c| (r, c),
+            |v| labels[v as usize - 1],
+        )
+    }
+
+    /// The grid with rows `a` and `b` swapped; they must share a band.
+    ///
+    /// ```
+    /// use sodo::Sudoku;
+    ///
+    /// let grid = Sudoku::from_string("12..............", 4).unwrap();
+    /// let swapped = grid.swap_rows(0, 1).unwrap();
+    /// assert_eq!(swapped.get(1, 1).unwrap().value(), Some(2));
+    /// assert!(grid.swap_rows(0, 2).is_err());
+    /// ```
+    pub fn swap_rows(&self, a: usize, b: usize) -> Result<Sudoku, String> {
+        let h = self.box_rows();
+        if a >= self.size() || b >= self.size() || a / h != b / h {
+            return Err(format!("Rows {a} and {b} are not in one band"));
+        }
+        let swap = |r| swapped(r, a, b);
+        self.remap(self.box_rows(), self.box_cols(), |r, c| (swap(r), c), |v| v)
+    }
+
+    /// The grid with columns `a` and `b` swapped; they must share a stack.
+    pub fn swap_cols(&self, a: usize, b: usize) -> Result<Sudoku, String> {
+        let w = self.box_cols();
+        if a >= self.size() || b >= self.size() || a / w != b / w {
+            return Err(format!("Columns {a} and {b} are not in one stack"));
+        }
+        let swap = |c| swapped(c, a, b);
+        self.remap(self.box_rows(), self.box_cols(), |r, c| (r, swap(c)), |v| v)
+    }
+
+    /// The grid with its bands, the rows of boxes, reordered: band `i` of
+    /// the result is band `order[i]` of this grid.
+    ///
+    /// ```
+    /// use sodo::Sudoku;
+    ///
+    /// let grid = Sudoku::from_string("1...............", 4).unwrap();
+    /// let moved = grid.permute_bands(&[1, 0]).unwrap();
+    /// assert_eq!(moved.get(2, 0).unwrap().value(), Some(1));
+    /// ```
+    pub fn permute_bands(&self, order: &[usize]) -> Result<Sudoku, String> {
+        let h = self.box_rows();
+        check_order(order, self.size() / h, "bands")?;
+        self.remap(
+            self.box_rows(),
+            self.box_cols(),
+            |r, c| (order[r / h] * h + r % h, c),
+            |v| v,
+        )
+    }
+
+    /// The grid with its stacks, the columns of boxes, reordered: stack
+    /// `i` of the result is stack `order[i]` of this grid.
+    pub fn permute_stacks(&self, order: &[usize]) -> Result<Sudoku, String> {
+        let w = self.box_cols();
+        check_order(order, self.size() / w, "stacks")?;
+        self.remap(
+            self.box_rows(),
+            self.box_cols(),
+            |r, c| (r, order[c / w] * w + c % w),
+            |v| v,
+        )
+    }
+
+    /// A grid with `box_rows` by `box_cols` boxes whose cell (r, c) is this
+    /// grid's cell `from(r, c)`, its digit passed through `digit`.
+    fn remap(
+        &self,
+        box_rows: usize,
+        box_cols: usize,
+        from: impl Fn(usize, usize) -> (usize, usize),
+        digit: impl Fn(u8) -> u8,
+    ) -> Result<Sudoku, String> {
+        if !self.config().is_empty() || !self.constraints().is_empty() {
+            return Err(NOT_CLASSIC.into());
+        }
+        let n = self.size();
+        let mut grid = Sudoku::with_boxes(box_rows, box_cols);
+        for (r, c) in (0..n * n).map(|i| (i / n, i % n)) {
+            let (fr, fc) = from(r, c);
+            let cell = match self.cell(fr, fc) {
+                Cell::Empty => Cell::Empty,
+                Cell::Given(v) => Cell::Given(digit(v)),
+                Cell::Filled(v) => Cell::Filled(digit(v)),
+            };
+            grid.put(r, c, cell);
+        }
+        Ok(grid)
+    }
+
+    /// The grid's minimum-lexicographic form under the moves that keep
+    /// every grid valid: relabelling digits, reordering bands and the rows
+    /// within each, the same for stacks and columns, and transposing when
+    /// boxes are square. Empty cells read as 0, so the form leads with
+    /// them; digits take 1, 2, 3, ... in order of first appearance. Givens
+    /// stay givens. Errors past 9x9 and for grids with rules beyond rows,
+    /// columns and boxes, which the moves would not carry.
+    ///
+    /// ```
+    /// use sodo::Sudoku;
+    ///
+    /// let puzzle = Sudoku::from_string(
+    ///     "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ///     9,
+    /// )
+    /// .unwrap();
+    /// let canonical = puzzle.canonicalize().unwrap();
+    /// assert_eq!(canonical.empty_count(), puzzle.empty_count());
+    /// let again = canonical.canonicalize().unwrap();
+    /// assert_eq!(again.to_string_compact(), canonical.to_string_compact());
+    /// ```
+    pub fn canonicalize(&self) -> Result<Sudoku, String> {
+        let n = self.size();
+        if n > MAX_CANONICAL {
+            return Err(format!(
+                "Canonical form is only searched up to {MAX_CANONICAL}x{MAX_CANONICAL}"
+            ));
+        }
+        if !self.config().is_empty() || !self.constraints().is_empty() {
+            return Err(NOT_CLASSIC.into());
+        }
+        let (box_rows, box_cols) = (self.box_rows(), self.box_cols());
+        let values: Vec<u8> = (0..n * n)
+            .map(|i| self.cell(i / n, i % n).value().unwrap_or(0))
+            .collect();
+        let row_orders = line_orders(n / box_rows, box_rows);
+        let col_orders = line_orders(n / box_cols, box_cols);
+        let flips: &[bool] = if box_rows == box_cols {
+            &[false, true]
+        } else {
+            &[false]
+        };
+
+        let mut best = vec![u8::MAX; n * n];
+        let mut best_map = (false, 0, 0);
+        let mut grid = vec![0; n * n];
+        for &flip in flips {
+            for (ri, rows) in row_orders.iter().enumerate() {
+                for (ci, cols) in col_orders.iter().enumerate() {
+                    let mut labels = [0u8; MAX_CANONICAL + 1];
+                    let mut next = 1;
+                    let mut smaller = false;
+                    for i in 0..n * n {
+                        let (r, c) = (rows[i / n], cols[i % n]);
+                        let v = if flip {
+                            values[c * n + r]
+                        } else {
+                            values[r * n + c]
+                        };
+                        let label = match v {
+                            0 => 0,
+                            v if labels[v as usize] == 0 => {
+                                labels[v as usize] = next;
+                                next += 1;
+                                next - 1
+                            }
+                            v => labels[v as usize],
+                        };
+                        if !smaller && label != best[i] {
+                            if label > best[i] {
+                                break;
+                            }
+                            smaller = true;
+                        }
+                        grid[i] = label;
+                    }
+                    if smaller {
+                        std::mem::swap(&mut best, &mut grid);
+                        best_map = (flip, ri, ci);
+                    }
+                }
+            }
+        }
+
+        let (flip, ri, ci) = best_map;
+        let (rows, cols) = (&row_orders[ri], &col_orders[ci]);
+        let mut labels = vec![0; n + 1];
+        for (i, &label) in best.iter().enumerate() {
+            let (r, c) = (rows[i / n], cols[i % n]);
+            let v = if flip {
+                values[c * n + r]
+            } else {
+                values[r * n + c]
+            };
+            labels[v as usize] = label;
+        }
+        let from = |r: usize, c: usize| {
+            let (r, c) = (rows[r], cols[c]);
+            if flip { (c, r) } else { (r, c) }
+        };
+        self.remap(box_rows, box_cols, from, |v| labels[v as usize])
+    }
+
+    /// True if some relabelling, line reordering or transposition turns one
+    /// grid into the other, as their [`canonicalize`](Self::canonicalize)d
+    /// forms agree. False if either has no canonical form.
+    ///
+    /// ```
+    /// use sodo::Sudoku;
+    ///
+    /// let a = Sudoku::from_string("12..............", 4).unwrap();
+    /// let b = Sudoku::from_string("..............21", 4).unwrap();
+    /// let c = Sudoku::from_string("1...2...........", 4).unwrap();
+    /// assert!(a.is_isomorphic(&b));
+    /// assert!(a.is_isomorphic(&c));
+    /// let d = Sudoku::from_string("1....1..........", 4).unwrap();
+    /// assert!(!a.is_isomorphic(&d));
+    /// ```
+    pub fn is_isomorphic(&self, other: &Sudoku) -> bool {
+        if self.size() != other.size() {
+            return false;
+        }
+        match (self.canonicalize(), other.canonicalize()) {
+            (Ok(a), Ok(b)) => a.to_string_compact() == b.to_string_compact(),
+            _ => false,
+        }
+    }
+}
+
+/// `line` with `a` and `b` swapped.
+fn swapped(line: usize, a: usize, b: usize) -> usize {
+    match line {
+        l if l == a => b,
+        l if l == b => a,
+        l => l,
+    }
+}
+
+/// Checks `order` holds each of `0..count` once.
+fn check_order(order: &[usize], count: usize, what: &str) -> Result<(), String> {
+    let mut sorted = order.to_vec();
+    sorted.sort_unstable();
+    if sorted.iter().copied().eq(0..count) {
+        Ok(())
+    } else {
+        Err(format!("Order must hold each of the {count} {what} once"))
+    }
+}
+
+/// Every order of `bands * width` lines that keeps each band's lines
+/// together: the bands in any order, then the lines within each.
+fn line_orders(bands: usize, width: usize) -> Vec<Vec<usize>> {
+    let within = permutations(width);
+    let mut orders = Vec::new();
+    for band_order in permutations(bands) {
+        let mut partial = vec![Vec::with_capacity(bands * width)];
+        for &band in &band_order {
+            partial = partial
+                .into_iter()
+                .flat_map(|prefix| {
+                    within.iter().map(move |perm| {
+                        let mut order = prefix.clone();
+                        order.extend(perm.iter().map(|&i| band * width + i));
+                        order
+                    })
+                })
+                .collect();
+        }
+        orders.extend(partial);
+    }
+    orders
+}
+
+/// Every order of `0..n`, lexicographically.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+    let mut all = Vec::new();
+    for first in 0..n {
+        for rest in permutations(n - 1) {
+            let mut perm = vec![first];
+            perm.extend(rest.into_iter().map(|i| if i >= first { i + 1 } else { i }));
+            all.push(perm);
+        }
+    }
+    all
+}
@@ -0,0 +1,388 @@
+//! Itemized validity checks, for editors that must say what is wrong.
+
+use crate::line::LineKind;
+use crate::sodo::{Cell, Conflict, House, Mark, Sudoku, Variant, bit, parse_char, value_char};
+use crate::solver::Solver;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One thing wrong with a puzzle. Positions are zero-based (row, col).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Problem {
+    /// No grid has this size, see [`Sudoku::box_shape`].
+    Size { size: usize },
+    /// The input string has the wrong number of cells.
+    Length { expected: usize, got: usize },
+    /// A character that isn't a digit allowed at this grid size.
+    OutOfRange { row: usize, col: usize, ch: char },
+    /// A digit repeated within a house.
+    Duplicate(Conflict),
+    /// A cage whose digits add up to more than its sum, or to anything else
+    /// once full. `cage` indexes [`Sudoku::cages`].
+    CageSum {
+        cage: usize,
+        total: u32,
+        sum: u32,
+        cells: Vec<(usize, usize)>,
+    },
+    /// A digit repeated a move apart under a rule such as
+    /// [`Variant::AntiKnight`].
+    MoveClash {
+        variant: Variant,
+        value: u8,
+        cells: Vec<(usize, usize)>,
+    },
+    /// A line whose digits break its rule: a thermometer that doesn't rise,
+    /// or an arrow whose shaft doesn't add up to its pill. `line` indexes
+    /// [`Sudoku::lines`].
+    Line {
+        line: usize,
+        kind: LineKind,
+        cells: Vec<(usize, usize)>,
+    },
+    /// A marked cell holding a digit its mark rules out.
+    Mark {
+        row: usize,
+        col: usize,
+        value: u8,
+        mark: Mark,
+    },
+    /// A cell holding a digit its pencilmarks rule out.
+    Pencilmark { row: usize, col: usize, value: u8 },
+    /// A Little Killer clue whose diagonal adds up to more than its value,
+    /// or to anything else once full. `clue` indexes [`Sudoku::clues`].
+    ClueSum {
+        clue: usize,
+        total: u32,
+        value: u32,
+        cells: Vec<(usize, usize)>,
+    },
+    /// A registered [`Constraint`](crate::Constraint) that doesn't hold,
+    /// with the placed digits it doesn't allow.
+    Constraint {
+        name: String,
+        cells: Vec<(usize, usize)>,
+    },
+    /// An empty cell that no digit can fill.
+    NoCandidates { row: usize, col: usize },
+    /// The rules hold but no solution exists.
+    NoSolution,
+    /// More than one solution exists.
+    MultipleSolutions,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Size { size } => write!(f, "No grid of size {size}"),
+            Self::Length { expected, got } => write!(f, "Expected {expected} cells, got {got}"),
+            Self::OutOfRange { row, col, ch } => {
+                write!(
+                    f,
+                    "Invalid value '{ch}' at row {}, col {}",
+                    row + 1,
+                    col + 1
+                )
+            }
+            Self::Duplicate(c) => {
+                let (kind, i) = match c.house {
+                    House::Row(i) => ("row", i),
+                    House::Col(i) => ("col", i),
+                    House::Box(i) => ("box", i),
+                    House::Diagonal(i) => ("diagonal", i),
+                    House::Window(i) => ("window", i),
+                    House::Group(i) => ("group", i),
+                    House::Cage(i) => ("cage", i),
+                };
+                write!(f, "{} repeated in {kind} {}", value_char(c.value), i + 1)
+            }
+            Self::CageSum {
+                cage, total, sum, ..
+            } => write!(f, "Cage {} adds up to {total}, not {sum}", cage + 1),
+            Self::MoveClash {
+                variant,
+                value,
+                cells,
+            } => {
+                let apart = match variant {
+                    Variant::AntiKnight => "a knight's move",
+                    Variant::AntiKing => "a king's move",
+                    _ => "a move",
+                };
+                let [(r1, c1), (r2, c2)] = cells[..] else {
+                    return write!(f, "{} repeated {apart} apart", value_char(*value));
+                };
+                write!(
+                    f,
+                    "{} repeated {apart} apart, at row {}, col {} and row {}, col {}",
+                    value_char(*value),
+                    r1 + 1,
+                    c1 + 1,
+                    r2 + 1,
+                    c2 + 1
+                )
+            }
+            Self::Line { line, kind, .. } => match kind {
+                LineKind::Thermo => write!(f, "Thermometer {} doesn't rise", line + 1),
+                LineKind::Arrow { .. } => write!(f, "Arrow {} doesn't add up", line + 1),
+            },
+            Self::Mark {
+                row,
+                col,
+                value,
+                mark,
+            } => {
+                let kind = match mark {
+                    Mark::Odd => "odd",
+                    Mark::Even => "even",
+                };
+                write!(
+                    f,
+                    "{} at row {}, col {} isn't {kind}",
+                    value_char(*value),
+                    row + 1,
+                    col + 1
+                )
+            }
+            Self::Pencilmark { row, col, value } => write!(
+                f,
+                "{} at row {}, col {} isn't pencilled in",
+                value_char(*value),
+                row + 1,
+                col + 1
+            ),
+            Self::ClueSum {
+                clue, total, value, ..
+            } => write!(f, "Clue {} adds up to {total}, not {value}", clue + 1),
+            Self::Constraint { name, .. } => write!(f, "{name} doesn't hold"),
+            Self::NoCandidates { row, col } => {
+                write!(f, "No value fits row {}, col {}", row + 1, col + 1)
+            }
+            Self::NoSolution => f.write_str("No solution"),
+            Self::MultipleSolutions => f.write_str("More than one solution"),
+        }
+    }
+}
+
+/// Every [`Problem`] found in a puzzle, in the order checked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValidationReport {
+    pub problems: Vec<Problem>,
+}
+
+impl ValidationReport {
+    /// True when nothing was found.
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Cells involved in any problem, deduplicated, for highlighting.
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        let mut cells: Vec<_> = self
+            .problems
+            .iter()
+            .flat_map(|p| match p {
+                Problem::OutOfRange { row, col, .. }
+                | Problem::Mark { row, col, .. }
+                | Problem::Pencilmark { row, col, .. }
+                | Problem::NoCandidates { row, col } => vec![(*row, *col)],
+                Problem::Duplicate(c) => c.cells.clone(),
+                Problem::CageSum { cells, .. }
+                | Problem::MoveClash { cells, .. }
+                | Problem::Line { cells, .. }
+                | Problem::ClueSum { cells, .. }
+                | Problem::Constraint { cells, .. } => cells.clone(),
+                _ => Vec::new(),
+            })
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_ok() {
+            return writeln!(f, "Valid");
+        }
+        for p in &self.problems {
+            writeln!(f, "{p}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Sudoku {
+    /// Lists repeated digits, broken cage sums, move rules, lines, marks,
+    /// pencilmarks, clues and constraints, and empty cells with no
+    /// candidates. Unlike [`is_valid`](Self::is_valid) this reports every
+    /// violation; it is empty whenever `is_valid` holds and no cell is stuck.
+    ///
+    /// ```
+    /// use sodo::{Problem, Sudoku, Variant};
+    ///
+    /// let mut sudoku = Sudoku::new(9).with_variant(Variant::AntiKnight);
+    /// sudoku.set(0, 2, 1).unwrap();
+    /// sudoku.set(1, 4, 1).unwrap();
+    /// let report = sudoku.validate_detailed();
+    /// assert!(matches!(report.problems[0], Problem::MoveClash { value: 1, .. }));
+    /// assert_eq!(report.cells(), [(0, 2), (1, 4)]);
+    /// ```
+    pub fn validate_detailed(&self) -> ValidationReport {
+        let mut problems: Vec<_> = self
+            .conflicts()
+            .into_iter()
+            .map(Problem::Duplicate)
+            .collect();
+        for (cage, k) in self.cages().iter().enumerate() {
+            if let Some(total) = self.cage_total(cage) {
+                problems.push(Problem::CageSum {
+                    cage,
+                    total,
+                    sum: k.sum,
+                    cells: k.cells.clone(),
+                });
+            }
+        }
+        let n = self.size();
+        for &variant in self.variants() {
+            for i in 0..n * n {
+                let (row, col) = (i / n, i % n);
+                let Some(value) = self.cell(row, col).value() else {
+                    continue;
+                };
+                for &(dr, dc) in variant.moves() {
+                    let Some(r) = row.checked_add_signed(dr).filter(|&r| r < n) else {
+                        continue;
+                    };
+                    let Some(c) = col.checked_add_signed(dc).filter(|&c| c < n) else {
+                        continue;
+                    };
+                    if r * n + c > i && self.cell(r, c).value() == Some(value) {
+                        problems.push(Problem::MoveClash {
+                            variant,
+                            value,
+                            cells: vec![(row, col), (r, c)],
+                        });
+                    }
+                }
+            }
+        }
+        for (line, l) in self.lines().iter().enumerate() {
+            if !self.line_holds(l) {
+                problems.push(Problem::Line {
+                    line,
+                    kind: l.kind,
+                    cells: l.cells.clone(),
+                });
+            }
+        }
+        for ((row, col), mark) in self.marks() {
+            if let Some(value) = self.cell(row, col).value()
+                && mark.mask() & bit(value) == 0
+            {
+                problems.push(Problem::Mark {
+                    row,
+                    col,
+                    value,
+                    mark,
+                });
+            }
+        }
+        for i in 0..n * n {
+            let (row, col) = (i / n, i % n);
+            if let Some(value) = self.cell(row, col).value()
+                && self.pencil_mask(row, col) & bit(value) == 0
+            {
+                problems.push(Problem::Pencilmark { row, col, value });
+            }
+        }
+        for (clue, k) in self.clues().iter().enumerate() {
+            if let Some(total) = self.clue_total(clue) {
+                problems.push(Problem::ClueSum {
+                    clue,
+                    total,
+                    value: k.value,
+                    cells: self.clue_cells(clue).to_vec(),
+                });
+            }
+        }
+        for k in self.constraints() {
+            if !k.is_valid(self) {
+                let cells = (0..n * n)
+                    .map(|i| (i / n, i % n))
+                    .filter(|&(r, c)| {
+                        self.cell(r, c)
+                            .value()
+                            .is_some_and(|v| !k.allows(self, r, c, v))
+                    })
+                    .collect();
+                problems.push(Problem::Constraint {
+                    name: k.name().to_string(),
+                    cells,
+                });
+            }
+        }
+        for row in 0..n {
+            for col in 0..n {
+                if self.cell(row, col).is_empty() && self.candidate_mask(row, col) == 0 {
+                    problems.push(Problem::NoCandidates { row, col });
+                }
+            }
+        }
+        ValidationReport { problems }
+    }
+
+    /// Checks raw input: size, length, then each character, then the grid
+    /// built from the characters that parsed.
+    pub fn validate_string(s: &str, size: usize) -> ValidationReport {
+        if Sudoku::box_shape(size).is_none() {
+            return ValidationReport {
+                problems: vec![Problem::Size { size }],
+            };
+        }
+        let expected = size * size;
+        let got = s.chars().count();
+        if got != expected {
+            return ValidationReport {
+                problems: vec![Problem::Length { expected, got }],
+            };
+        }
+
+        let mut sudoku = Sudoku::new(size);
+        let mut problems = Vec::new();
+        for (i, ch) in s.chars().enumerate() {
+            let (row, col) = (i / size, i % size);
+            match ch {
+                '0' | '.' | ' ' => {}
+                _ => match parse_char(ch, size) {
+                    Some(v) => sudoku.put(row, col, Cell::Given(v)),
+                    None => problems.push(Problem::OutOfRange { row, col, ch }),
+                },
+            }
+        }
+
+        problems.extend(sudoku.validate_detailed().problems);
+        ValidationReport { problems }
+    }
+}
+
+impl Solver {
+    /// [`Sudoku::validate_detailed`], plus a solution count when the grid
+    /// is otherwise clean: reports no solution or more than one.
+    pub fn validate(&self, sudoku: &Sudoku) -> ValidationReport {
+        let mut report = sudoku.validate_detailed();
+        if report.is_ok() {
+            match self.count_solutions(sudoku, Some(2)) {
+                0 => report.problems.push(Problem::NoSolution),
+                1 => {}
+                _ => report.problems.push(Problem::MultipleSolutions),
+            }
+        }
+        report
+    }
+}